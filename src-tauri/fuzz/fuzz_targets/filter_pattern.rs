@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logcat_lib::filter::{self, FilterConfig, LogFilter};
+use logcat_lib::parser::LogParser;
+
+// Hostile search patterns: validation and matching must not panic
+fuzz_target!(|data: &[u8]| {
+    let pattern = String::from_utf8_lossy(data).into_owned();
+    let config = FilterConfig {
+        search_text: pattern,
+        is_regex: true,
+        ..Default::default()
+    };
+    let _ = filter::validate(&config);
+    let filter = LogFilter::new(config);
+    let entry = LogParser::new()
+        .parse_line("12-04 10:30:45.123  1234  1234 I Fuzz: payload")
+        .expect("valid line");
+    let _ = filter.matches(&entry);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logcat_lib::parser::LogParser;
+
+// Arbitrary bytes as adb output: every line goes through the parser and the
+// raw-entry fallback, as in a live stream
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let mut parser = LogParser::new();
+    for line in text.lines() {
+        if let Some(entry) = parser.parse_line(line) {
+            let _ = entry.to_line();
+            let _ = entry.device_time_millis();
+        } else {
+            let _ = parser.unparsed_entry(line, "RAW");
+        }
+    }
+});
@@ -1,15 +1,31 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::process::Stdio;
+use std::path::Path;
+use std::process::{Output, Stdio};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufRead, BufReader};
 use tokio::process::Command;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::time::{interval, Duration};
+use chrono::FixedOffset;
 use log::{debug, error, info};
 use tauri::{AppHandle, Emitter};
 
+use crate::audit::{self, AdbInvocation, AuditLog};
+use crate::cancel::{CancelToken, Operation, Operations};
+use crate::commands;
+use crate::follow::{self, PackageSnapshot};
+use crate::logd::{self, LogdStats};
+use crate::metrics::Metrics;
+use crate::pairing::{self, MdnsService};
+use crate::palette;
 use crate::parser::{LogEntry, LogParser};
+use crate::props::{self, DebugProp, DebugToggle};
+use crate::settings;
+use crate::source::LineReader;
+use crate::streamstats::StreamStats;
+use crate::tasks::TaskSet;
+use crate::thermal::{self, ThermalSnapshot};
 
 /// Represents a connected Android device
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +36,24 @@ pub struct Device {
     pub state: DeviceState,
     #[serde(rename = "isEmulator")]
     pub is_emulator: bool,
+    pub transport: Transport,
+    #[serde(rename = "transportId", skip_serializing_if = "Option::is_none")]
+    pub transport_id: Option<u32>,
+    /// Stable palette index derived from the serial
+    #[serde(rename = "colorIndex")]
+    pub color_index: u8,
+    /// Names of the device groups the device belongs to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+}
+
+/// How adb reaches the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    Usb,
+    Tcpip,
+    Unknown,
 }
 
 /// Device connection state
@@ -50,6 +84,13 @@ impl DeviceState {
 pub enum DeviceEvent {
     Connected { device: Device },
     Disconnected { device_id: String },
+    /// A device is waiting for the USB debugging prompt to be accepted
+    Unauthorized {
+        #[serde(rename = "deviceId")]
+        device_id: String,
+    },
+    /// The USB debugging prompt was accepted
+    Authorized { device: Device },
     ListUpdated { devices: Vec<Device> },
 }
 
@@ -62,38 +103,248 @@ pub struct ProcessInfo {
     pub package_name: Option<String>,
 }
 
+/// Per-device command capabilities derived from the Android version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    /// `ro.build.version.sdk`, 0 when unknown
+    pub sdk_level: u32,
+    /// `ro.build.version.release`, e.g. "7.1.2"
+    pub release: String,
+    /// toybox `ps -A -o PID,NAME` (Android 8.0 / API 26+)
+    pub ps_all: bool,
+}
+
+impl DeviceCapabilities {
+    pub fn from_sdk(sdk_level: u32, release: String) -> Self {
+        // Unknown SDK: assume a modern device rather than degrade everything
+        let modern = |min: u32| sdk_level == 0 || sdk_level >= min;
+        DeviceCapabilities {
+            sdk_level,
+            release,
+            ps_all: modern(26),
+        }
+    }
+
+    /// `adb shell` arguments listing all processes
+    pub fn ps_args(&self) -> &'static [&'static str] {
+        if self.ps_all {
+            &["ps", "-A", "-o", "PID,NAME"]
+        } else {
+            &["ps"]
+        }
+    }
+}
+
+/// Whether adbd can run (or runs) as root on a device
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RootStatus {
+    /// `ro.build.type`: "user", "userdebug" or "eng"
+    pub build_type: String,
+    /// `adb root` is allowed (debuggable, non-production build)
+    pub available: bool,
+    /// adbd currently runs as root
+    pub active: bool,
+    /// Log sources readable only as root, listed while `active`
+    pub privileged_sources: Vec<String>,
+}
+
+/// Upper bound for `adb bugreport`, which is slow on large devices
+const BUGREPORT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Sources that need adbd running as root
+const PRIVILEGED_SOURCES: &[&str] = &["dmesg", "/data/anr", "/data/tombstones"];
+
+/// Result of re-running a recorded adb invocation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdbReplay {
+    pub invocation: AdbInvocation,
+    pub stdout: String,
+    pub stderr: String,
+}
+
 /// ADB manager for device communication
 pub struct AdbManager {
-    adb_path: String,
+    /// Explicit adb path; `None` follows the `adbPath` setting
+    adb_path: Option<String>,
+    /// Detected capabilities keyed by device ID
+    capabilities: RwLock<HashMap<String, DeviceCapabilities>>,
+    /// Recent adb invocations, for `get_adb_history`
+    audit: std::sync::Mutex<AuditLog>,
+    /// Per-device queues for `output`
+    device_locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Long-running operations `cancel_operation` can stop
+    operations: Operations,
 }
 
 impl AdbManager {
     pub fn new() -> Self {
         AdbManager {
-            adb_path: "adb".to_string(),
+            adb_path: None,
+            capabilities: RwLock::new(HashMap::new()),
+            audit: Default::default(),
+            device_locks: Default::default(),
+            operations: Default::default(),
         }
     }
 
     pub fn with_path(path: String) -> Self {
-        AdbManager { adb_path: path }
+        AdbManager {
+            adb_path: Some(path),
+            capabilities: RwLock::new(HashMap::new()),
+            audit: Default::default(),
+            device_locks: Default::default(),
+            operations: Default::default(),
+        }
+    }
+
+    /// Detect (and cache) what the device's shell tools support
+    pub async fn capabilities(&self, device_id: &str) -> DeviceCapabilities {
+        if let Some(caps) = self.capabilities.read().await.get(device_id) {
+            return caps.clone();
+        }
+
+        let output = self
+            .output(&[
+                "-s",
+                device_id,
+                "shell",
+                "getprop ro.build.version.sdk; getprop ro.build.version.release",
+            ])
+            .await;
+
+        let caps = match output {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let mut lines = stdout.lines().map(str::trim);
+                let sdk = lines.next().and_then(|l| l.parse().ok()).unwrap_or(0);
+                let release = lines.next().unwrap_or_default().to_string();
+                DeviceCapabilities::from_sdk(sdk, release)
+            }
+            _ => {
+                // Don't cache a failed probe; the device may still be booting
+                error!("Failed to detect capabilities for {}", device_id);
+                return DeviceCapabilities::from_sdk(0, String::new());
+            }
+        };
+
+        info!("Device {} runs SDK {}", device_id, caps.sdk_level);
+        self.capabilities
+            .write()
+            .await
+            .insert(device_id.to_string(), caps.clone());
+        caps
     }
 
     /// Check if ADB is available
     pub async fn check_adb(&self) -> Result<bool, String> {
-        let output = Command::new(&self.adb_path)
-            .arg("version")
-            .output()
+        let output = self.output(&["version"])
             .await
             .map_err(|e| format!("Failed to run adb: {}", e))?;
 
         Ok(output.status.success())
     }
 
+    /// Path of the adb executable in use
+    pub fn adb_path(&self) -> String {
+        self.adb_path
+            .clone()
+            .unwrap_or_else(|| settings::current().adb_path)
+    }
+
+    /// Run adb to completion, recording the invocation in the audit log.
+    /// Commands to one device (`-s <serial>`) run one at a time, since
+    /// overlapping ps/getprop/screencap calls contend on adbd; different
+    /// devices run in parallel.
+    async fn output<S: AsRef<str>>(&self, args: &[S]) -> std::io::Result<Output> {
+        let args: Vec<String> = args.iter().map(|arg| arg.as_ref().to_string()).collect();
+        self.run_queued(&args, None).await.0
+    }
+
+    /// `output` returning the audit record too; `cancel` stops the command
+    /// early. Runs longer than the `adbTimeoutSecs` setting are killed.
+    async fn run_queued(
+        &self,
+        args: &[String],
+        cancel: Option<&CancelToken>,
+    ) -> (std::io::Result<Output>, AdbInvocation) {
+        let lock = device_of(args).map(|device| self.device_lock(device));
+        let _guard = match &lock {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+        let limit = Duration::from_secs(settings::current().adb_timeout_secs.max(1));
+        audit::run(&self.adb_path(), args, limit, cancel, &self.audit).await
+    }
+
+    /// Queue lock serializing the commands of one device
+    fn device_lock(&self, device_id: &str) -> Arc<Mutex<()>> {
+        self.device_locks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(device_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Recent adb invocations, oldest first
+    pub fn history(&self) -> Vec<AdbInvocation> {
+        self.audit.lock().unwrap_or_else(|e| e.into_inner()).list()
+    }
+
+    /// Register a long-running operation under a frontend-chosen ID
+    pub fn begin_operation(&self, id: &str) -> Operation {
+        self.operations.begin(id)
+    }
+
+    /// Stop the adb command of a running operation; false if none has that ID
+    pub fn cancel_operation(&self, id: &str) -> bool {
+        self.operations.cancel(id)
+    }
+
+    /// Run a recorded invocation again with the same arguments
+    pub async fn rerun(
+        &self,
+        id: u64,
+        cancel: Option<&CancelToken>,
+    ) -> Result<AdbReplay, String> {
+        let previous = self
+            .audit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(id)
+            .ok_or_else(|| format!("adb invocation {} is no longer in the history", id))?;
+        if previous.streaming {
+            return Err("Streaming commands can't be re-run; start the stream instead".to_string());
+        }
+        info!("Re-running adb {}", previous.args.join(" "));
+        let (result, invocation) = self.run_queued(&previous.args, cancel).await;
+        let output = result.map_err(|e| format!("Failed to run adb: {}", e))?;
+        Ok(AdbReplay {
+            invocation,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
+    /// Get the `adb version` output
+    pub async fn version(&self) -> Result<String, String> {
+        let output = self.output(&["version"])
+            .await
+            .map_err(|e| format!("Failed to run adb: {}", e))?;
+
+        if !output.status.success() {
+            return Err("ADB command failed".to_string());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
     /// Get list of connected devices
     pub async fn get_devices(&self) -> Result<Vec<Device>, String> {
-        let output = Command::new(&self.adb_path)
-            .args(["devices", "-l"])
-            .output()
+        let output = self.output(&["devices", "-l"])
             .await
             .map_err(|e| format!("Failed to get devices: {}", e))?;
 
@@ -129,6 +380,8 @@ impl AdbManager {
             // Parse additional device info
             let mut model = String::new();
             let mut name = String::new();
+            let mut has_usb = false;
+            let mut transport_id = None;
 
             for part in &parts[2..] {
                 if let Some(val) = part.strip_prefix("model:") {
@@ -137,8 +390,23 @@ impl AdbManager {
                 if let Some(val) = part.strip_prefix("device:") {
                     name = val.to_string();
                 }
+                if part.starts_with("usb:") {
+                    has_usb = true;
+                }
+                if let Some(val) = part.strip_prefix("transport_id:") {
+                    transport_id = val.parse().ok();
+                }
             }
 
+            // Network devices show up as "ip:port" or as mDNS service names
+            let transport = if has_usb {
+                Transport::Usb
+            } else if id.contains(':') || id.contains("._adb-tls-connect.") {
+                Transport::Tcpip
+            } else {
+                Transport::Unknown
+            };
+
             // Check if emulator
             let is_emulator = id.starts_with("emulator-") || id.contains("localhost");
 
@@ -156,22 +424,241 @@ impl AdbManager {
             }
 
             devices.push(Device {
+                color_index: palette::color_index(&id),
                 id,
                 name,
                 model,
                 state,
                 is_emulator,
+                transport,
+                transport_id,
+                groups: Vec::new(),
             });
         }
 
         devices
     }
 
+    /// Switch a USB device to adb over Wi-Fi and connect to it.
+    /// Returns the serial of the new network connection ("ip:port").
+    pub async fn switch_to_tcpip(&self, device_id: &str, port: u16) -> Result<String, String> {
+        let output = self.output(&["-s", device_id, "shell", "ip", "route"])
+            .await
+            .map_err(|e| format!("Failed to read routes: {}", e))?;
+        let routes = String::from_utf8_lossy(&output.stdout);
+        let ip = parse_wifi_ip(&routes).ok_or("Device has no Wi-Fi address")?;
+
+        let output = self.output(&["-s", device_id, "tcpip", &port.to_string()])
+            .await
+            .map_err(|e| format!("Failed to run adb tcpip: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "adb tcpip failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        // adbd restarts in TCP mode; give it a moment before connecting
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let serial = format!("{}:{}", ip, port);
+        let output = self.output(&["connect", &serial])
+            .await
+            .map_err(|e| format!("Failed to run adb connect: {}", e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("connected to") {
+            info!("Connected to {} over Wi-Fi", serial);
+            Ok(serial)
+        } else {
+            Err(format!("adb connect failed: {}", stdout.trim()))
+        }
+    }
+
+    /// Services currently discovered by the adb server's mDNS browser
+    pub async fn mdns_services(&self) -> Result<Vec<MdnsService>, String> {
+        let output = self.output(&["mdns", "services"])
+            .await
+            .map_err(|e| format!("Failed to run adb mdns services: {}", e))?;
+        Ok(pairing::parse_mdns_services(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Pair with a device's wireless debugging pairing service ("ip:port")
+    pub async fn pair(&self, address: &str, password: &str) -> Result<(), String> {
+        let output = self.output(&["pair", address, password])
+            .await
+            .map_err(|e| format!("Failed to run adb pair: {}", e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if stdout.contains("Successfully paired") {
+            info!("Paired with {}", address);
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = if stdout.trim().is_empty() { stderr.trim() } else { stdout.trim() };
+            Err(format!("adb pair failed: {}", detail))
+        }
+    }
+
+    /// Restart the adb server with fresh host keys, for devices stuck in
+    /// `unauthorized`. The old keys are kept as `.bak-<timestamp>`, never
+    /// overwriting an earlier backup; returns the backup files. The server is
+    /// started again even when the keys couldn't be moved.
+    pub async fn restart_with_revoked_keys(&self) -> Result<Vec<String>, String> {
+        let dir = adb_key_dir().ok_or("Cannot locate the .android directory")?;
+
+        let _ = self
+            .output(&["kill-server"])
+            .await
+            .map_err(|e| format!("Failed to run adb kill-server: {}", e))?;
+
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let moved = backup_host_keys(&dir, &stamp);
+
+        let output = self
+            .output(&["start-server"])
+            .await
+            .map_err(|e| format!("Failed to run adb start-server: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "adb start-server failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let moved = moved?;
+        info!("Restarted adb server with new keys (backups {:?})", moved);
+        Ok(moved)
+    }
+
+    /// Buffer sizes and per-UID usage from `logcat -g` and `logcat -S`
+    pub async fn logd_stats(&self, device_id: &str) -> Result<LogdStats, String> {
+        let output = self.output(&["-s", device_id, "shell", "logcat -g; logcat -S"])
+            .await
+            .map_err(|e| format!("Failed to read logd statistics: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "logcat -S failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(logd::parse_stats(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Detect whether adbd runs, or may run, as root
+    pub async fn root_status(&self, device_id: &str) -> Result<RootStatus, String> {
+        let output = self
+            .output(&[
+                "-s",
+                device_id,
+                "shell",
+                "getprop ro.build.type; getprop ro.debuggable; id -u",
+            ])
+            .await
+            .map_err(|e| format!("Failed to read build type: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to read build type: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(parse_root_status(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Restart adbd as root (`adb root`) or back as shell (`adb unroot`).
+    /// Running streams of the device end while adbd restarts.
+    pub async fn restart_adbd(&self, device_id: &str, root: bool) -> Result<RootStatus, String> {
+        let status = self.root_status(device_id).await?;
+        if root && !status.available {
+            return Err(format!(
+                "adb root is not available on this build (ro.build.type={}); \
+                 privileged sources need a userdebug or eng build",
+                status.build_type
+            ));
+        }
+
+        let command = if root { "root" } else { "unroot" };
+        let output = self.output(&["-s", device_id, command])
+            .await
+            .map_err(|e| format!("Failed to run adb {}: {}", command, e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // adb exits 0 even when adbd refuses, so check the message
+        if !output.status.success() || stdout.contains("cannot run as root") {
+            return Err(format!(
+                "adb {} failed: {}{}",
+                command,
+                stdout.trim(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        // Wait outside the device queue, like `bugreport`, so the device's
+        // other commands aren't held up for as long as adbd takes to return
+        let args = ["-s", device_id, "wait-for-device"].map(String::from);
+        let limit = Duration::from_secs(settings::current().adb_timeout_secs.max(1));
+        let _ = audit::run(&self.adb_path(), &args, limit, None, &self.audit).await;
+        info!("Restarted adbd on {} ({})", device_id, command);
+        self.root_status(device_id).await
+    }
+
+    /// Installed `versionName` of a package, if it can be read
+    pub async fn package_version(&self, device_id: &str, package: &str) -> Option<String> {
+        let output = self.output(&["-s", device_id, "shell", "dumpsys", "package", package])
+            .await
+            .ok()?;
+        parse_version_name(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Thermal status and sensor temperatures from `thermalservice`, or just
+    /// the `/sys/class/thermal` zones on devices without it
+    pub async fn thermal_snapshot(&self, device_id: &str) -> Result<ThermalSnapshot, String> {
+        let output = self
+            .output(&["-s", device_id, "shell", "dumpsys", "thermalservice"])
+            .await
+            .map_err(|e| format!("Failed to run dumpsys thermalservice: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Device {} did not respond", device_id));
+        }
+        let mut snapshot = thermal::parse_thermalservice(&String::from_utf8_lossy(&output.stdout));
+        if snapshot.temperatures.is_empty() {
+            let script = "for z in /sys/class/thermal/thermal_zone*; do echo \"$(cat $z/type) $(cat $z/temp)\"; done";
+            if let Ok(output) = self.output(&["-s", device_id, "shell", script]).await {
+                snapshot.temperatures = thermal::parse_thermal_zones(&String::from_utf8_lossy(&output.stdout));
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Install path and main process of a package; errors when the device doesn't answer
+    pub async fn package_snapshot(&self, device_id: &str, package: &str) -> Result<PackageSnapshot, String> {
+        follow::validate_package(package)?;
+        let script = format!("pm path {}; echo --", package);
+        let output = self
+            .output(&["-s", device_id, "shell", &script])
+            .await
+            .map_err(|e| format!("Failed to run pm path: {}", e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        // `pm path` fails for missing packages, so check the shell actually ran
+        if !stdout.contains("--") {
+            return Err(format!("Device {} did not respond", device_id));
+        }
+        let apk_path = stdout
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("package:"))
+            .map(str::to_string);
+        let pid = self
+            .get_processes(device_id)
+            .await?
+            .into_iter()
+            .find(|p| p.name == package)
+            .map(|p| p.pid);
+        Ok(PackageSnapshot { apk_path, pid })
+    }
+
     /// Get running processes on a device
     pub async fn get_processes(&self, device_id: &str) -> Result<Vec<ProcessInfo>, String> {
-        let output = Command::new(&self.adb_path)
-            .args(["-s", device_id, "shell", "ps", "-A", "-o", "PID,NAME"])
-            .output()
+        let caps = self.capabilities(device_id).await;
+        let mut args = vec!["-s", device_id, "shell"];
+        args.extend(caps.ps_args());
+        let output = self
+            .output(&args)
             .await
             .map_err(|e| format!("Failed to get processes: {}", e))?;
 
@@ -180,57 +667,118 @@ impl AdbManager {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let processes = self.parse_processes_output(&stdout);
+        let processes = parse_ps_output(&stdout, !caps.ps_all);
         
         Ok(processes)
     }
 
-    /// Parse the output of ps command
-    fn parse_processes_output(&self, output: &str) -> Vec<ProcessInfo> {
-        let mut processes = Vec::new();
+    /// Current UTC offset of the device clock, from `date +%z`. The zone name in
+    /// `persist.sys.timezone` would need a tz database to resolve.
+    pub async fn device_utc_offset(&self, device_id: &str) -> Option<FixedOffset> {
+        let output = self.output(&["-s", device_id, "shell", "date", "+%z"])
+            .await
+            .ok()?;
+        parse_utc_offset(&String::from_utf8_lossy(&output.stdout))
+    }
 
-        for line in output.lines().skip(1) {
-            // Skip header
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
-            }
+    /// Kernel boot ID, which changes on every reboot
+    pub async fn boot_id(&self, device_id: &str) -> Option<String> {
+        let output = self
+            .output(&["-s", device_id, "shell", "cat", "/proc/sys/kernel/random/boot_id"])
+            .await
+            .ok()?;
+        let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !id.is_empty()).then_some(id)
+    }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 2 {
-                continue;
-            }
+    /// Whether Android reports `sys.boot_completed`; false while adbd is not up yet
+    pub async fn boot_completed(&self, device_id: &str) -> bool {
+        self.output(&["-s", device_id, "shell", "getprop", "sys.boot_completed"])
+            .await
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+    }
 
-            if let Ok(pid) = parts[0].parse::<u32>() {
-                let name = parts[1..].join(" ");
-                let package_name = if name.contains('.') {
-                    Some(name.clone())
-                } else {
-                    None
-                };
+    /// Value of a debugging prop; empty when unset
+    pub async fn get_prop(&self, device_id: &str, key: &str) -> Result<String, String> {
+        props::validate(key, "")?;
+        let output = self.output(&["-s", device_id, "shell", "getprop", key])
+            .await
+            .map_err(|e| format!("Failed to run getprop: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "getprop {} failed: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
 
-                processes.push(ProcessInfo {
-                    pid,
-                    name,
-                    package_name,
-                });
-            }
+    /// Set a debugging prop; an empty value clears it. Rendering props are
+    /// read by apps when they start drawing, so running apps may need a restart.
+    pub async fn set_prop(&self, device_id: &str, key: &str, value: &str) -> Result<(), String> {
+        props::validate(key, value)?;
+        let value = if value.is_empty() { "''" } else { value };
+        let output = self.output(&["-s", device_id, "shell", "setprop", key, value])
+            .await
+            .map_err(|e| format!("Failed to run setprop: {}", e))?;
+        // setprop prints its errors but may still exit 0 on older releases
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() || !stderr.trim().is_empty() {
+            return Err(format!("setprop {} failed: {}", key, stderr.trim()));
         }
+        info!("Set {}={} on {}", key, value, device_id);
+        Ok(())
+    }
 
-        processes
+    /// Switch a Developer options toggle
+    pub async fn apply_debug_toggle(
+        &self,
+        device_id: &str,
+        toggle: DebugToggle,
+        enabled: bool,
+    ) -> Result<(), String> {
+        let output = self.output(&["-s", device_id, "shell", &toggle.command(enabled)])
+            .await
+            .map_err(|e| format!("Failed to switch {}: {}", toggle.label(), e))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() || !stderr.trim().is_empty() {
+            return Err(format!("Failed to switch {}: {}", toggle.label(), stderr.trim()));
+        }
+        info!("{} {} on {}", toggle.label(), if enabled { "on" } else { "off" }, device_id);
+        Ok(())
     }
 
-    /// Start logcat streaming with process info enrichment
+    /// Curated debugging props and any `log.tag.*` levels with their values
+    pub async fn debug_props(&self, device_id: &str) -> Result<Vec<DebugProp>, String> {
+        let output = self.output(&["-s", device_id, "shell", "getprop"])
+            .await
+            .map_err(|e| format!("Failed to run getprop: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("Device {} did not respond", device_id));
+        }
+        Ok(props::debug_props(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Start logcat streaming with process info enrichment.
+    /// `filter_specs` are appended as logcat filterspecs (`TAG:LEVEL`); empty
+    /// `buffers` reads the `defaultBuffers` setting.
+    /// Lines are counted in `stats`; unparsed ones also in `metrics` and, with
+    /// `emitUnparsed`, streamed as raw entries.
+    /// With `keep_buffer` the device's buffered lines are streamed too instead of being cleared.
     pub async fn start_logcat(
         &self,
         device_id: &str,
+        (filter_specs, buffers): (&[String], &[String]),
+        keep_buffer: bool,
         sender: mpsc::Sender<LogEntry>,
+        (metrics, stats): (Arc<Mutex<Metrics>>, Arc<StreamStats>),
+        tasks: &TaskSet,
     ) -> Result<tokio::process::Child, String> {
         info!("Starting logcat for device: {}", device_id);
 
         // Create process cache
-        let process_cache: Arc<RwLock<HashMap<u32, (String, Option<String>)>>> = 
-            Arc::new(RwLock::new(HashMap::new()));
+        let process_cache: ProcessCache = Arc::new(RwLock::new(HashMap::new()));
 
         // Initial process list fetch
         if let Ok(processes) = self.get_processes(device_id).await {
@@ -241,37 +789,40 @@ impl AdbManager {
             info!("Loaded {} processes into cache", cache.len());
         }
 
-        // Spawn task to periodically refresh process list
-        let adb_path = self.adb_path.clone();
+        // Supervised task periodically refreshing the process list; it ends
+        // with the reader, which holds the last strong reference to the cache.
+        // Like the device monitor's poll, the refresh isn't recorded in the audit log
+        let adb_path = self.adb_path();
         let device_id_clone = device_id.to_string();
-        let cache_clone = process_cache.clone();
-        tokio::spawn(async move {
-            let mut refresh_interval = interval(Duration::from_secs(5));
-            loop {
-                refresh_interval.tick().await;
-                
-                let output = Command::new(&adb_path)
-                    .args(["-s", &device_id_clone, "shell", "ps", "-A", "-o", "PID,NAME"])
-                    .output()
-                    .await;
-
-                if let Ok(output) = output {
-                    if output.status.success() {
-                        let stdout = String::from_utf8_lossy(&output.stdout);
-                        let mut cache = cache_clone.write().await;
-                        
-                        for line in stdout.lines().skip(1) {
-                            let parts: Vec<&str> = line.trim().split_whitespace().collect();
-                            if parts.len() >= 2 {
-                                if let Ok(pid) = parts[0].parse::<u32>() {
-                                    let name = parts[1..].join(" ");
-                                    let package_name = if name.contains('.') {
-                                        Some(name.clone())
-                                    } else {
-                                        None
-                                    };
-                                    cache.insert(pid, (name, package_name));
-                                }
+        let cache_weak = Arc::downgrade(&process_cache);
+        let caps = self.capabilities(device_id).await;
+        tasks.supervise("process-cache", Some(device_id), move || {
+            let adb_path = adb_path.clone();
+            let device_id = device_id_clone.clone();
+            let cache_weak = cache_weak.clone();
+            let caps = caps.clone();
+            async move {
+                let mut refresh_interval = interval(Duration::from_secs(5));
+                loop {
+                    refresh_interval.tick().await;
+
+                    let output = Command::new(&adb_path)
+                        .args(["-s", &device_id, "shell"])
+                        .args(caps.ps_args())
+                        .kill_on_drop(true)
+                        .output()
+                        .await;
+
+                    let Some(cache) = cache_weak.upgrade() else {
+                        break;
+                    };
+                    if let Ok(output) = output {
+                        if output.status.success() {
+                            let stdout = String::from_utf8_lossy(&output.stdout);
+                            let mut cache = cache.write().await;
+
+                            for proc in parse_ps_output(&stdout, !caps.ps_all) {
+                                cache.insert(proc.pid, (proc.name, proc.package_name));
                             }
                         }
                     }
@@ -279,40 +830,49 @@ impl AdbManager {
             }
         });
 
-        info!("Clearing logcat buffer before streaming");
-        self.clear_logcat(device_id).await?;
+        if !keep_buffer {
+            info!("Clearing logcat buffer before streaming");
+            self.clear_logcat(device_id).await?;
+        }
+
+        let config = settings::current();
+        let mut args = vec!["-s".to_string(), device_id.to_string(), "logcat".to_string()];
+        let buffers = if buffers.is_empty() { &config.default_buffers[..] } else { buffers };
+        for buffer in buffers {
+            args.push("-b".to_string());
+            args.push(buffer.clone());
+        }
+        args.extend(["-v".to_string(), "threadtime".to_string()]);
+        args.extend(filter_specs.iter().cloned());
 
-        let mut child = Command::new(&self.adb_path)
-            .args(["-s", device_id, "logcat", "-v", "threadtime"])
+        let spawned = Command::new(self.adb_path())
+            .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to start logcat: {}", e))?;
+            .spawn();
+        self.audit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_spawn(&args, spawned.as_ref().err().map(|e| e.to_string()));
+        let mut child = spawned.map_err(|e| format!("Failed to start logcat: {}", e))?;
 
         let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-        let mut parser = LogParser::new();
-        let cache_for_reader = process_cache.clone();
+        let pump = LogcatPump {
+            parser: match self.device_utc_offset(device_id).await {
+                Some(offset) => LogParser::with_offset(offset),
+                None => LogParser::new(),
+            },
+            cache: process_cache.clone(),
+            metrics,
+            stats,
+            device_id: device_id.to_string(),
+            emit_unparsed: config.emit_unparsed,
+            unparsed_tag: config.unparsed_tag,
+        };
 
         // Spawn task to read logcat output
-        tokio::spawn(async move {
-            while let Ok(Some(line)) = lines.next_line().await {
-                if let Some(mut entry) = parser.parse_line(&line) {
-                    // Enrich with process info from cache
-                    let cache = cache_for_reader.read().await;
-                    if let Some((process_name, package_name)) = cache.get(&entry.pid) {
-                        entry.process_name = Some(process_name.clone());
-                        entry.package_name = package_name.clone();
-                    }
-                    drop(cache);
-                    
-                    if sender.send(entry).await.is_err() {
-                        debug!("Logcat receiver dropped, stopping");
-                        break;
-                    }
-                }
-            }
+        tasks.spawn("logcat-reader", Some(device_id), async move {
+            pump.run(BufReader::new(stdout), sender).await;
             info!("Logcat reader task finished");
         });
 
@@ -321,9 +881,7 @@ impl AdbManager {
 
     /// Clear logcat buffer
     pub async fn clear_logcat(&self, device_id: &str) -> Result<(), String> {
-        let output = Command::new(&self.adb_path)
-            .args(["-s", device_id, "logcat", "-c"])
-            .output()
+        let output = self.output(&["-s", device_id, "logcat", "-c"])
             .await
             .map_err(|e| format!("Failed to clear logcat: {}", e))?;
 
@@ -335,78 +893,605 @@ impl AdbManager {
         }
     }
 
+    /// Capture the screen as PNG via `exec-out screencap -p`
+    pub async fn screenshot(
+        &self,
+        device_id: &str,
+        cancel: Option<&CancelToken>,
+    ) -> Result<Vec<u8>, String> {
+        let args = ["-s", device_id, "exec-out", "screencap", "-p"].map(String::from);
+        let output = self.run_queued(&args, cancel)
+            .await
+            .0
+            .map_err(|e| format!("Failed to capture screenshot: {}", e))?;
+
+        if output.status.success() && output.stdout.starts_with(b"\x89PNG") {
+            Ok(output.stdout)
+        } else {
+            Err(format!("screencap failed on {}", device_id))
+        }
+    }
+
+    /// Write a bugreport zip to `path`. Runs outside the device queue and
+    /// without the `adbTimeoutSecs` limit, since it takes minutes while other
+    /// commands keep working.
+    pub async fn bugreport(&self, device_id: &str, path: &Path) -> Result<(), String> {
+        let args = ["-s", device_id, "bugreport", &path.display().to_string()].map(String::from);
+        let output = audit::run(&self.adb_path(), &args, BUGREPORT_TIMEOUT, None, &self.audit)
+            .await
+            .0
+            .map_err(|e| format!("Failed to run bugreport: {}", e))?;
+        if output.status.success() && path.exists() {
+            Ok(())
+        } else {
+            Err(format!(
+                "bugreport failed on {}: {}",
+                device_id,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
     /// Start device monitor task to detect device connection/disconnection
-    pub fn start_device_monitor(&self, app_handle: AppHandle) {
+    pub fn start_device_monitor(&self, app_handle: AppHandle, tasks: &TaskSet) {
         let adb_path = self.adb_path.clone();
 
-        tauri::async_runtime::spawn(async move {
-            let mut last_devices: HashMap<String, DeviceState> = HashMap::new();
-            let mut interval = interval(Duration::from_secs(2));
+        tasks.supervise("device-monitor", None, move || {
+            let adb_path = adb_path.clone();
+            let app_handle = app_handle.clone();
+            async move {
+                let mut last_devices: HashMap<String, DeviceState> = HashMap::new();
+                let mut interval = interval(Duration::from_secs(2));
 
-            loop {
-                interval.tick().await;
+                loop {
+                    interval.tick().await;
 
-                // Get current device list
-                let manager = AdbManager {
-                    adb_path: adb_path.clone(),
-                };
-                let current_devices = match manager.get_devices().await {
-                    Ok(devices) => devices,
-                    Err(e) => {
-                        error!("Failed to get devices: {}", e);
-                        continue;
+                    // Get current device list
+                    // Not recorded in the audit log, which this poll would flood
+                    let manager = AdbManager {
+                        adb_path: adb_path.clone(),
+                        capabilities: RwLock::new(HashMap::new()),
+                        audit: Default::default(),
+                        device_locks: Default::default(),
+                        operations: Default::default(),
+                    };
+                    let current_devices = match manager.get_devices().await {
+                        Ok(devices) => devices,
+                        Err(e) => {
+                            error!("Failed to get devices: {}", e);
+                            continue;
+                        }
+                    };
+
+                    // Build current device state map
+                    let current_map: HashMap<String, DeviceState> = current_devices
+                        .iter()
+                        .map(|d| (d.id.clone(), d.state))
+                        .collect();
+
+                    // Detect disconnected devices
+                    for (old_id, old_state) in &last_devices {
+                        if *old_state == DeviceState::Device {
+                            if !current_map.contains_key(old_id)
+                                || current_map.get(old_id) != Some(&DeviceState::Device)
+                            {
+                                // Device disconnected
+                                info!("Device disconnected: {}", old_id);
+                                let event = DeviceEvent::Disconnected {
+                                    device_id: old_id.clone(),
+                                };
+                                let _ = app_handle.emit("device-event", &event);
+                            }
+                        }
                     }
-                };
 
-                // Build current device state map
-                let current_map: HashMap<String, DeviceState> = current_devices
-                    .iter()
-                    .map(|d| (d.id.clone(), d.state))
-                    .collect();
-
-                // Detect disconnected devices
-                for (old_id, old_state) in &last_devices {
-                    if *old_state == DeviceState::Device {
-                        if !current_map.contains_key(old_id)
-                            || current_map.get(old_id) != Some(&DeviceState::Device)
+                    // Devices waiting for (or done with) the authorization prompt
+                    let mut authorized = Vec::new();
+                    for device in &current_devices {
+                        let previous = last_devices.get(&device.id);
+                        if device.state == DeviceState::Unauthorized
+                            && previous != Some(&DeviceState::Unauthorized)
                         {
-                            // Device disconnected
-                            info!("Device disconnected: {}", old_id);
-                            let event = DeviceEvent::Disconnected {
-                                device_id: old_id.clone(),
+                            info!("Device unauthorized: {}", device.id);
+                            let event = DeviceEvent::Unauthorized {
+                                device_id: device.id.clone(),
                             };
                             let _ = app_handle.emit("device-event", &event);
+                        } else if device.state == DeviceState::Device
+                            && previous == Some(&DeviceState::Unauthorized)
+                        {
+                            info!("Device authorized: {}", device.id);
+                            authorized.push(device);
                         }
                     }
-                }
-
-                // Detect newly connected devices
-                for device in &current_devices {
-                    if device.state == DeviceState::Device {
-                        let was_disconnected = !last_devices.contains_key(&device.id)
-                            || last_devices.get(&device.id) != Some(&DeviceState::Device);
-
-                        if was_disconnected {
-                            // Device connected
-                            info!("Device connected: {}", device.id);
-                            let event = DeviceEvent::Connected {
-                                device: device.clone(),
+                    if !authorized.is_empty() {
+                        let event = DeviceEvent::ListUpdated {
+                            devices: current_devices.clone(),
+                        };
+                        let _ = app_handle.emit("device-event", &event);
+                        for device in &authorized {
+                            let event = DeviceEvent::Authorized {
+                                device: (*device).clone(),
                             };
                             let _ = app_handle.emit("device-event", &event);
                         }
                     }
-                }
 
-                // Update device list
-                last_devices = current_map;
+                    // Detect newly connected devices
+                    for device in &current_devices {
+                        if device.state == DeviceState::Device {
+                            let was_disconnected = !last_devices.contains_key(&device.id)
+                                || last_devices.get(&device.id) != Some(&DeviceState::Device);
+
+                            if was_disconnected {
+                                // Device connected
+                                info!("Device connected: {}", device.id);
+                                let event = DeviceEvent::Connected {
+                                    device: device.clone(),
+                                };
+                                let _ = app_handle.emit("device-event", &event);
+                                if authorized.iter().any(|d| d.id == device.id) {
+                                    commands::autostart_if_authorized(&app_handle, device).await;
+                                }
+                                commands::autostart_profile(&app_handle, device).await;
+                                commands::autostart_if_pinned(&app_handle, device).await;
+                            }
+                        }
+                    }
+
+                    // Update device list
+                    last_devices = current_map;
+                }
             }
         });
     }
 }
 
+/// Parse the output of ps command.
+/// Modern toybox output is "PID NAME"; legacy (pre-Oreo) output is
+/// "USER PID PPID VSIZE RSS WCHAN PC S NAME".
+fn parse_ps_output(output: &str, legacy: bool) -> Vec<ProcessInfo> {
+    let mut processes = Vec::new();
+
+    for line in output.lines().skip(1) {
+        // Skip header
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let (pid, name) = if legacy {
+            if parts.len() < 3 {
+                continue;
+            }
+            (parts[1], parts[parts.len() - 1].to_string())
+        } else {
+            if parts.len() < 2 {
+                continue;
+            }
+            (parts[0], parts[1..].join(" "))
+        };
+
+        if let Ok(pid) = pid.parse::<u32>() {
+            let package_name = if name.contains('.') {
+                Some(name.clone())
+            } else {
+                None
+            };
+
+            processes.push(ProcessInfo {
+                pid,
+                name,
+                package_name,
+            });
+        }
+    }
+
+    processes
+}
+
+/// Directory holding the adb host keys (`$ANDROID_USER_HOME` or `~/.android`)
+fn adb_key_dir() -> Option<std::path::PathBuf> {
+    if let Some(dir) = std::env::var_os("ANDROID_USER_HOME") {
+        return Some(dir.into());
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| std::path::PathBuf::from(home).join(".android"))
+}
+
+/// Move `adbkey` and `adbkey.pub` in `dir` to `<name>.bak-<stamp>`, so adb
+/// generates new ones. Nothing is moved when a backup of that name exists, and
+/// a key moved before a later failure is moved back.
+fn backup_host_keys(dir: &Path, stamp: &str) -> Result<Vec<String>, String> {
+    let keys: Vec<_> = ["adbkey", "adbkey.pub"]
+        .iter()
+        .map(|name| (dir.join(name), dir.join(format!("{}.bak-{}", name, stamp))))
+        .filter(|(key, _)| key.exists())
+        .collect();
+    if let Some((_, backup)) = keys.iter().find(|(_, backup)| backup.exists()) {
+        return Err(format!("Backup {} already exists", backup.display()));
+    }
+
+    for (i, (key, backup)) in keys.iter().enumerate() {
+        if let Err(e) = std::fs::rename(key, backup) {
+            for (key, backup) in &keys[..i] {
+                let _ = std::fs::rename(backup, key);
+            }
+            return Err(format!("Failed to move {}: {}", key.display(), e));
+        }
+    }
+    Ok(keys.iter().map(|(_, backup)| backup.display().to_string()).collect())
+}
+
+/// Check a logcat filterspec such as `ActivityManager:I` or `*:S`
+pub fn validate_filterspec(spec: &str) -> Result<(), String> {
+    let valid = spec.rsplit_once(':').is_some_and(|(tag, level)| {
+        !tag.is_empty()
+            && !tag.chars().any(char::is_whitespace)
+            && matches!(level, "V" | "D" | "I" | "W" | "E" | "F" | "S")
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid logcat filterspec: {}", spec))
+    }
+}
+
+/// Serial a command targets with `-s`
+fn device_of(args: &[String]) -> Option<&str> {
+    args.iter()
+        .position(|arg| arg == "-s")
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Parse the output of `getprop ro.build.type; getprop ro.debuggable; id -u`
+fn parse_root_status(output: &str) -> RootStatus {
+    let mut lines = output.lines().map(str::trim);
+    let build_type = lines.next().unwrap_or_default().to_string();
+    let debuggable = lines.next() == Some("1");
+    let active = lines.next() == Some("0");
+    RootStatus {
+        available: active || (debuggable && build_type != "user"),
+        active,
+        privileged_sources: if active {
+            PRIVILEGED_SOURCES.iter().map(|s| s.to_string()).collect()
+        } else {
+            Vec::new()
+        },
+        build_type,
+    }
+}
+
+/// Extract the Wi-Fi address from `ip route` output
+/// e.g. "192.168.1.0/24 dev wlan0 proto kernel scope link src 192.168.1.23"
+fn parse_wifi_ip(routes: &str) -> Option<String> {
+    routes
+        .lines()
+        .filter(|line| line.contains("wlan"))
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            parts.find(|p| *p == "src")?;
+            parts.next().map(|ip| ip.to_string())
+        })
+}
+
+/// First `versionName=` in `dumpsys package` output
+fn parse_version_name(dumpsys: &str) -> Option<String> {
+    dumpsys
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("versionName="))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Running processes by PID: (process name, package)
+type ProcessCache = Arc<RwLock<HashMap<u32, (String, Option<String>)>>>;
+
+/// Reader side of a logcat stream: parses lines, enriches them from the process
+/// cache and counts unparsed ones. Reads any `AsyncBufRead`, so it runs the same
+/// on adb's stdout and on canned output in tests.
+struct LogcatPump {
+    parser: LogParser,
+    cache: ProcessCache,
+    metrics: Arc<Mutex<Metrics>>,
+    stats: Arc<StreamStats>,
+    device_id: String,
+    emit_unparsed: bool,
+    unparsed_tag: String,
+}
+
+impl LogcatPump {
+    /// Forward entries until the input ends or the receiver is dropped
+    async fn run<R: AsyncBufRead + Unpin>(mut self, reader: R, sender: mpsc::Sender<LogEntry>) {
+        let mut lines = LineReader::new(reader);
+        while let Ok(Some(line)) = lines.next_line().await {
+            self.stats.record_line();
+            let mut parsed = true;
+            let mut entry = match self.parser.parse_line(&line) {
+                Some(entry) => entry,
+                None => match self.parser.unparsed_entry(&line, &self.unparsed_tag) {
+                    Some(raw) => {
+                        self.metrics.lock().await.record_unparsed(&self.device_id);
+                        self.stats.record_unparsed();
+                        parsed = false;
+                        if !self.emit_unparsed {
+                            continue;
+                        }
+                        raw
+                    }
+                    None => continue,
+                },
+            };
+
+            // Enrich with process info from cache
+            let cache = self.cache.read().await;
+            let known = cache.get(&entry.pid);
+            if let Some((process_name, package_name)) = known {
+                entry.process_name = Some(process_name.clone());
+                entry.package_name = package_name.clone();
+            }
+            if parsed {
+                self.stats.record_parsed(known.is_some());
+            }
+            drop(cache);
+
+            if sender.send(entry).await.is_err() {
+                debug!("Logcat receiver dropped, stopping");
+                break;
+            }
+        }
+    }
+}
+
+/// UTC offset printed by `date +%z`, e.g. "+0800"
+fn parse_utc_offset(output: &str) -> Option<FixedOffset> {
+    let value = output.trim();
+    let sign = match value.get(..1)? {
+        "+" => 1,
+        "-" => -1,
+        _ => return None,
+    };
+    let digits = value.get(1..)?;
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
 impl Default for AdbManager {
     fn default() -> Self {
         Self::new()
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_parse_devices_transport() {
+        let output = "List of devices attached\n\
+            R58M123ABC     device usb:1-1 product:beyond1 model:SM_G973F device:beyond1 transport_id:3\n\
+            192.168.1.23:5555 device product:beyond1 model:SM_G973F device:beyond1 transport_id:4\n\
+            emulator-5554  device product:sdk_gphone64 model:sdk_gphone64 device:emu64 transport_id:1\n";
+        let devices = AdbManager::new().parse_devices_output(output).await;
+
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].transport, Transport::Usb);
+        assert_eq!(devices[0].transport_id, Some(3));
+        assert_eq!(devices[1].transport, Transport::Tcpip);
+        assert_eq!(devices[2].transport, Transport::Unknown);
+        assert!(devices[2].is_emulator);
+    }
+
+    #[test]
+    fn test_parse_ps_output() {
+        let modern = "PID NAME\n  1 init\n4567 com.example.app\n";
+        let processes = parse_ps_output(modern, false);
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[1].pid, 4567);
+        assert_eq!(processes[1].package_name.as_deref(), Some("com.example.app"));
+
+        let legacy = "USER      PID   PPID  VSIZE  RSS   WCHAN              PC  NAME\n\
+            root      1     0     9876   1234  SyS_epoll_ 00000000 S /init\n\
+            u0_a123   4567  234   123456 45678 SyS_epoll_ 00000000 S com.example.app\n";
+        let processes = parse_ps_output(legacy, true);
+        assert_eq!(processes.len(), 2);
+        assert_eq!(processes[1].pid, 4567);
+        assert_eq!(processes[1].name, "com.example.app");
+    }
+
+    #[test]
+    fn test_capabilities_from_sdk() {
+        let nougat = DeviceCapabilities::from_sdk(24, "7.0".to_string());
+        assert!(!nougat.ps_all);
+        assert_eq!(nougat.ps_args(), &["ps"]);
+
+        let oreo = DeviceCapabilities::from_sdk(26, "8.0".to_string());
+        assert_eq!(oreo.ps_args(), &["ps", "-A", "-o", "PID,NAME"]);
+
+        let unknown = DeviceCapabilities::from_sdk(0, String::new());
+        assert!(unknown.ps_all);
+    }
+
+    #[test]
+    fn test_parse_wifi_ip() {
+        let routes = "10.0.0.0/8 dev rmnet0 proto kernel scope link src 10.1.2.3\n\
+            192.168.1.0/24 dev wlan0 proto kernel scope link src 192.168.1.23\n";
+        assert_eq!(parse_wifi_ip(routes), Some("192.168.1.23".to_string()));
+        assert_eq!(parse_wifi_ip("default via 10.0.0.1 dev rmnet0"), None);
+    }
+
+    #[test]
+    fn test_parse_version_name() {
+        let dumpsys = "Packages:\n  Package [com.example] (1a2b):\n    versionCode=42 minSdk=24\n    versionName=2.3.1\n";
+        assert_eq!(parse_version_name(dumpsys), Some("2.3.1".to_string()));
+        assert_eq!(parse_version_name("Unable to find package"), None);
+    }
+
+    #[test]
+    fn test_parse_root_status() {
+        let production = parse_root_status("user\n0\n2000\n");
+        assert!(!production.available);
+        assert!(production.privileged_sources.is_empty());
+
+        let userdebug = parse_root_status("userdebug\n1\n2000\n");
+        assert!(userdebug.available && !userdebug.active);
+
+        let rooted = parse_root_status("userdebug\n1\n0\n");
+        assert!(rooted.active);
+        assert!(rooted.privileged_sources.contains(&"dmesg".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_logcat_pump_enriches_and_counts_unparsed() {
+        let cache: ProcessCache = Arc::new(RwLock::new(HashMap::from([(
+            1234,
+            ("com.example.app".to_string(), Some("com.example.app".to_string())),
+        )])));
+        let metrics = Arc::new(Mutex::new(Metrics::new()));
+        let stats = Arc::new(StreamStats::default());
+        let pump = LogcatPump {
+            parser: LogParser::new(),
+            cache,
+            metrics: metrics.clone(),
+            stats: stats.clone(),
+            device_id: "emulator-5554".to_string(),
+            emit_unparsed: true,
+            unparsed_tag: "RAW".to_string(),
+        };
+        let output: &[u8] = b"--------- beginning of main\n\
+            12-04 10:30:45.123  1234  1234 I MainActivity: onCreate\n\
+            garbage from a broken device\n";
+        let (tx, mut rx) = mpsc::channel(8);
+        pump.run(output, tx).await;
+
+        let entry = rx.recv().await.unwrap();
+        assert_eq!(entry.package_name.as_deref(), Some("com.example.app"));
+        let raw = rx.recv().await.unwrap();
+        assert_eq!(raw.tag, "RAW");
+        assert!(rx.recv().await.is_none());
+        assert!(metrics
+            .lock()
+            .await
+            .render()
+            .contains("logcat_unparsed_lines_total{device=\"emulator-5554\"} 1"));
+        let report = stats.report("emulator-5554");
+        assert_eq!((report.lines_read, report.parsed, report.unparsed), (3, 1, 1));
+        assert_eq!(report.enrichment_rate, 1.0);
+    }
+
+    /// AdbManager running `tests/fixtures/fake-adb.sh` instead of adb
+    #[cfg(unix)]
+    fn fake_adb() -> AdbManager {
+        AdbManager::with_path(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/fake-adb.sh").to_string())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fake_adb_devices_and_processes() {
+        let adb = fake_adb();
+        assert!(adb.check_adb().await.unwrap());
+
+        let devices = adb.get_devices().await.unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].state, DeviceState::Device);
+        assert_eq!(devices[1].state, DeviceState::Unauthorized);
+
+        let processes = adb.get_processes("emulator-5554").await.unwrap();
+        assert_eq!(adb.capabilities("emulator-5554").await.sdk_level, 34);
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes[2].name, "com.example.app:sync");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commands_serialized_per_device() {
+        let adb = Arc::new(fake_adb());
+        let emulator = ["-s", "emulator-5554", "shell", "date", "+%z"];
+        let phone = ["-s", "R58M123ABC", "shell", "date", "+%z"];
+
+        // While the emulator's queue is held, its next command waits and
+        // other devices' commands still run
+        let queue = adb.device_lock("emulator-5554");
+        let held = queue.lock().await;
+        let queued = tokio::spawn({
+            let adb = adb.clone();
+            async move { adb.output(&emulator).await.is_ok() }
+        });
+        assert!(adb.output(&phone).await.is_ok());
+        assert!(!queued.is_finished());
+        assert_eq!(adb.history().len(), 1);
+
+        drop(held);
+        assert!(queued.await.unwrap());
+        let devices: Vec<String> = adb.history().iter().map(|run| run.args[1].clone()).collect();
+        assert_eq!(devices, vec!["R58M123ABC", "emulator-5554"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_fake_adb_stream_end_to_end() {
+        let adb = fake_adb();
+        let (tx, mut rx) = mpsc::channel(16);
+        let metrics = Arc::new(Mutex::new(Metrics::new()));
+        let stats = Arc::new(StreamStats::default());
+        let mut child = adb
+            .start_logcat("emulator-5554", (&[], &[]), false, tx, (metrics, stats), &TaskSet::default())
+            .await
+            .unwrap();
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.message, "onCreate");
+        assert_eq!(first.package_name.as_deref(), Some("com.example.app"));
+        assert_eq!(first.time.unwrap().offset().local_minus_utc(), 8 * 3600);
+        let second = rx.recv().await.unwrap();
+        assert_eq!(second.process_name.as_deref(), Some("com.example.app:sync"));
+        assert_eq!(rx.recv().await.unwrap().tag, "init");
+        assert!(rx.recv().await.is_none());
+        let _ = child.wait().await;
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+0800\n"), FixedOffset::east_opt(8 * 3600));
+        assert_eq!(parse_utc_offset("-0930"), FixedOffset::west_opt(9 * 3600 + 30 * 60));
+        assert_eq!(parse_utc_offset("date: bad format"), None);
+    }
+
+    #[test]
+    fn test_validate_filterspec() {
+        assert!(validate_filterspec("ActivityManager:I").is_ok());
+        assert!(validate_filterspec("*:S").is_ok());
+        assert!(validate_filterspec("MyTag:X").is_err());
+        assert!(validate_filterspec("My Tag:D").is_err());
+        assert!(validate_filterspec("MyTag").is_err());
+    }
+
+    #[test]
+    fn test_backup_host_keys() {
+        let dir = std::env::temp_dir().join(format!("adbkey-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("adbkey"), "original").unwrap();
+        std::fs::write(dir.join("adbkey.pub"), "original pub").unwrap();
+
+        let moved = backup_host_keys(&dir, "1").unwrap();
+        assert_eq!(moved.len(), 2);
+        assert!(!dir.join("adbkey").exists());
+
+        // A second run keeps the first backup and refuses a clashing name
+        std::fs::write(dir.join("adbkey"), "regenerated").unwrap();
+        assert!(backup_host_keys(&dir, "1").is_err());
+        assert!(dir.join("adbkey").exists());
+        backup_host_keys(&dir, "2").unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("adbkey.bak-1")).unwrap(), "original");
+        assert_eq!(std::fs::read_to_string(dir.join("adbkey.bak-2")).unwrap(), "regenerated");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,167 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::parser::{LogEntry, LogLevel};
+use crate::workspace::AlertRule;
+
+/// Minimum time between two firings of the same rule
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Payload of the `alert-fired` event
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertFired {
+    pub rule: String,
+    #[serde(rename = "deviceId")]
+    pub device_id: String,
+    pub entry: LogEntry,
+    /// Matches dropped by rate limiting since the previous firing
+    pub suppressed: u32,
+}
+
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    min_level: Option<LogLevel>,
+}
+
+/// Evaluates workspace alert rules against incoming entries, with per-rule rate limiting
+pub struct AlertEngine {
+    rules: Vec<CompiledRule>,
+    last_fired: HashMap<String, Instant>,
+    suppressed: HashMap<String, u32>,
+    min_interval: Duration,
+}
+
+impl AlertEngine {
+    pub fn new() -> Self {
+        AlertEngine {
+            rules: Vec::new(),
+            last_fired: HashMap::new(),
+            suppressed: HashMap::new(),
+            min_interval: DEFAULT_MIN_INTERVAL,
+        }
+    }
+
+    /// Replace the active rules
+    pub fn set_rules(&mut self, rules: &[AlertRule]) -> Result<(), String> {
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let pattern = if rule.is_regex {
+                rule.pattern.clone()
+            } else {
+                regex::escape(&rule.pattern)
+            };
+            let regex = Regex::new(&format!("(?i){}", pattern))
+                .map_err(|e| format!("Invalid pattern in alert {}: {}", rule.name, e))?;
+            compiled.push(CompiledRule {
+                name: rule.name.clone(),
+                regex,
+                min_level: rule.min_level,
+            });
+        }
+        self.rules = compiled;
+        self.last_fired.clear();
+        self.suppressed.clear();
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn set_min_interval(&mut self, interval: Duration) {
+        self.min_interval = interval;
+    }
+
+    /// Check an entry against all rules, returning the alerts that fire
+    pub fn check(&mut self, device_id: &str, entry: &LogEntry) -> Vec<AlertFired> {
+        let mut fired = Vec::new();
+        let now = Instant::now();
+
+        for rule in &self.rules {
+            if rule.min_level.is_some_and(|min| entry.level < min) {
+                continue;
+            }
+            if !rule.regex.is_match(&entry.tag) && !rule.regex.is_match(&entry.message) {
+                continue;
+            }
+
+            let limited = self
+                .last_fired
+                .get(&rule.name)
+                .is_some_and(|last| now.duration_since(*last) < self.min_interval);
+            if limited {
+                *self.suppressed.entry(rule.name.clone()).or_insert(0) += 1;
+                continue;
+            }
+
+            self.last_fired.insert(rule.name.clone(), now);
+            fired.push(AlertFired {
+                rule: rule.name.clone(),
+                device_id: device_id.to_string(),
+                entry: entry.clone(),
+                suppressed: self.suppressed.remove(&rule.name).unwrap_or(0),
+            });
+        }
+
+        fired
+    }
+}
+
+impl Default for AlertEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    fn rule(name: &str, pattern: &str, min_level: Option<LogLevel>) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            is_regex: false,
+            min_level,
+        }
+    }
+
+    #[test]
+    fn test_rule_matches_level_and_text() {
+        let mut parser = LogParser::new();
+        let mut engine = AlertEngine::new();
+        engine
+            .set_rules(&[rule("oom", "OutOfMemoryError", Some(LogLevel::E))])
+            .unwrap();
+
+        let error = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 E AndroidRuntime: java.lang.OutOfMemoryError")
+            .unwrap();
+        let info = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 I Foo: outofmemoryerror mentioned")
+            .unwrap();
+
+        assert_eq!(engine.check("dev", &error).len(), 1);
+        engine.set_min_interval(Duration::ZERO);
+        assert!(engine.check("dev", &info).is_empty());
+    }
+
+    #[test]
+    fn test_rate_limit_counts_suppressed() {
+        let mut parser = LogParser::new();
+        let mut engine = AlertEngine::new();
+        engine.set_rules(&[rule("net", "timeout", None)]).unwrap();
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 W OkHttp: read timeout")
+            .unwrap();
+
+        assert_eq!(engine.check("dev", &entry).len(), 1);
+        assert!(engine.check("dev", &entry).is_empty());
+        assert!(engine.check("dev", &entry).is_empty());
+
+        engine.set_min_interval(Duration::ZERO);
+        let fired = engine.check("dev", &entry);
+        assert_eq!(fired[0].suppressed, 2);
+    }
+}
@@ -0,0 +1,129 @@
+use log::error;
+use std::time::Instant;
+
+use crate::crash::{CrashDetector, CrashReport};
+use crate::fold::{FoldGroup, FoldTracker};
+use crate::jank::{parse_jank_event, JankEvent, JankStats};
+use crate::lifecycle::{LifecycleEvent, LifecycleTracker};
+use crate::parser::LogEntry;
+use crate::power::PowerTracker;
+use crate::secrets::{SecretScanner, SecretWarning};
+use crate::series::SeriesStore;
+use crate::settings::Settings;
+use crate::spans::{Span, SpanTracker};
+use crate::testrun::{TestEvent, TestTracker};
+use crate::timeseries::TimeSeriesStore;
+
+/// Everything derived from one device's stream. The stream task feeds it a
+/// chunk of entries at a time, so a chunk costs one lock however many
+/// analyzers there are.
+pub struct DeviceAnalysis {
+    pub secrets: SecretScanner,
+    pub folds: FoldTracker,
+    pub timeseries: TimeSeriesStore,
+    pub series: SeriesStore,
+    pub power: PowerTracker,
+    pub lifecycle: LifecycleTracker,
+    pub crashes: CrashDetector,
+    pub tests: TestTracker,
+    pub spans: SpanTracker,
+    pub jank: JankStats,
+}
+
+/// What a chunk turned up, for the stream task to emit once the lock is released
+#[derive(Debug, Default)]
+pub struct Findings {
+    pub secrets: Vec<SecretWarning>,
+    /// Fold groups the chunk closed
+    pub folds: Vec<FoldGroup>,
+    /// Lifecycle events with the epoch of the entry that produced them
+    pub lifecycle: Vec<(LifecycleEvent, Option<u64>)>,
+    pub crashes: Vec<CrashReport>,
+    pub tests: Vec<TestEvent>,
+    pub spans: Vec<Span>,
+    pub jank: Vec<JankEvent>,
+}
+
+impl DeviceAnalysis {
+    /// Fresh analyzers for a new stream; invalid span or fold rules are
+    /// reported and left out
+    pub fn new(config: &Settings) -> Self {
+        DeviceAnalysis {
+            secrets: SecretScanner::default(),
+            folds: FoldTracker::new(&config.fold_rules).unwrap_or_else(|e| {
+                error!("Ignoring fold rules: {}", e);
+                FoldTracker::default()
+            }),
+            timeseries: TimeSeriesStore::new(config.max_log_lines),
+            series: SeriesStore::default(),
+            power: PowerTracker::new(),
+            lifecycle: LifecycleTracker::new(),
+            crashes: CrashDetector::new(),
+            tests: TestTracker::new(),
+            spans: SpanTracker::new(&config.span_rules).unwrap_or_else(|e| {
+                error!("Ignoring span rules: {}", e);
+                SpanTracker::default()
+            }),
+            jank: JankStats::default(),
+        }
+    }
+
+    /// Run a chunk of entries through every analyzer, in stream order. Secret
+    /// scanning and folding annotate the entries themselves.
+    pub fn observe(
+        &mut self,
+        device_id: &str,
+        entries: &mut [LogEntry],
+        now: Instant,
+        findings: &mut Findings,
+    ) {
+        for entry in entries {
+            findings.secrets.extend(self.secrets.observe(device_id, entry));
+            findings.folds.extend(self.folds.observe(device_id, entry, now));
+            self.timeseries.observe(entry);
+            self.series.observe(entry);
+            self.power.observe(entry);
+            if let Some(event) = self.lifecycle.observe(entry) {
+                findings.lifecycle.push((event, entry.epoch));
+            }
+            if let Some(crash) = self.crashes.observe(device_id, entry) {
+                self.tests
+                    .record_problem(crash.entry_id, format!("Crash: {}", crash.exception));
+                findings.crashes.push(crash);
+            }
+            findings.tests.extend(self.tests.observe(entry));
+            findings.spans.extend(self.spans.observe(device_id, entry));
+            if let Some(event) = parse_jank_event(entry) {
+                self.jank.record(&event);
+                findings.jank.push(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_chunk_feeds_every_analyzer() {
+        let mut parser = LogParser::new();
+        let mut entries: Vec<LogEntry> = [
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: FATAL EXCEPTION: main",
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: java.lang.IllegalStateException: boom",
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: \tat com.example.Foo.bar(Foo.kt:42)",
+            "12-04 10:30:47.000   200   200 I Choreographer: Skipped 42 frames!  The application may be doing too much work on its main thread.",
+        ]
+        .into_iter()
+        .map(|line| parser.parse_line(line).unwrap())
+        .collect();
+
+        let mut analysis = DeviceAnalysis::new(&Settings::default());
+        let mut findings = Findings::default();
+        analysis.observe("emulator-5554", &mut entries, Instant::now(), &mut findings);
+        assert_eq!(findings.crashes.len(), 1);
+        assert_eq!(findings.jank.len(), 1);
+        assert!(entries[2].group_id.is_some());
+    }
+}
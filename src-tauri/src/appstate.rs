@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::parser::LogEntry;
+use crate::sessions::SessionMetadata;
+
+/// Version written to snapshots; newer files are refused
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A device that was streaming, and the window it streamed to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamSnapshot {
+    pub device_id: String,
+    pub window: String,
+}
+
+/// Everything needed to resume an investigation, written by `save_app_state`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSnapshot {
+    pub version: u32,
+    /// RFC 3339 time the snapshot was saved
+    pub saved_at: String,
+    pub streams: Vec<StreamSnapshot>,
+    pub active_workspace: Option<String>,
+    /// Pinned entries (bookmarks) per device
+    #[serde(default)]
+    pub pins: BTreeMap<String, Vec<LogEntry>>,
+    #[serde(default)]
+    pub session_metadata: BTreeMap<String, SessionMetadata>,
+    /// Frontend state such as filters and window layout hints, stored as given
+    #[serde(default)]
+    pub ui: serde_json::Value,
+    /// Recent entries per device; only when saved with buffers
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub buffers: BTreeMap<String, Vec<LogEntry>>,
+}
+
+pub fn write(path: &Path, snapshot: &AppSnapshot) -> Result<(), String> {
+    let json = serde_json::to_string(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write app state: {}", e))
+}
+
+pub fn read(path: &Path) -> Result<AppSnapshot, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read app state: {}", e))?;
+    let snapshot: AppSnapshot =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid app state file: {}", e))?;
+    if snapshot.version > FORMAT_VERSION {
+        return Err(format!(
+            "App state version {} is newer than this app supports ({})",
+            snapshot.version, FORMAT_VERSION
+        ));
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_round_trip_and_version_check() {
+        let entry = LogParser::new()
+            .parse_line("12-04 10:30:45.123  1234  1234 I MainActivity: onCreate")
+            .unwrap();
+        let snapshot = AppSnapshot {
+            version: FORMAT_VERSION,
+            saved_at: "2024-12-04T10:31:00+08:00".to_string(),
+            streams: vec![StreamSnapshot {
+                device_id: "emulator-5554".to_string(),
+                window: "main".to_string(),
+            }],
+            active_workspace: Some("checkout".to_string()),
+            pins: BTreeMap::from([("emulator-5554".to_string(), vec![entry.clone()])]),
+            session_metadata: BTreeMap::new(),
+            ui: serde_json::json!({ "filter": "level:W", "splitPane": true }),
+            buffers: BTreeMap::from([("emulator-5554".to_string(), vec![entry])]),
+        };
+        let path = std::env::temp_dir().join(format!("app-state-{}.json", std::process::id()));
+        write(&path, &snapshot).unwrap();
+        let restored = read(&path);
+        std::fs::write(&path, r#"{"version":99,"savedAt":"","streams":[],"activeWorkspace":null}"#)
+            .unwrap();
+        let newer = read(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let restored = restored.unwrap();
+        assert_eq!(restored.streams, snapshot.streams);
+        assert_eq!(restored.ui, snapshot.ui);
+        assert_eq!(restored.pins["emulator-5554"][0].message, "onCreate");
+        assert_eq!(restored.buffers["emulator-5554"].len(), 1);
+        assert!(newer.is_err());
+    }
+}
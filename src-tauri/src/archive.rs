@@ -0,0 +1,139 @@
+use chrono::{Datelike, Local, Timelike};
+
+/// Compute the CRC-32 (IEEE) checksum used by the zip format
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Minimal zip writer producing uncompressed ("stored") archives.
+/// Enough for bundling text reports without pulling in a compression crate.
+pub struct ZipWriter {
+    buf: Vec<u8>,
+    entries: Vec<CentralEntry>,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        let now = Local::now();
+        let dos_time =
+            ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+        let dos_date = ((((now.year() - 1980).max(0) as u32) << 9)
+            | (now.month() << 5)
+            | now.day()) as u16;
+        ZipWriter {
+            buf: Vec::new(),
+            entries: Vec::new(),
+            dos_time,
+            dos_date,
+        }
+    }
+
+    /// Append a file to the archive
+    pub fn add_file(&mut self, name: &str, data: &[u8]) {
+        let crc = crc32(data);
+        let offset = self.buf.len() as u32;
+
+        self.buf.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.write_common_header(name, crc, data.len() as u32);
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.extend_from_slice(data);
+
+        self.entries.push(CentralEntry {
+            name: name.to_string(),
+            crc,
+            size: data.len() as u32,
+            offset,
+        });
+    }
+
+    /// Fields shared by local and central headers, from "version needed" to "extra length"
+    fn write_common_header(&mut self, name: &str, crc: u32, size: u32) {
+        self.buf.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        self.buf.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        self.buf.extend_from_slice(&0u16.to_le_bytes()); // stored
+        self.buf.extend_from_slice(&self.dos_time.to_le_bytes());
+        self.buf.extend_from_slice(&self.dos_date.to_le_bytes());
+        self.buf.extend_from_slice(&crc.to_le_bytes());
+        self.buf.extend_from_slice(&size.to_le_bytes()); // compressed
+        self.buf.extend_from_slice(&size.to_le_bytes()); // uncompressed
+        self.buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        self.buf.extend_from_slice(&0u16.to_le_bytes()); // extra length
+    }
+
+    /// Write the central directory and return the archive bytes
+    pub fn finish(mut self) -> Vec<u8> {
+        let cd_offset = self.buf.len() as u32;
+        let entries = std::mem::take(&mut self.entries);
+
+        for entry in &entries {
+            self.buf.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            self.buf.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            self.write_common_header(&entry.name, entry.crc, entry.size);
+            self.buf.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            self.buf.extend_from_slice(&0u16.to_le_bytes()); // disk number
+            self.buf.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+            self.buf.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+            self.buf.extend_from_slice(&entry.offset.to_le_bytes());
+            self.buf.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let cd_size = self.buf.len() as u32 - cd_offset;
+        self.buf.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.buf.extend_from_slice(&0u16.to_le_bytes()); // this disk
+        self.buf.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+        self.buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        self.buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        self.buf.extend_from_slice(&cd_size.to_le_bytes());
+        self.buf.extend_from_slice(&cd_offset.to_le_bytes());
+        self.buf.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+        self.buf
+    }
+}
+
+impl Default for ZipWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_zip_layout() {
+        let mut zip = ZipWriter::new();
+        zip.add_file("a.txt", b"hello");
+        zip.add_file("b.txt", b"world");
+        let bytes = zip.finish();
+
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        // End of central directory record is the last 22 bytes
+        let eocd = &bytes[bytes.len() - 22..];
+        assert_eq!(&eocd[..4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 2);
+    }
+}
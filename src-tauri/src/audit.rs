@@ -0,0 +1,169 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::process::Output;
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+use crate::cancel::CancelToken;
+
+/// Invocations kept in the audit log. The periodic device and process polls
+/// are not recorded, so this covers a long session.
+const CAPACITY: usize = 1000;
+
+/// One adb run, as returned by `get_adb_history`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AdbInvocation {
+    pub id: u64,
+    /// Arguments after the adb executable
+    pub args: Vec<String>,
+    /// RFC 3339 start time
+    pub started_at: String,
+    pub duration_ms: u64,
+    /// `None` when adb could not be started, was killed by a signal, or
+    /// `streaming` is set
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    /// Long-running process (e.g. logcat) whose output is streamed, not awaited
+    pub streaming: bool,
+}
+
+/// Bounded history of adb invocations, newest last
+#[derive(Default)]
+pub struct AuditLog {
+    entries: VecDeque<AdbInvocation>,
+    next_id: u64,
+}
+
+impl AuditLog {
+    fn push(&mut self, mut invocation: AdbInvocation) -> AdbInvocation {
+        self.next_id += 1;
+        invocation.id = self.next_id;
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(invocation.clone());
+        invocation
+    }
+
+    pub fn list(&self) -> Vec<AdbInvocation> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<AdbInvocation> {
+        self.entries.iter().find(|entry| entry.id == id).cloned()
+    }
+
+    /// Record a process that was spawned rather than run to completion
+    pub fn record_spawn(&mut self, args: &[String], error: Option<String>) {
+        self.push(AdbInvocation {
+            id: 0,
+            args: args.to_vec(),
+            started_at: chrono::Local::now().to_rfc3339(),
+            duration_ms: 0,
+            exit_code: None,
+            error,
+            streaming: true,
+        });
+    }
+}
+
+/// Run `program` with `args` to completion and record it in `log`. A run
+/// over `limit` is killed and fails with `ErrorKind::TimedOut`; one stopped
+/// through `cancel` fails with `ErrorKind::Interrupted`.
+pub async fn run(
+    program: &str,
+    args: &[String],
+    limit: Duration,
+    cancel: Option<&CancelToken>,
+    log: &Mutex<AuditLog>,
+) -> (std::io::Result<Output>, AdbInvocation) {
+    let started_at = chrono::Local::now().to_rfc3339();
+    let start = Instant::now();
+    let command = Command::new(program).args(args).kill_on_drop(true).output();
+    let cancelled = async {
+        match cancel {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    };
+    let result = tokio::select! {
+        result = timeout(limit, command) => match result {
+            Ok(result) => result,
+            Err(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("adb {} timed out after {}s", args.join(" "), limit.as_secs()),
+            )),
+        },
+        _ = cancelled => Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            format!("adb {} was cancelled", args.join(" ")),
+        )),
+    };
+    let (exit_code, error) = match &result {
+        Ok(output) => (output.status.code(), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let invocation = AdbInvocation {
+        id: 0,
+        args: args.to_vec(),
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        exit_code,
+        error,
+        streaming: false,
+    };
+    let invocation = log
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(invocation);
+    (result, invocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_is_bounded_and_numbered() {
+        let mut log = AuditLog::default();
+        for _ in 0..CAPACITY + 5 {
+            log.record_spawn(&["logcat".to_string()], None);
+        }
+        let entries = log.list();
+        assert_eq!(entries.len(), CAPACITY);
+        assert_eq!(entries[0].id, 6);
+        assert!(log.get(1).is_none());
+        assert_eq!(log.get(6).map(|e| e.streaming), Some(true));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_times_out() {
+        let log = Mutex::new(AuditLog::default());
+        let (result, invocation) =
+            run("sleep", &["5".to_string()], Duration::from_millis(100), None, &log).await;
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::TimedOut);
+        assert!(invocation.duration_ms < 5000);
+        assert!(invocation.error.is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_cancelled() {
+        let log = Mutex::new(AuditLog::default());
+        let operations = crate::cancel::Operations::default();
+        let operation = operations.begin("op");
+        let args = ["5".to_string()];
+        let run = run("sleep", &args, Duration::from_secs(30), Some(operation.token()), &log);
+        let (result, _) = tokio::join!(run, async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            operations.cancel("op")
+        });
+        let (result, invocation) = result;
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+        assert!(invocation.duration_ms < 5000);
+    }
+}
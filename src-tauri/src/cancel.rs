@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Signal that a long-running operation should stop
+#[derive(Clone)]
+pub struct CancelToken(watch::Receiver<bool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the operation is cancelled; never otherwise
+    pub async fn cancelled(&self) {
+        let mut receiver = self.0.clone();
+        while !*receiver.borrow_and_update() {
+            if receiver.changed().await.is_err() {
+                return std::future::pending().await;
+            }
+        }
+    }
+}
+
+/// Running cancellable operations, keyed by the ID the frontend passed in
+#[derive(Clone, Default)]
+pub struct Operations {
+    senders: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+}
+
+/// A registered operation; unregistered when dropped
+pub struct Operation {
+    id: String,
+    token: CancelToken,
+    operations: Operations,
+}
+
+impl Operation {
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        self.operations.lock().remove(&self.id);
+    }
+}
+
+impl Operations {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, watch::Sender<bool>>> {
+        self.senders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register `id` so `cancel(id)` reaches the returned operation's token
+    pub fn begin(&self, id: &str) -> Operation {
+        let (sender, receiver) = watch::channel(false);
+        self.lock().insert(id.to_string(), sender);
+        Operation {
+            id: id.to_string(),
+            token: CancelToken(receiver),
+            operations: self.clone(),
+        }
+    }
+
+    /// Cancel a running operation; false if no operation has that ID
+    pub fn cancel(&self, id: &str) -> bool {
+        self.lock().get(id).is_some_and(|sender| sender.send(true).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_reaches_running_operation() {
+        let operations = Operations::default();
+        let operation = operations.begin("screenshot-1");
+        let token = operation.token().clone();
+        let waiter = tokio::spawn(async move { token.cancelled().await });
+
+        assert!(!operations.cancel("other"));
+        assert!(operations.cancel("screenshot-1"));
+        waiter.await.unwrap();
+        assert!(operation.token().is_cancelled());
+
+        drop(operation);
+        assert!(!operations.cancel("screenshot-1"));
+    }
+}
@@ -0,0 +1,81 @@
+/// Bitmask of the event channels the frontend subscribes to. Device, stream
+/// lifecycle and warning events are always sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelMask(u8);
+
+impl ChannelMask {
+    /// `logcat-entries`, `logcat-highlights` and `fold-group`
+    pub const ENTRIES: u8 = 1;
+    /// `crash-known` and `crash-new`
+    pub const CRASH: u8 = 1 << 1;
+    /// `test-event`, `span`, `jank-event`, `alert-fired`, `anomaly-detected`,
+    /// `secret-detected` and `thermal-status`
+    pub const SYSTEM_EVENTS: u8 = 1 << 2;
+    /// `stream-stats`
+    pub const STATS: u8 = 1 << 3;
+    /// `marker`, sent besides the marker entry in `logcat-entries`
+    pub const MARKERS: u8 = 1 << 4;
+    pub const ALL: ChannelMask = ChannelMask(0x1F);
+
+    const NAMES: [(&'static str, u8); 5] = [
+        ("logcat-entries", Self::ENTRIES),
+        ("crash", Self::CRASH),
+        ("system-events", Self::SYSTEM_EVENTS),
+        ("stats", Self::STATS),
+        ("markers", Self::MARKERS),
+    ];
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        ChannelMask(bits & Self::ALL.0)
+    }
+
+    /// Bits of the named channels
+    pub fn parse(names: &[String]) -> Result<u8, String> {
+        names.iter().try_fold(0, |bits, name| {
+            Self::NAMES
+                .iter()
+                .find(|(known, _)| known == name)
+                .map(|(_, bit)| bits | bit)
+                .ok_or_else(|| format!("Unknown event channel: {}", name))
+        })
+    }
+
+    pub fn contains(self, channel: u8) -> bool {
+        self.0 & channel != 0
+    }
+
+    /// Names of the subscribed channels
+    pub fn names(self) -> Vec<String> {
+        Self::NAMES
+            .iter()
+            .filter(|(_, bit)| self.contains(*bit))
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+impl Default for ChannelMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_names() {
+        let bits = ChannelMask::parse(&["crash".to_string(), "stats".to_string()]).unwrap();
+        let mask = ChannelMask::from_bits(bits);
+        assert!(mask.contains(ChannelMask::CRASH));
+        assert!(!mask.contains(ChannelMask::ENTRIES));
+        assert_eq!(mask.names(), vec!["crash", "stats"]);
+        assert_eq!(ChannelMask::ALL.names().len(), 5);
+        assert!(ChannelMask::parse(&["network".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use crate::convert::{self, ConvertFormat};
+use crate::filter::{FilterConfig, LogFilter, MatchMode};
+use crate::parser::LogLevel;
+use crate::profile::{self, LaunchProfile};
+
+const USAGE: &str = "Usage: logcat session convert <input> <output> [options]
+
+Formats are inferred from the extensions (.json session, .jsonl, .csv,
+anything else plain logcat) unless given explicitly.
+
+Options:
+  --from <format>   session, jsonl, logcat or csv
+  --to <format>     session, jsonl, logcat or csv
+  --level <L>       keep entries of level L (V, D, I, W, E, A) and above
+  --tag <tag>       keep entries whose tag contains <tag>; repeatable
+  --grep <text>     keep entries whose message, tag or process contains <text>
+  --regex           treat --tag and --grep values as regexes";
+
+/// Options of `session convert`
+#[derive(Debug)]
+struct ConvertArgs {
+    input: String,
+    output: String,
+    from: Option<ConvertFormat>,
+    to: Option<ConvertFormat>,
+    filter: FilterConfig,
+}
+
+fn parse_convert_args(args: &[String]) -> Result<ConvertArgs, String> {
+    let mut paths = Vec::new();
+    let mut from = None;
+    let mut to = None;
+    let mut filter = FilterConfig::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--from" => from = Some(ConvertFormat::parse(value()?)?),
+            "--to" => to = Some(ConvertFormat::parse(value()?)?),
+            "--level" => {
+                let level = value()?
+                    .chars()
+                    .next()
+                    .and_then(|c| LogLevel::from_char(c.to_ascii_uppercase()))
+                    .ok_or_else(|| "Level must be one of V, D, I, W, E, A".to_string())?;
+                filter.levels.retain(|l| *l >= level);
+            }
+            "--tag" => filter.tags.push(value()?.clone()),
+            "--grep" => filter.search_text = value()?.clone(),
+            "--regex" => {
+                filter.is_regex = true;
+                filter.tag_match.mode = MatchMode::Regex;
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            path => paths.push(path.to_string()),
+        }
+    }
+    let [input, output] = <[String; 2]>::try_from(paths)
+        .map_err(|_| "Expected an input and an output file".to_string())?;
+    Ok(ConvertArgs {
+        input,
+        output,
+        from,
+        to,
+        filter,
+    })
+}
+
+fn run_convert(args: &[String]) -> Result<(), String> {
+    let args = parse_convert_args(args)?;
+    let input = Path::new(&args.input);
+    let output = Path::new(&args.output);
+    let from = args.from.unwrap_or_else(|| ConvertFormat::from_path(input));
+    let to = args.to.unwrap_or_else(|| ConvertFormat::from_path(output));
+    let filter = LogFilter::unbudgeted(args.filter);
+    let summary = convert::convert((input, from), (output, to), &filter)?;
+    eprintln!(
+        "Converted {} of {} entries to {} ({} unparsable lines skipped)",
+        summary.written,
+        summary.read,
+        output.display(),
+        summary.skipped
+    );
+    Ok(())
+}
+
+/// Run a command-line subcommand without starting the UI. Returns the exit
+/// code, or None when `args` (including the program name) hold no subcommand.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.get(1..3) {
+        Some([group, command]) if group == "session" && command == "convert" => {
+            match run_convert(&args[3..]) {
+                Ok(()) => Some(0),
+                Err(e) => {
+                    eprintln!("{}\n\n{}", e, USAGE);
+                    Some(2)
+                }
+            }
+        }
+        _ if args.get(1).is_some_and(|arg| arg == "session") => {
+            eprintln!("{}", USAGE);
+            Some(2)
+        }
+        _ => None,
+    }
+}
+
+/// Launch profile given as `--profile <file>`
+pub fn launch_profile(args: &[String]) -> Result<Option<LaunchProfile>, String> {
+    let Some(index) = args.iter().position(|arg| arg == "--profile") else {
+        return Ok(None);
+    };
+    let path = args
+        .get(index + 1)
+        .ok_or_else(|| "Missing value for --profile".to_string())?;
+    profile::load(Path::new(path)).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_convert_args() {
+        let args = parse_convert_args(&strings(&[
+            "in.log", "out.txt", "--to", "csv", "--level", "w", "--tag", "Checkout",
+        ]))
+        .unwrap();
+        assert_eq!((args.input.as_str(), args.output.as_str()), ("in.log", "out.txt"));
+        assert_eq!(args.to, Some(ConvertFormat::Csv));
+        assert_eq!(args.filter.levels, vec![LogLevel::W, LogLevel::E, LogLevel::A]);
+        assert_eq!(args.filter.tags, vec!["Checkout"]);
+
+        assert!(parse_convert_args(&strings(&["in.log"])).is_err());
+        assert!(parse_convert_args(&strings(&["a", "b", "--level"])).is_err());
+        assert_eq!(run(&strings(&["logcat"])), None);
+        assert_eq!(run(&strings(&["logcat", "session", "merge"])), Some(2));
+        assert_eq!(launch_profile(&strings(&["logcat"])), Ok(None));
+        assert!(launch_profile(&strings(&["logcat", "--profile"])).is_err());
+    }
+}
@@ -1,27 +1,155 @@
-use log::{error, info};
+use log::{error, info, warn};
 use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use tokio::process::Child;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder, Window, WindowEvent};
+use tokio::io::BufReader;
+use tokio::process::{Child, ChildStderr};
 use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
 
-use crate::adb::{AdbManager, Device, ProcessInfo};
-use crate::parser::LogEntry;
+use crate::alert::AlertEngine;
+use crate::analysis::{DeviceAnalysis, Findings};
+use crate::appstate::{self, AppSnapshot, StreamSnapshot};
+use crate::audit::AdbInvocation;
+use crate::adb::{self, AdbManager, AdbReplay, Device, DeviceCapabilities, DeviceState, ProcessInfo, RootStatus};
+use crate::channels::ChannelMask;
+use crate::correlation::CorrelationExtractor;
+use crate::crash::{self, CrashReport, CrashSignature, SignatureMatcher};
+use crate::crashloop::{self, CrashLoop, CrashLoopDetector, CrashLoopExported};
+use crate::custom_source::{self, CustomParser, CustomSourceHandle, DeviceFileSource, SourceFormat};
+use crate::diagnostics;
+use crate::editor;
+use crate::environment::{self, EnvironmentStatus};
+use crate::fold::{FoldGroup, FoldRule, FoldTracker};
+use crate::filter::{self, FilterConfig, FilterValidation, LogFilter, RuleCost};
+use crate::follow;
+use crate::highlight::{self, ActiveSearch, EntryMatches};
+use crate::history::{self, Direction, EntryHistory, FilterPreview, Relation};
+use crate::ios::{self, IosSyslogSource};
+use crate::jank::JankStats;
+use crate::lifecycle::LifecycleEvent;
+use crate::links;
+use crate::logd::LogdStats;
+use crate::metrics::Metrics;
+use crate::noise::{self, NoiseFilter, QuickToggle};
+use crate::palette;
+use crate::pairing::{self, PairingPayload, PairingStatus};
+use crate::parser::{self, LogEntry, LogLevel};
+use crate::perfetto;
+use crate::pidcat;
+use crate::power::PowerSummary;
+use crate::projection::FieldMask;
+use crate::profile;
+use crate::props::{DebugProp, DebugToggle};
+use crate::redaction::{self, RedactionPreview, Redactor};
+use crate::retention::{self, PruneReport, StorageUsage};
+use crate::sessions::{self, SessionFilter, SessionInfo, SessionMetadata};
+use crate::settings::{self, Settings};
+use crate::sink::{self, FileSink};
+use crate::source::{self, LineParser, LineReader, LogSource};
+use crate::testrun::TestCase;
+use crate::spans::{Span, SpanRule, SpanStats, SpanTracker};
+use crate::streamstats::{self, AnomalyDetector, RateMeter, StreamStats, StreamStatsReport};
+use crate::startup::{self, StartupHistory, StartupMeasurement};
+use crate::tagfamily::FamilyNode;
+use crate::tasks::{TaskHealth, TaskSet};
+use crate::secrets::SecretReport;
+use crate::series::{MessageSeries, MessageTemplate};
+use crate::thermal::{self, ThermalSnapshot};
+use crate::timeseries::{GroupBy, TimeSeries};
+use crate::timezone::DisplayTimezone;
+use crate::tray;
+use crate::workspace::{self, AlertRule, Workspace};
 
 /// Global ADB manager instance
 static ADB_MANAGER: Lazy<AdbManager> = Lazy::new(AdbManager::new);
 
+/// Serializes read-modify-write of the startup history file
+static STARTUP_HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Package follow tasks keyed by (device ID, package)
+type FollowTasks = HashMap<(String, String), JoinHandle<()>>;
+
+/// Label of the window created from tauri.conf.json
+pub const MAIN_WINDOW: &str = "main";
+
+/// A running device stream and the window it feeds
+pub struct DeviceStream {
+    child: Child,
+    running: Arc<AtomicBool>,
+    /// Injects synthetic entries (markers); weak so a dead source still closes the channel
+    sender: mpsc::WeakSender<LogEntry>,
+    /// Read/parse/latency counters, reported as `stream-stats`
+    stats: Arc<StreamStats>,
+    /// Emit entries one by one instead of batching, see `set_low_latency`
+    low_latency: Arc<AtomicBool>,
+    pub window: String,
+}
+
 /// Logcat process state
 pub struct LogcatState {
-    pub process: Arc<Mutex<Option<Child>>>,
-    pub is_running: Arc<Mutex<bool>>,
+    /// Running streams keyed by device ID
+    pub streams: Arc<Mutex<HashMap<String, DeviceStream>>>,
+    /// Power, lifecycle, crash, test, span, fold... analysis keyed by device ID
+    pub analysis: Arc<Mutex<HashMap<String, DeviceAnalysis>>>,
+    /// Custom sources (commands or TCP listeners) keyed by name
+    pub custom_sources: Arc<Mutex<HashMap<String, CustomSourceHandle>>>,
+    /// Optional entry fields included in emitted batches (`FieldMask` bits)
+    pub emit_fields: Arc<AtomicU8>,
+    /// Event channels the frontend subscribed to (`ChannelMask` bits)
+    pub event_channels: Arc<AtomicU8>,
+    /// Alert rules evaluated against every streamed entry
+    pub alerts: Arc<Mutex<AlertEngine>>,
+    /// Cumulative stream health counters, kept across sessions
+    pub metrics: Arc<Mutex<Metrics>>,
+    /// Recent entries for filter previews keyed by device ID
+    pub history: Arc<Mutex<HashMap<String, EntryHistory>>>,
+    /// Imported known-crash signatures
+    pub crash_signatures: Arc<Mutex<SignatureMatcher>>,
+    /// Running package follow tasks
+    pub follows: Arc<Mutex<FollowTasks>>,
+    /// Discard incoming entries on every stream (tray pause)
+    pub paused: Arc<AtomicBool>,
+    /// Metadata given when a device's capture started, keyed by device ID
+    pub session_metadata: Arc<Mutex<HashMap<String, SessionMetadata>>>,
+    /// Active search whose match spans accompany each batch, keyed by device ID
+    pub highlights: Arc<Mutex<HashMap<String, ActiveSearch>>>,
+    /// Pinned entries kept visible regardless of filters, keyed by device ID
+    pub pins: Arc<Mutex<HashMap<String, Vec<LogEntry>>>>,
+    /// Kernel boot ID seen when a device's stream last started, to spot reboots
+    pub boot_ids: Arc<Mutex<HashMap<String, String>>>,
+    /// Correlation ID patterns applied to every streamed entry
+    pub correlation: Arc<Mutex<CorrelationExtractor>>,
+    /// Files the next stream of a device is written to, taken when it starts
+    pub sinks: Arc<Mutex<HashMap<String, FileSink>>>,
+    /// Background tasks of the streams and the device monitor
+    pub tasks: Arc<TaskSet>,
 }
 
 impl Default for LogcatState {
     fn default() -> Self {
         LogcatState {
-            process: Arc::new(Mutex::new(None)),
-            is_running: Arc::new(Mutex::new(false)),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            analysis: Arc::new(Mutex::new(HashMap::new())),
+            custom_sources: Arc::new(Mutex::new(HashMap::new())),
+            emit_fields: Arc::new(AtomicU8::new(FieldMask::ALL.bits())),
+            event_channels: Arc::new(AtomicU8::new(ChannelMask::ALL.bits())),
+            alerts: Arc::new(Mutex::new(AlertEngine::new())),
+            metrics: Arc::new(Mutex::new(Metrics::new())),
+            history: Arc::new(Mutex::new(HashMap::new())),
+            crash_signatures: Arc::new(Mutex::new(SignatureMatcher::default())),
+            follows: Arc::new(Mutex::new(HashMap::new())),
+            paused: Arc::new(AtomicBool::new(false)),
+            session_metadata: Arc::new(Mutex::new(HashMap::new())),
+            highlights: Arc::new(Mutex::new(HashMap::new())),
+            pins: Arc::new(Mutex::new(HashMap::new())),
+            boot_ids: Arc::new(Mutex::new(HashMap::new())),
+            correlation: Arc::new(Mutex::new(CorrelationExtractor::default())),
+            sinks: Arc::new(Mutex::new(HashMap::new())),
+            tasks: Arc::new(TaskSet::default()),
         }
     }
 }
@@ -32,11 +160,148 @@ pub async fn check_adb() -> Result<bool, String> {
     ADB_MANAGER.check_adb().await
 }
 
+/// logd buffer usage and chattiest UIDs, to spot quota pruning
+#[tauri::command]
+pub async fn get_logd_stats(device_id: String) -> Result<LogdStats, String> {
+    ADB_MANAGER.logd_stats(&device_id).await
+}
+
+/// Whether adbd runs, or may run, as root on the device
+#[tauri::command]
+pub async fn get_root_status(device_id: String) -> Result<RootStatus, String> {
+    ADB_MANAGER.root_status(&device_id).await
+}
+
+/// Restart adbd as root to read privileged sources; fails on production builds
+#[tauri::command]
+pub async fn restart_adbd_root(device_id: String) -> Result<RootStatus, String> {
+    info!("Restarting adbd as root on {}", device_id);
+    ADB_MANAGER.restart_adbd(&device_id, true).await
+}
+
+/// Restart adbd without root
+#[tauri::command]
+pub async fn restart_adbd_unroot(device_id: String) -> Result<RootStatus, String> {
+    info!("Restarting adbd without root on {}", device_id);
+    ADB_MANAGER.restart_adbd(&device_id, false).await
+}
+
+/// Restart the adb server with new host keys so unauthorized devices prompt again
+#[tauri::command]
+pub async fn restart_adb_with_revoked_keys() -> Result<Vec<String>, String> {
+    ADB_MANAGER.restart_with_revoked_keys().await
+}
+
+/// Curated debugging props (`debug.hwui.*`, `log.tag.*`, ...) and their values
+#[tauri::command]
+pub async fn get_debug_props(device_id: String) -> Result<Vec<DebugProp>, String> {
+    ADB_MANAGER.debug_props(&device_id).await
+}
+
+/// Read one prop from the debugging prop list
+#[tauri::command]
+pub async fn get_prop(device_id: String, key: String) -> Result<String, String> {
+    ADB_MANAGER.get_prop(&device_id, &key).await
+}
+
+/// Write one prop from the debugging prop list; an empty value clears it
+#[tauri::command]
+pub async fn set_prop(device_id: String, key: String, value: String) -> Result<String, String> {
+    ADB_MANAGER.set_prop(&device_id, &key, &value).await?;
+    ADB_MANAGER.get_prop(&device_id, &key).await
+}
+
+/// Switch a Developer options toggle and mark the moment in the device's
+/// stream, so log changes that follow can be attributed to the switch.
+/// Returns the marker note.
+#[tauri::command]
+pub async fn set_debug_toggle(
+    device_id: String,
+    toggle: DebugToggle,
+    enabled: bool,
+    state: State<'_, LogcatState>,
+) -> Result<String, String> {
+    ADB_MANAGER.apply_debug_toggle(&device_id, toggle, enabled).await?;
+    let note = format!("{} {}", toggle.label(), if enabled { "on" } else { "off" });
+    let sender = state
+        .streams
+        .lock()
+        .await
+        .get(&device_id)
+        .and_then(|stream| stream.sender.upgrade());
+    if let Some(sender) = sender {
+        let _ = sender.send(marker_entry(&note, chrono::Local::now())).await;
+    }
+    Ok(note)
+}
+
+/// adb invocations made by the app (arguments, duration, exit code), oldest first
+#[tauri::command]
+pub async fn get_adb_history() -> Result<Vec<AdbInvocation>, String> {
+    Ok(ADB_MANAGER.history())
+}
+
+/// Read, parse and latency counters of every running stream; the same
+/// reports are emitted as `stream-stats` every few seconds
+#[tauri::command]
+pub async fn get_stream_stats(
+    state: State<'_, LogcatState>,
+) -> Result<Vec<StreamStatsReport>, String> {
+    let streams = state.streams.lock().await;
+    let mut reports: Vec<StreamStatsReport> = streams
+        .iter()
+        .map(|(device_id, stream)| stream.stats.report(device_id))
+        .collect();
+    reports.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    Ok(reports)
+}
+
+/// Liveness of the background tasks (device monitor, per-stream readers and
+/// process-cache refreshes), with their panic restarts
+#[tauri::command]
+pub async fn get_task_health(state: State<'_, LogcatState>) -> Result<Vec<TaskHealth>, String> {
+    Ok(state.tasks.health())
+}
+
+/// Run a recorded adb invocation again and return its output. With an
+/// `operation_id`, `cancel_operation` can stop it.
+#[tauri::command]
+pub async fn rerun_adb_command(
+    id: u64,
+    operation_id: Option<String>,
+) -> Result<AdbReplay, String> {
+    let operation = operation_id.map(|op| ADB_MANAGER.begin_operation(&op));
+    ADB_MANAGER.rerun(id, operation.as_ref().map(|op| op.token())).await
+}
+
+/// Stop a long-running operation started with this `operation_id`; its
+/// command fails with a "cancelled" error. False if it already finished.
+#[tauri::command]
+pub async fn cancel_operation(operation_id: String) -> Result<bool, String> {
+    info!("Cancelling operation {}", operation_id);
+    Ok(ADB_MANAGER.cancel_operation(&operation_id))
+}
+
+/// adb, adb server and device status for the setup checklist
+#[tauri::command]
+pub async fn get_environment_status() -> Result<EnvironmentStatus, String> {
+    Ok(environment::check(&ADB_MANAGER).await)
+}
+
 /// Get list of connected devices
 #[tauri::command]
 pub async fn get_devices() -> Result<Vec<Device>, String> {
     info!("Getting device list");
-    ADB_MANAGER.get_devices().await
+    let mut devices = ADB_MANAGER.get_devices().await?;
+    let groups = settings::current().device_groups;
+    for device in &mut devices {
+        device.groups = groups
+            .iter()
+            .filter(|g| g.devices.contains(&device.id))
+            .map(|g| g.name.clone())
+            .collect();
+    }
+    Ok(devices)
 }
 
 /// Get processes running on a device
@@ -46,61 +311,630 @@ pub async fn get_processes(device_id: String) -> Result<Vec<ProcessInfo>, String
     ADB_MANAGER.get_processes(&device_id).await
 }
 
-/// Start logcat streaming for a device
+/// Get the detected command capabilities (SDK level, ps/logcat options) of a device
 #[tauri::command]
-pub async fn start_logcat(
+pub async fn get_device_capabilities(device_id: String) -> Result<DeviceCapabilities, String> {
+    Ok(ADB_MANAGER.capabilities(&device_id).await)
+}
+
+/// Switch a USB device to adb over Wi-Fi; returns the new "ip:port" serial
+#[tauri::command]
+pub async fn switch_to_tcpip(device_id: String, port: Option<u16>) -> Result<String, String> {
+    info!("Switching device to tcpip: {}", device_id);
+    ADB_MANAGER
+        .switch_to_tcpip(&device_id, port.unwrap_or(5555))
+        .await
+}
+
+/// Start a QR pairing attempt: the frontend renders `qrContent` for the phone's
+/// "Pair device with QR code" scanner, then calls `complete_qr_pairing`
+#[tauri::command]
+pub async fn create_qr_pairing() -> Result<PairingPayload, String> {
+    Ok(pairing::new_payload())
+}
+
+/// Wait (default 120 s) for the phone that scanned the QR code to advertise its
+/// pairing service over mDNS, then run `adb pair`. Progress is reported with
+/// `qr-pairing` events; returns the paired "ip:port".
+#[tauri::command]
+pub async fn complete_qr_pairing(
+    app: AppHandle,
+    service_name: String,
+    password: String,
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    let status = |stage: &str, message: Option<String>| {
+        let payload = PairingStatus {
+            service_name: service_name.clone(),
+            stage: stage.to_string(),
+            message,
+        };
+        if let Err(e) = app.emit("qr-pairing", &payload) {
+            error!("Failed to emit pairing status: {}", e);
+        }
+    };
+
+    status("waiting", None);
+    let deadline = std::time::Instant::now()
+        + std::time::Duration::from_secs(timeout_secs.unwrap_or(120));
+    let address = loop {
+        let services = ADB_MANAGER.mdns_services().await?;
+        if let Some(address) = pairing::pairing_address(&services, &service_name) {
+            break address;
+        }
+        if std::time::Instant::now() >= deadline {
+            let message = "Timed out waiting for the device to scan the QR code".to_string();
+            status("failed", Some(message.clone()));
+            return Err(message);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    };
+
+    status("pairing", Some(address.clone()));
+    match ADB_MANAGER.pair(&address, &password).await {
+        Ok(()) => {
+            status("paired", Some(address.clone()));
+            Ok(address)
+        }
+        Err(e) => {
+            status("failed", Some(e.clone()));
+            Err(e)
+        }
+    }
+}
+
+/// Keep watching a package across uninstall/reinstall and process restarts,
+/// emitting `package-follow` events; the frontend moves a PID filter on the
+/// package's old process to the new one
+#[tauri::command]
+pub async fn follow_package(
     app: AppHandle,
     device_id: String,
+    package: String,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    follow::validate_package(&package)?;
+    let mut follows = state.follows.lock().await;
+    let key = (device_id.clone(), package.clone());
+    if follows.contains_key(&key) {
+        return Ok(());
+    }
+    let task = follow::spawn(app, &ADB_MANAGER, device_id, package);
+    follows.insert(key, task);
+    Ok(())
+}
+
+/// Stop following a package
+#[tauri::command]
+pub async fn unfollow_package(
+    device_id: String,
+    package: String,
     state: State<'_, LogcatState>,
 ) -> Result<(), String> {
+    info!("Unfollowing {} on {}", package, device_id);
+    if let Some(task) = state.follows.lock().await.remove(&(device_id, package)) {
+        task.abort();
+    }
+    Ok(())
+}
+
+/// Start logcat streaming for a device.
+/// `filter_specs` (e.g. `["ActivityManager:I", "*:S"]`) are passed to logcat on
+/// the device to cut transfer volume; analyzers only see what the device sends.
+/// `metadata` is kept for `save_session`. Returns true when the device was
+/// already streaming in the calling window (started for it by the backend);
+/// the window then gets the entries so far from `attach_logcat`.
+#[tauri::command]
+pub async fn start_logcat(
+    app: AppHandle,
+    window: Window,
+    device_id: String,
+    filter_specs: Option<Vec<String>>,
+    metadata: Option<SessionMetadata>,
+    state: State<'_, LogcatState>,
+) -> Result<bool, String> {
     info!("Starting logcat for device: {}", device_id);
+    let filter_specs = filter_specs.unwrap_or_default();
+    for spec in &filter_specs {
+        adb::validate_filterspec(spec)?;
+    }
+    let streaming_here = state
+        .streams
+        .lock()
+        .await
+        .get(&device_id)
+        .is_some_and(|stream| stream.window == window.label());
+    if streaming_here {
+        info!("Device {} is already streaming in this window", device_id);
+        return Ok(true);
+    }
+    start_logcat_internal(&app, window.label(), device_id.clone(), (&filter_specs, &[]), false, &state).await?;
+    let mut sessions = state.session_metadata.lock().await;
+    match metadata {
+        Some(metadata) => sessions.insert(device_id, metadata),
+        None => sessions.remove(&device_id),
+    };
+    Ok(false)
+}
 
-    // Check if already running
-    {
-        let is_running = state.is_running.lock().await;
-        if *is_running {
-            return Err("Logcat is already running".to_string());
+/// Attach the calling window to a stream the backend started for it (see
+/// `stream-autostarted`), returning the entries streamed so far. The window
+/// should listen for batches first and drop those already in the result.
+#[tauri::command]
+pub async fn attach_logcat(
+    window: Window,
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<LogEntry>, String> {
+    match state.streams.lock().await.get(&device_id) {
+        Some(stream) if stream.window == window.label() => {}
+        Some(stream) => {
+            return Err(format!(
+                "Device {} is streaming in window {}",
+                device_id, stream.window
+            ))
         }
+        None => return Err(format!("No log stream for device: {}", device_id)),
     }
+    let history = state.history.lock().await;
+    let mask = FieldMask::from_bits(state.emit_fields.load(Ordering::Relaxed));
+    let mut entries: Vec<LogEntry> = history
+        .get(&device_id)
+        .map(|store| store.entries().cloned().collect())
+        .unwrap_or_default();
+    drop(history);
+    for entry in &mut entries {
+        mask.apply(entry);
+    }
+    Ok(entries)
+}
 
-    // Stop any existing process
-    stop_logcat_internal(&state).await?;
+/// Payload of the `boot-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootProgress {
+    pub device_id: String,
+    /// "waiting-device", "booting", "completed" or "timeout"
+    pub stage: String,
+    pub elapsed_ms: u64,
+    /// A stream was started for the boot (only set once it runs)
+    pub capturing: bool,
+}
 
-    // Create channel for log entries
-    let (tx, mut rx) = mpsc::channel::<LogEntry>(1000);
+/// Wait until the device is online and `sys.boot_completed` is set, emitting
+/// `boot-progress` on every stage change. With `capture`, streaming starts in the
+/// calling window as soon as adb sees the device, keeping the buffered boot logs.
+/// Gives up after `timeout_secs` (default 300); returns the boot time in ms.
+#[tauri::command]
+pub async fn wait_for_boot(
+    app: AppHandle,
+    window: Window,
+    device_id: String,
+    capture: Option<bool>,
+    timeout_secs: Option<u64>,
+    state: State<'_, LogcatState>,
+) -> Result<u64, String> {
+    info!("Waiting for {} to boot", device_id);
+    let started = std::time::Instant::now();
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(300));
+    let mut capturing = false;
+    let mut last_stage = "";
+    loop {
+        let online = ADB_MANAGER
+            .get_devices()
+            .await?
+            .iter()
+            .any(|d| d.id == device_id && d.state == DeviceState::Device);
+        if online && capture.unwrap_or(false) && !capturing {
+            start_logcat_internal(&app, window.label(), device_id.clone(), (&[], &[]), true, &state).await?;
+            capturing = true;
+        }
+        let stage = if online && ADB_MANAGER.boot_completed(&device_id).await {
+            "completed"
+        } else if started.elapsed() >= timeout {
+            "timeout"
+        } else if online {
+            "booting"
+        } else {
+            "waiting-device"
+        };
+
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        if stage != last_stage {
+            let progress = BootProgress {
+                device_id: device_id.clone(),
+                stage: stage.to_string(),
+                elapsed_ms,
+                capturing,
+            };
+            if let Err(e) = app.emit("boot-progress", &progress) {
+                error!("Failed to emit boot progress: {}", e);
+            }
+            last_stage = stage;
+        }
+        match stage {
+            "completed" => {
+                info!("{} booted after {} ms", device_id, elapsed_ms);
+                return Ok(elapsed_ms);
+            }
+            "timeout" => return Err(format!("Device {} did not finish booting", device_id)),
+            _ => tokio::time::sleep(std::time::Duration::from_secs(1)).await,
+        }
+    }
+}
+
+/// Payload of the `stream-autostarted` event
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamAutostarted {
+    #[serde(rename = "deviceId")]
+    pub device_id: String,
+    /// What started it: "pinned", "authorized" or "profile"
+    pub reason: &'static str,
+    #[serde(rename = "packageName", skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+    /// Filter query the frontend should apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+/// Start streaming a device in the main window unless it already has a stream.
+/// Empty `buffers` reads the default ones.
+async fn autostart_main(
+    app: &AppHandle,
+    device_id: &str,
+    buffers: &[String],
+    event: StreamAutostarted,
+) -> bool {
+    let state = app.state::<LogcatState>();
+    let main_busy = state
+        .streams
+        .lock()
+        .await
+        .values()
+        .any(|stream| stream.window == MAIN_WINDOW);
+    if main_busy {
+        info!("Device {} connected but a stream is already running", device_id);
+        return false;
+    }
+
+    info!("Auto-starting logcat for device: {}", device_id);
+    let started =
+        start_logcat_internal(app, MAIN_WINDOW, device_id.to_string(), (&[], buffers), false, &state).await;
+    if let Err(e) = started {
+        error!("Failed to auto-start logcat: {}", e);
+        return false;
+    }
+
+    if let Err(e) = app.emit("stream-autostarted", &event) {
+        error!("Failed to emit stream-autostarted: {}", e);
+    }
+    true
+}
+
+/// Start the capture described by the launch profile when a matching device
+/// connects, with its buffers, filter and file sink
+pub async fn autostart_profile(app: &AppHandle, device: &Device) {
+    let Some(profile) = profile::active().filter(|p| p.matches(&device.id)) else {
+        return;
+    };
+
+    let state = app.state::<LogcatState>();
+    if let Some(template) = &profile.sink_path {
+        let path = sink::expand_path(template, &device.id, chrono::Local::now());
+        match FileSink::open(&path) {
+            Ok(sink) => {
+                info!("Writing {} to {}", device.id, path.display());
+                state.sinks.lock().await.insert(device.id.clone(), sink);
+            }
+            Err(e) => error!("Ignoring launch profile sink: {}", e),
+        }
+    }
+    let event = StreamAutostarted {
+        device_id: device.id.clone(),
+        reason: "profile",
+        package_name: None,
+        filter: profile.filter.clone(),
+    };
+    if !autostart_main(app, &device.id, &profile.buffers, event).await {
+        state.sinks.lock().await.remove(&device.id);
+    }
+}
+
+/// Use the launch profile's alert rules, if it has any, over the workspace's
+pub async fn apply_launch_profile(app: &AppHandle) {
+    let Some(profile) = profile::active() else {
+        return;
+    };
+    info!("Launch profile active for device: {}", profile.device);
+    if profile.alert_rules.is_empty() {
+        return;
+    }
+    let state = app.state::<LogcatState>();
+    if let Err(e) = state.alerts.lock().await.set_rules(&profile.alert_rules) {
+        error!("Failed to apply launch profile alert rules: {}", e);
+    }
+}
+
+/// Start streaming automatically when the pinned device connects
+pub async fn autostart_if_pinned(app: &AppHandle, device: &Device) {
+    let config = settings::current();
+    if config.pinned_device.as_deref() != Some(device.id.as_str()) {
+        return;
+    }
+
+    let event = StreamAutostarted {
+        device_id: device.id.clone(),
+        reason: "pinned",
+        filter: config
+            .pinned_filter
+            .clone()
+            .or_else(|| config.pinned_package.as_ref().map(|p| format!("package:{}", p))),
+        package_name: config.pinned_package,
+    };
+    autostart_main(app, &device.id, &[], event).await;
+}
+
+/// Start streaming a device once its USB debugging prompt is accepted
+pub async fn autostart_if_authorized(app: &AppHandle, device: &Device) {
+    let config = settings::current();
+    if !config.stream_on_authorize || config.pinned_device.as_deref() == Some(device.id.as_str()) {
+        // The pinned device is started by `autostart_if_pinned`
+        return;
+    }
+
+    let event = StreamAutostarted {
+        device_id: device.id.clone(),
+        reason: "authorized",
+        package_name: None,
+        filter: None,
+    };
+    autostart_main(app, &device.id, &[], event).await;
+}
+
+/// Internal function to start logcat
+async fn start_logcat_internal(
+    app: &AppHandle,
+    window: &str,
+    device_id: String,
+    (filter_specs, buffers): (&[String], &[String]),
+    keep_buffer: bool,
+    state: &LogcatState,
+) -> Result<(), String> {
+    // After a reboot the capture goes on as one timeline: history is kept, the
+    // new boot's buffered lines are streamed after a marker, and the parser
+    // picks up the device's UTC offset again
+    let boot_id = ADB_MANAGER.boot_id(&device_id).await;
+    let rebooted = match &boot_id {
+        Some(id) => state
+            .boot_ids
+            .lock()
+            .await
+            .insert(device_id.clone(), id.clone())
+            .is_some_and(|previous| previous != *id),
+        None => false,
+    };
+    let (tx, rx, config) = prepare_stream(&device_id, window, rebooted, state).await?;
+    let sender = tx.downgrade();
+    if rebooted {
+        info!("Device {} rebooted, continuing its capture", device_id);
+        let _ = tx.try_send(marker_entry("Device rebooted", chrono::Local::now()));
+        let payload = DeviceRebooted {
+            device_id: device_id.clone(),
+        };
+        if let Err(e) = app.emit("device-rebooted", &payload) {
+            error!("Failed to emit device-rebooted: {}", e);
+        }
+    }
 
     // Start logcat process
+    let stats = Arc::new(StreamStats::default());
     let child = ADB_MANAGER
-        .start_logcat(&device_id, tx)
+        .start_logcat(
+            &device_id,
+            (filter_specs, buffers),
+            keep_buffer || rebooted,
+            tx,
+            (state.metrics.clone(), stats.clone()),
+            &state.tasks,
+        )
         .await?;
 
-    // Store process handle
-    {
-        let mut process = state.process.lock().await;
-        *process = Some(child);
+    if config.thermal_poll_secs > 0 {
+        thermal::spawn(
+            app.clone(),
+            &ADB_MANAGER,
+            device_id.clone(),
+            (sender.clone(), state.event_channels.clone()),
+            config.thermal_poll_secs,
+        );
     }
+    run_stream(app, device_id, window, state, (child, sender, stats), rx, config).await;
+    Ok(())
+}
+
+/// Payload of the `device-rebooted` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRebooted {
+    pub device_id: String,
+}
+
+/// Start streaming from a non-adb log source (e.g. iOS syslog)
+async fn start_source_internal(
+    app: &AppHandle,
+    window: &str,
+    device_id: String,
+    source: &dyn LogSource,
+    state: &LogcatState,
+) -> Result<(), String> {
+    let (tx, rx, config) = prepare_stream(&device_id, window, false, state).await?;
+    let sender = tx.downgrade();
+    let child = source::spawn(source, tx)?;
+    // Only latency is measured; the source's reader doesn't report line counts
+    let stats = Arc::new(StreamStats::default());
+    run_stream(app, device_id, window, state, (child, sender, stats), rx, config).await;
+    Ok(())
+}
 
-    // Mark as running
+/// Check the window and device are free, reset analyzers and create the entry
+/// channel. `keep_history` continues the device's entry history.
+async fn prepare_stream(
+    device_id: &str,
+    window: &str,
+    keep_history: bool,
+    state: &LogcatState,
+) -> Result<(mpsc::Sender<LogEntry>, mpsc::Receiver<LogEntry>, Settings), String> {
+    // One stream per window, one window per device
     {
-        let mut is_running = state.is_running.lock().await;
-        *is_running = true;
+        let streams = state.streams.lock().await;
+        if let Some(stream) = streams.get(device_id) {
+            return Err(format!(
+                "Device {} is already streaming in window {}",
+                device_id, stream.window
+            ));
+        }
+        if streams.values().any(|stream| stream.window == window) {
+            return Err("Logcat is already running".to_string());
+        }
+    }
+
+    // A new stream starts over (usually from a cleared buffer), so previous analysis is stale
+    let config = settings::current();
+    state
+        .analysis
+        .lock()
+        .await
+        .insert(device_id.to_string(), DeviceAnalysis::new(&config));
+    let mut history = state.history.lock().await;
+    if !keep_history || !history.contains_key(device_id) {
+        history.insert(device_id.to_string(), EntryHistory::new(config.history_lines));
+    }
+    drop(history);
+    match CorrelationExtractor::new(&config.correlation_patterns) {
+        Ok(extractor) => *state.correlation.lock().await = extractor,
+        Err(e) => error!("Ignoring correlation patterns: {}", e),
+    }
+
+    // Create channel for log entries
+    let (tx, rx) = mpsc::channel::<LogEntry>(config.channel_capacity.max(1));
+    Ok((tx, rx, config))
+}
+
+/// Payload of the `stream-warning` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamWarning {
+    pub device_id: String,
+    /// Line the stream process printed on stderr
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<&'static str>,
+}
+
+/// Log a stream process's stderr and surface it as `stream-warning` events
+fn drain_stderr(app: AppHandle, device_id: String, stderr: ChildStderr, tasks: &TaskSet) {
+    let owner = device_id.clone();
+    tasks.spawn("stderr", Some(&owner), async move {
+        let mut lines = LineReader::new(BufReader::new(stderr));
+        while let Ok(Some(line)) = lines.next_line().await {
+            let line = line.trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            warn!("Stream {} stderr: {}", device_id, line);
+            let warning = StreamWarning {
+                device_id: device_id.clone(),
+                hint: source::stderr_hint(&line),
+                message: line,
+            };
+            if let Err(e) = app.emit("stream-warning", &warning) {
+                error!("Failed to emit stream warning: {}", e);
+            }
+        }
+    });
+}
+
+/// Entries per second above which low-latency mode falls back to batching,
+/// since one event per entry would flood the frontend
+const LOW_LATENCY_MAX_RATE: u32 = 100;
+
+/// Store the stream process and spawn the task forwarding batches to the frontend
+async fn run_stream(
+    app: &AppHandle,
+    device_id: String,
+    window: &str,
+    state: &LogcatState,
+    (mut child, sender, stats): (Child, mpsc::WeakSender<LogEntry>, Arc<StreamStats>),
+    mut rx: mpsc::Receiver<LogEntry>,
+    config: Settings,
+) {
+    if let Some(stderr) = child.stderr.take() {
+        drain_stderr(app.clone(), device_id.clone(), stderr, &state.tasks);
     }
 
-    // Spawn task to forward logs to frontend
+    // Store process handle and mark as running
+    let running = Arc::new(AtomicBool::new(true));
+    let low_latency = Arc::new(AtomicBool::new(false));
+    state.streams.lock().await.insert(
+        device_id.clone(),
+        DeviceStream {
+            child,
+            running: running.clone(),
+            sender,
+            stats: stats.clone(),
+            low_latency: low_latency.clone(),
+            window: window.to_string(),
+        },
+    );
+
+    // Spawn task to forward logs to frontend. It isn't in the task set: it winds
+    // down through `running` so the last batch is still emitted after a stop
     let app_handle = app.clone();
-    let is_running = state.is_running.clone();
+    let window = window.to_string();
+    let streams = state.streams.clone();
+    let analysis = state.analysis.clone();
+    let emit_fields = state.emit_fields.clone();
+    let event_channels = state.event_channels.clone();
+    let alerts = state.alerts.clone();
+    let metrics = state.metrics.clone();
+    let history = state.history.clone();
+    let crash_signatures = state.crash_signatures.clone();
+    let paused = state.paused.clone();
+    let highlights = state.highlights.clone();
+    let correlation = state.correlation.clone();
+    let mut sink = state.sinks.lock().await.remove(&device_id);
     
     tokio::spawn(async move {
-        let mut batch: Vec<LogEntry> = Vec::with_capacity(100);
+        let mut batch: Vec<LogEntry> = Vec::with_capacity(config.batch_size);
+        // Entries taken off the channel together, analyzed under one lock each
+        let mut chunk: Vec<LogEntry> = Vec::with_capacity(config.batch_size);
         let mut last_emit = std::time::Instant::now();
+        let mut last_stats = std::time::Instant::now();
+        let mut rate = RateMeter::new();
+        let mut anomalies = AnomalyDetector::new(std::time::Instant::now());
+        let mut crash_loops = {
+            let settings = settings::current();
+            CrashLoopDetector::new(settings.crash_loop_threshold, settings.crash_loop_window_mins)
+        };
+        // Fold groups closed since the last batch, sent after it
+        let mut closed_folds: Vec<FoldGroup> = Vec::new();
+        let subscribed = |channel| {
+            ChannelMask::from_bits(event_channels.load(Ordering::Relaxed)).contains(channel)
+        };
         
         loop {
-            // Check if still running
-            {
-                let running = is_running.lock().await;
-                if !*running {
-                    break;
+            if last_stats.elapsed().as_secs() >= streamstats::STATS_INTERVAL_SECS {
+                if subscribed(ChannelMask::STATS) {
+                    if let Err(e) = app_handle.emit("stream-stats", stats.report(&device_id)) {
+                        error!("Failed to emit stream stats: {}", e);
+                    }
                 }
+                last_stats = std::time::Instant::now();
+            }
+
+            // Check if still running
+            if !running.load(Ordering::Relaxed) {
+                break;
             }
 
             // Try to receive logs with timeout
@@ -108,77 +942,1868 @@ pub async fn start_logcat(
                 std::time::Duration::from_millis(50),
                 rx.recv()
             ).await {
+                Ok(Some(_)) if paused.load(Ordering::Relaxed) => {}
                 Ok(Some(entry)) => {
-                    batch.push(entry);
-                    
-                    // Emit batch if large enough or enough time passed
-                    if batch.len() >= 50 || last_emit.elapsed().as_millis() > 100 {
-                        if let Err(e) = app_handle.emit("logcat-entries", &batch) {
-                            error!("Failed to emit logs: {}", e);
+                    // Take what else is queued, up to what the batch has room for
+                    chunk.push(entry);
+                    let room = config.batch_size.saturating_sub(batch.len()).max(1);
+                    while chunk.len() < room {
+                        match rx.try_recv() {
+                            Ok(entry) => chunk.push(entry),
+                            Err(_) => break,
+                        }
+                    }
+
+                    let extractor = correlation.lock().await;
+                    for entry in chunk.iter_mut() {
+                        entry.seq = parser::next_device_seq(&device_id);
+                        entry.device_color = Some(palette::color_index(&device_id));
+                        entry.package_color = entry.package_name.as_deref().map(palette::color_index);
+                        entry.correlation_id = extractor.extract(&entry.message);
+                        entry.links = links::detect(&entry.message);
+                    }
+                    drop(extractor);
+
+                    let mut findings = Findings::default();
+                    if let Some(device) = analysis.lock().await.get_mut(&device_id) {
+                        let now = std::time::Instant::now();
+                        device.observe(&device_id, &mut chunk, now, &mut findings);
+                    }
+                    let mut counters = metrics.lock().await;
+                    for entry in &chunk {
+                        counters.observe(&device_id, entry);
+                    }
+                    drop(counters);
+
+                    let entries_wanted = subscribed(ChannelMask::ENTRIES);
+                    let system_events = subscribed(ChannelMask::SYSTEM_EVENTS);
+                    if entries_wanted {
+                        closed_folds.append(&mut findings.folds);
+                    }
+                    for warning in findings.secrets.iter().filter(|_| system_events) {
+                        if let Err(e) = app_handle.emit("secret-detected", warning) {
+                            error!("Failed to emit secret warning: {}", e);
+                        }
+                    }
+                    for (event, epoch) in &findings.lifecycle {
+                        let epoch =
+                            epoch.unwrap_or_else(|| chrono::Local::now().timestamp_millis() as u64);
+                        if let Some(measurement) = StartupMeasurement::from_event(&device_id, event, epoch) {
+                            tokio::spawn(record_startup(measurement));
+                        }
+                    }
+                    for crash in findings.crashes {
+                        if let Some(crash_loop) = crash_loops.observe(&crash, std::time::Instant::now()) {
+                            let notify = subscribed(ChannelMask::CRASH);
+                            tokio::spawn(capture_crash_loop(app_handle.clone(), history.clone(), crash_loop, notify));
+                        }
+                        if subscribed(ChannelMask::CRASH) {
+                            emit_crash(&app_handle, &crash_signatures, crash).await;
+                        }
+                    }
+                    for event in findings.tests.iter().filter(|_| system_events) {
+                        if let Err(e) = app_handle.emit("test-event", event) {
+                            error!("Failed to emit test event: {}", e);
+                        }
+                    }
+                    for span in findings.spans.iter().filter(|_| system_events) {
+                        if let Err(e) = app_handle.emit("span", span) {
+                            error!("Failed to emit span: {}", e);
                         }
+                    }
+                    for event in findings.jank.iter().filter(|_| system_events) {
+                        if let Err(e) = app_handle.emit("jank-event", event) {
+                            error!("Failed to emit jank event: {}", e);
+                        }
+                    }
+
+                    let mut engine = alerts.lock().await;
+                    for entry in &chunk {
+                        let now = std::time::Instant::now();
+                        let anomaly = anomalies.observe(&device_id, entry, now);
+                        if let Some(anomaly) = anomaly.filter(|_| system_events) {
+                            if let Err(e) = app_handle.emit("anomaly-detected", &anomaly) {
+                                error!("Failed to emit anomaly: {}", e);
+                            }
+                        }
+                        for alert in engine.check(&device_id, entry) {
+                            if system_events {
+                                if let Err(e) = app_handle.emit("alert-fired", &alert) {
+                                    error!("Failed to emit alert: {}", e);
+                                }
+                            }
+                            tray::note_alert(&app_handle);
+                        }
+                        if let Some(file) = sink.as_mut() {
+                            if let Err(e) = file.write(entry) {
+                                error!("Failed to write {}: {}", file.path().display(), e);
+                                sink = None;
+                            }
+                        }
+                        if entry.marker && subscribed(ChannelMask::MARKERS) {
+                            if let Err(e) = app_handle.emit("marker", entry) {
+                                error!("Failed to emit marker: {}", e);
+                            }
+                        }
+                        rate.record(now);
+                    }
+                    drop(engine);
+
+                    // History keeps the full entries; emitted ones are masked copies
+                    if let Some(store) = history.lock().await.get_mut(&device_id) {
+                        if entries_wanted {
+                            chunk.iter().for_each(|entry| store.push(entry.clone()));
+                        } else {
+                            chunk.drain(..).for_each(|entry| store.push(entry));
+                        }
+                    }
+                    if !entries_wanted {
+                        chunk.clear();
+                        continue;
+                    }
+                    let mask = FieldMask::from_bits(emit_fields.load(Ordering::Relaxed));
+                    for mut entry in chunk.drain(..) {
+                        mask.apply(&mut entry);
+                        batch.push(entry);
+                    }
+                    
+                    // Emit batch if large enough or enough time passed, or right
+                    // away in low-latency mode while the stream is quiet
+                    if batch.len() >= config.batch_size
+                        || last_emit.elapsed().as_millis() > config.batch_interval_ms as u128
+                        || (low_latency.load(Ordering::Relaxed)
+                            && rate.per_second() < LOW_LATENCY_MAX_RATE)
+                    {
+                        let matches =
+                            highlight_batch(&app_handle, &device_id, &batch, &highlights, &metrics).await;
+                        emit_entries(&app_handle, &window, &batch, &matches, &stats);
+                        emit_fold_groups(&app_handle, &window, &mut closed_folds);
                         batch.clear();
                         last_emit = std::time::Instant::now();
                     }
                 }
                 Ok(None) => {
-                    // Channel closed
+                    // Channel closed; if nobody stopped the stream the source died
+                    if running.load(Ordering::Relaxed) {
+                        metrics.lock().await.record_drop(&device_id);
+                    }
                     break;
                 }
                 Err(_) => {
                     // Timeout - emit any pending logs
-                    if !batch.is_empty() {
-                        if let Err(e) = app_handle.emit("logcat-entries", &batch) {
-                            error!("Failed to emit logs: {}", e);
+                    if let Some(file) = sink.as_mut() {
+                        if let Err(e) = file.flush() {
+                            error!("Failed to write {}: {}", file.path().display(), e);
+                            sink = None;
                         }
+                    }
+                    if !batch.is_empty() {
+                        let matches =
+                            highlight_batch(&app_handle, &device_id, &batch, &highlights, &metrics).await;
+                        emit_entries(&app_handle, &window, &batch, &matches, &stats);
                         batch.clear();
                         last_emit = std::time::Instant::now();
                     }
+                    // Summarize blocks that stopped growing, e.g. the trace of a dead process
+                    if let Some(device) = analysis.lock().await.get_mut(&device_id) {
+                        let idle = device.folds.close_idle(&device_id, std::time::Instant::now());
+                        if subscribed(ChannelMask::ENTRIES) {
+                            closed_folds.extend(idle);
+                        }
+                    }
+                    emit_fold_groups(&app_handle, &window, &mut closed_folds);
                 }
             }
         }
 
         // Emit any remaining logs
         if !batch.is_empty() {
-            let _ = app_handle.emit("logcat-entries", &batch);
+            let matches = highlight_batch(&app_handle, &device_id, &batch, &highlights, &metrics).await;
+            emit_entries(&app_handle, &window, &batch, &matches, &stats);
+        }
+        emit_fold_groups(&app_handle, &window, &mut closed_folds);
+
+        // Forget the stream if it ended on its own
+        let mut streams = streams.lock().await;
+        if streams
+            .get(&device_id)
+            .is_some_and(|stream| Arc::ptr_eq(&stream.running, &running))
+        {
+            streams.remove(&device_id);
+        }
+        drop(streams);
+
+        let pending = analysis
+            .lock()
+            .await
+            .get_mut(&device_id)
+            .and_then(|device| device.crashes.flush());
+        if let Some(crash) = pending.filter(|_| subscribed(ChannelMask::CRASH)) {
+            emit_crash(&app_handle, &crash_signatures, crash).await;
         }
 
         info!("Logcat forwarding task finished");
     });
+}
 
-    Ok(())
+/// Payload of the `filter-rule-disabled` warning
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleDisabled {
+    pub device_id: String,
+    pub cost: RuleCost,
 }
 
-/// Stop logcat streaming
-#[tauri::command]
-pub async fn stop_logcat(state: State<'_, LogcatState>) -> Result<(), String> {
-    info!("Stopping logcat");
-    stop_logcat_internal(&state).await
+/// Tell the frontend a pattern rule went over `filter::RULE_BUDGET` and was turned off
+fn emit_rule_disabled(app: &AppHandle, device_id: &str, cost: RuleCost) {
+    warn!(
+        "Disabled {:?} rule for {}: {} ms on one line",
+        cost.rule,
+        device_id,
+        cost.max_nanos / 1_000_000
+    );
+    let payload = RuleDisabled {
+        device_id: device_id.to_string(),
+        cost,
+    };
+    if let Err(e) = app.emit("filter-rule-disabled", &payload) {
+        error!("Failed to emit filter warning: {}", e);
+    }
 }
 
-/// Internal function to stop logcat
-async fn stop_logcat_internal(state: &LogcatState) -> Result<(), String> {
-    // Mark as not running
-    {
-        let mut is_running = state.is_running.lock().await;
-        *is_running = false;
+/// Send fold groups closed so far, after the batch holding their entries, as
+/// `fold-group[:<label>]`
+fn emit_fold_groups(app: &AppHandle, window: &str, groups: &mut Vec<FoldGroup>) {
+    for group in groups.drain(..) {
+        let result = if window == MAIN_WINDOW {
+            app.emit("fold-group", &group)
+        } else {
+            app.emit_to(window, &format!("fold-group:{}", window), &group)
+        };
+        if let Err(e) = result {
+            error!("Failed to emit fold group: {}", e);
+        }
     }
+}
 
-    // Kill process if running
-    {
-        let mut process = state.process.lock().await;
-        if let Some(ref mut child) = *process {
-            let _ = child.kill().await;
+/// Match spans of the device's active search in `batch`, with the time spent
+/// recorded in the metrics. A search over budget is dropped instead of
+/// stalling ingestion.
+async fn highlight_batch(
+    app: &AppHandle,
+    device_id: &str,
+    batch: &[LogEntry],
+    highlights: &Mutex<HashMap<String, ActiveSearch>>,
+    metrics: &Mutex<Metrics>,
+) -> Vec<EntryMatches> {
+    let mut highlights = highlights.lock().await;
+    let Some(search) = highlights.get_mut(device_id) else {
+        return Vec::new();
+    };
+    let evaluated = search.cost.evaluations;
+    let start = std::time::Instant::now();
+    let matches = search.batch_matches(batch);
+    let elapsed = start.elapsed();
+    let evaluations = search.cost.evaluations - evaluated;
+    let disabled = search.cost.disabled.then(|| search.cost.clone());
+    if disabled.is_some() {
+        highlights.remove(device_id);
+    }
+    drop(highlights);
+
+    let mut metrics = metrics.lock().await;
+    metrics.record_filter_cost(device_id, "search", evaluations, elapsed);
+    if let Some(cost) = disabled {
+        metrics.record_filter_disabled(device_id);
+        drop(metrics);
+        emit_rule_disabled(app, device_id, cost);
+    }
+    matches
+}
+
+/// Send a batch to the stream's window: `logcat-entries` for the main window,
+/// `logcat-entries:<label>` for detached device windows. The batch's search
+/// match spans, if any, go first as `logcat-highlights[:<label>]`.
+fn emit_entries(
+    app: &AppHandle,
+    window: &str,
+    batch: &[LogEntry],
+    matches: &[EntryMatches],
+    stats: &StreamStats,
+) {
+    stats.record_emitted(batch, chrono::Local::now().timestamp_millis() as u64);
+    if !matches.is_empty() {
+        let result = if window == MAIN_WINDOW {
+            app.emit("logcat-highlights", matches)
+        } else {
+            app.emit_to(window, &format!("logcat-highlights:{}", window), matches)
+        };
+        if let Err(e) = result {
+            error!("Failed to emit highlights: {}", e);
         }
-        *process = None;
     }
+    let result = if window == MAIN_WINDOW {
+        app.emit("logcat-entries", batch)
+    } else {
+        app.emit_to(window, &format!("logcat-entries:{}", window), batch)
+    };
+    if let Err(e) = result {
+        error!("Failed to emit logs: {}", e);
+    }
+}
 
-    Ok(())
+/// Label of the window dedicated to a device
+fn device_window_label(device_id: &str) -> String {
+    format!("device-{}", device_id)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
 }
 
-/// Clear logcat buffer on device
+/// Open (or focus) a separate window dedicated to one device; returns its label.
+/// The window loads the app with `?device=<id>` and receives `logcat-entries:<label>`.
 #[tauri::command]
-pub async fn clear_logcat(device_id: String) -> Result<(), String> {
-    info!("Clearing logcat for device: {}", device_id);
-    ADB_MANAGER.clear_logcat(&device_id).await
-}
+pub async fn open_device_window(app: AppHandle, device_id: String) -> Result<String, String> {
+    let label = device_window_label(&device_id);
+    if let Some(existing) = app.get_webview_window(&label) {
+        existing.set_focus().map_err(|e| e.to_string())?;
+        return Ok(label);
+    }
 
+    info!("Opening window {} for device {}", label, device_id);
+    let url = format!("index.html?device={}", device_id);
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(format!("Logcat - {}", device_id))
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .build()
+        .map_err(|e| format!("Failed to open window: {}", e))?;
+
+    // Stop the window's stream when it closes
+    let handle = app.clone();
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            let handle = handle.clone();
+            let label = closed_label.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<LogcatState>();
+                stop_streams(&state, Some(&label), None).await;
+            });
+        }
+    });
+    Ok(label)
+}
+
+/// Outcome of a batch operation on one device
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceResult {
+    pub device_id: String,
+    pub ok: bool,
+    /// Window label on success, the error otherwise
+    pub message: String,
+}
+
+impl DeviceResult {
+    fn new(device_id: String, result: Result<String, String>) -> Self {
+        let ok = result.is_ok();
+        let message = result.unwrap_or_else(|e| e);
+        DeviceResult { device_id, ok, message }
+    }
+}
+
+/// Online devices (state `device`)
+async fn online_devices() -> Result<Vec<String>, String> {
+    Ok(ADB_MANAGER
+        .get_devices()
+        .await?
+        .into_iter()
+        .filter(|d| d.state == DeviceState::Device)
+        .map(|d| d.id)
+        .collect())
+}
+
+/// Start streaming each device that isn't streaming yet in its own device
+/// window, concurrently. The stream starts before the window opens, and the
+/// window attaches to it on load.
+async fn start_in_device_windows(
+    app: &AppHandle,
+    state: &LogcatState,
+    device_ids: Vec<String>,
+) -> Vec<DeviceResult> {
+    let mut tasks: Vec<(String, JoinHandle<Result<String, String>>)> = Vec::new();
+    for device_id in device_ids {
+        let busy = state.streams.lock().await.get(&device_id).map(|s| s.window.clone());
+        let app = app.clone();
+        let id = device_id.clone();
+        let task = tokio::spawn(async move {
+            if let Some(window) = busy {
+                return Err(format!("Already streaming in window {}", window));
+            }
+            let state = app.state::<LogcatState>();
+            let label = device_window_label(&id);
+            start_logcat_internal(&app, &label, id.clone(), (&[], &[]), false, &state).await?;
+            if let Err(e) = open_device_window(app.clone(), id).await {
+                stop_streams(&state, Some(&label), None).await;
+                return Err(e);
+            }
+            Ok(label)
+        });
+        tasks.push((device_id, task));
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (device_id, task) in tasks {
+        let result = match task.await {
+            Ok(result) => result,
+            Err(e) => Err(e.to_string()),
+        };
+        results.push(DeviceResult::new(device_id, result));
+    }
+    results
+}
+
+/// Stream every online device concurrently, each in its own device window.
+/// A device's result is ok once its stream runs.
+#[tauri::command]
+pub async fn start_logcat_all(
+    app: AppHandle,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<DeviceResult>, String> {
+    Ok(start_in_device_windows(&app, &state, online_devices().await?).await)
+}
+
+/// Online members of a device group
+async fn group_devices(group: &str) -> Result<Vec<String>, String> {
+    let members = settings::current()
+        .device_groups
+        .into_iter()
+        .find(|g| g.name == group)
+        .ok_or_else(|| format!("Device group not found: {}", group))?
+        .devices;
+    Ok(online_devices()
+        .await?
+        .into_iter()
+        .filter(|id| members.contains(id))
+        .collect())
+}
+
+/// Stream every online device of a group, each in its own device window
+#[tauri::command]
+pub async fn start_logcat_group(
+    app: AppHandle,
+    group: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<DeviceResult>, String> {
+    info!("Starting device group {}", group);
+    let devices = group_devices(&group).await?;
+    Ok(start_in_device_windows(&app, &state, devices).await)
+}
+
+/// Ask the windows streaming a group's devices to apply a filter query
+/// (`apply-filter` event; filtering stays in each window)
+#[tauri::command]
+pub async fn apply_group_filter(
+    app: AppHandle,
+    group: String,
+    filter: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<DeviceResult>, String> {
+    let devices = group_devices(&group).await?;
+    let streams = state.streams.lock().await;
+    Ok(devices
+        .into_iter()
+        .map(|device_id| {
+            let result = match streams.get(&device_id) {
+                Some(stream) => app
+                    .emit_to(stream.window.as_str(), "apply-filter", &filter)
+                    .map(|_| stream.window.clone())
+                    .map_err(|e| e.to_string()),
+                None => Err("Not streaming".to_string()),
+            };
+            DeviceResult::new(device_id, result)
+        })
+        .collect())
+}
+
+/// Clear the logcat buffer of every online device concurrently
+#[tauri::command]
+pub async fn clear_logcat_all() -> Result<Vec<DeviceResult>, String> {
+    let tasks: Vec<(String, JoinHandle<Result<(), String>>)> = online_devices()
+        .await?
+        .into_iter()
+        .map(|id| {
+            let device_id = id.clone();
+            (id, tokio::spawn(async move { ADB_MANAGER.clear_logcat(&device_id).await }))
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (device_id, task) in tasks {
+        let result = match task.await {
+            Ok(result) => result.map(|_| "cleared".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        results.push(DeviceResult::new(device_id, result));
+    }
+    Ok(results)
+}
+
+/// Label a crash against the known signatures and emit `crash-known` or `crash-new`
+async fn emit_crash(app: &AppHandle, signatures: &Mutex<SignatureMatcher>, mut crash: CrashReport) {
+    crash.known = signatures.lock().await.find(&crash);
+    let event = if crash.known.is_some() { "crash-known" } else { "crash-new" };
+    info!("Crash detected ({}): {}", event, crash.exception);
+    if let Err(e) = app.emit(event, &crash) {
+        error!("Failed to emit crash: {}", e);
+    }
+}
+
+/// Save a crash loop's history window, crash reports and a bugreport to a
+/// timestamped folder, so the evidence survives an unattended run. Emits
+/// `crash-loop` once the logs are on disk and `crash-loop-exported` after
+/// the bugreport.
+async fn capture_crash_loop(
+    app: AppHandle,
+    history: Arc<Mutex<HashMap<String, EntryHistory>>>,
+    mut crash_loop: CrashLoop,
+    notify: bool,
+) {
+    warn!(
+        "Crash loop: {} crashed {} times within {} min",
+        crash_loop.package_name,
+        crash_loop.crashes.len(),
+        crash_loop.window_mins
+    );
+    let entries = match history.lock().await.get(&crash_loop.device_id) {
+        Some(store) => crashloop::window_entries(&store.entries().collect::<Vec<_>>(), &crash_loop),
+        None => Vec::new(),
+    };
+    let folder = settings::config_dir()
+        .ok_or_else(|| "Config directory not initialized".to_string())
+        .and_then(|dir| {
+            let dir = dir.join(crashloop::CRASH_LOOPS_DIR);
+            crashloop::write_capture(&dir, &crash_loop, &entries, chrono::Local::now())
+        });
+    let folder = match folder {
+        Ok(folder) => folder,
+        Err(e) => {
+            error!("Failed to save crash loop capture: {}", e);
+            return;
+        }
+    };
+    crash_loop.folder = Some(folder.display().to_string());
+    tray::note_alert(&app);
+    if notify {
+        if let Err(e) = app.emit("crash-loop", &crash_loop) {
+            error!("Failed to emit crash loop: {}", e);
+        }
+    }
+
+    if !settings::current().crash_loop_bugreport {
+        return;
+    }
+    let path = folder.join("bugreport.zip");
+    let result = ADB_MANAGER.bugreport(&crash_loop.device_id, &path).await;
+    if let Err(e) = &result {
+        error!("Crash loop bugreport failed: {}", e);
+    }
+    let exported = CrashLoopExported {
+        device_id: crash_loop.device_id,
+        package_name: crash_loop.package_name,
+        folder: folder.display().to_string(),
+        bugreport: result.is_ok().then(|| path.display().to_string()),
+        error: result.err(),
+    };
+    if notify {
+        if let Err(e) = app.emit("crash-loop-exported", &exported) {
+            error!("Failed to emit crash loop export: {}", e);
+        }
+    }
+}
+
+/// List connected iOS devices (requires libimobiledevice)
+#[tauri::command]
+pub async fn get_ios_devices() -> Result<Vec<Device>, String> {
+    info!("Getting iOS device list");
+    ios::list_devices().await
+}
+
+/// Start streaming an iOS device's syslog through the same pipeline as logcat
+#[tauri::command]
+pub async fn start_ios_syslog(
+    app: AppHandle,
+    window: Window,
+    udid: String,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Starting iOS syslog for device: {}", udid);
+    let source = IosSyslogSource { udid: udid.clone() };
+    start_source_internal(&app, window.label(), udid, &source, &state).await
+}
+
+/// Add a custom log source running next to the device stream.
+/// `command_or_port` is either a TCP port (listened on 127.0.0.1) or a command line;
+/// `format` is "threadtime", "brief" or "regex:<pattern with named groups>".
+#[tauri::command]
+pub async fn add_custom_source(
+    app: AppHandle,
+    name: String,
+    command_or_port: String,
+    format: String,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Adding custom source {}: {}", name, command_or_port);
+    let format = SourceFormat::parse(&format)?;
+
+    let mut sources = state.custom_sources.lock().await;
+    if sources.contains_key(&name) {
+        return Err(format!("Custom source already exists: {}", name));
+    }
+    let handle = custom_source::start(app, name.clone(), command_or_port, format).await?;
+    sources.insert(name, handle);
+    Ok(())
+}
+
+/// Stream lines appended to a file on the device as a custom source named
+/// `<device>:<path>`. With `package` the file is read via `run-as` (debuggable
+/// apps; the path may be relative to the app's data dir), otherwise the path
+/// must be absolute and readable by the shell user or root. `format` is as for
+/// `add_custom_source`. Stop it with `remove_custom_source`.
+#[tauri::command]
+pub async fn tail_device_file(
+    app: AppHandle,
+    device_id: String,
+    path: String,
+    package: Option<String>,
+    format: String,
+    state: State<'_, LogcatState>,
+) -> Result<String, String> {
+    DeviceFileSource::validate(&path, package.as_deref())?;
+    let source = DeviceFileSource {
+        adb_path: ADB_MANAGER.adb_path(),
+        device_id,
+        path,
+        package,
+        format: SourceFormat::parse(&format)?,
+    };
+    let name = source.name();
+    info!("Tailing device file {}", name);
+
+    let mut sources = state.custom_sources.lock().await;
+    if sources.contains_key(&name) {
+        return Err(format!("Custom source already exists: {}", name));
+    }
+    let handle = custom_source::start_source(app, &source)?;
+    sources.insert(name.clone(), handle);
+    Ok(name)
+}
+
+/// Check a filter's search pattern before applying it
+#[tauri::command]
+pub async fn validate_filter(config: FilterConfig) -> Result<FilterValidation, String> {
+    Ok(filter::validate(&config))
+}
+
+/// Evaluate a candidate filter against the device's recent entries:
+/// match count plus the latest `sample` matches (default 20).
+/// Enabled quick toggles are stacked on the filter; pinned entries always match.
+/// `pidPackage` is resolved against the device's current processes.
+/// Rules that go over the per-line time budget are disabled, reported in
+/// `ruleCosts` and with a `filter-rule-disabled` event.
+#[tauri::command]
+pub async fn preview_filter(
+    app: AppHandle,
+    device_id: String,
+    config: FilterConfig,
+    sample: Option<usize>,
+    state: State<'_, LogcatState>,
+) -> Result<FilterPreview, String> {
+    let validation = filter::validate(&config);
+    if let Some(error) = validation.errors.first() {
+        return Err(format!("Invalid filter: {}", error.message));
+    }
+    let package = config.pid_package.clone();
+    let mut filter = LogFilter::new(config);
+    filter.set_exclusions(NoiseFilter::new(&settings::current().quick_toggles)?);
+    if let Some(pins) = state.pins.lock().await.get(&device_id) {
+        filter.set_pinned(pins.iter().map(|entry| entry.id).collect());
+    }
+    if let Some(package) = package {
+        let processes = ADB_MANAGER.get_processes(&device_id).await?;
+        filter.set_package_pids(filter::package_pids(&processes, &package));
+    }
+    let history = state.history.lock().await;
+    let store = history
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?;
+    let mut preview = store.preview(&filter, sample.unwrap_or(history::DEFAULT_SAMPLE));
+    drop(history);
+    for cost in preview.rule_costs.iter().filter(|cost| cost.disabled) {
+        emit_rule_disabled(&app, &device_id, cost.clone());
+    }
+    display_times(&mut preview.sample);
+    Ok(preview)
+}
+
+/// Next or previous stored entry sharing the PID, TID or tag of `entry_id`,
+/// for walking one thread's or tag's activity
+#[tauri::command]
+pub async fn find_adjacent(
+    device_id: String,
+    entry_id: u64,
+    relation: Relation,
+    direction: Direction,
+    state: State<'_, LogcatState>,
+) -> Result<Option<LogEntry>, String> {
+    let history = state.history.lock().await;
+    let store = history
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?;
+    let mut adjacent = store.adjacent(entry_id, relation, direction).cloned();
+    if let Some(entry) = adjacent.as_mut() {
+        display_times(std::slice::from_mut(entry));
+    }
+    Ok(adjacent)
+}
+
+/// Stored entries of every device sharing a correlation ID, in canonical
+/// order, to follow one request across components and processes
+#[tauri::command]
+pub async fn get_entries_by_correlation(
+    id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<LogEntry>, String> {
+    let history = state.history.lock().await;
+    let mut entries: Vec<(String, LogEntry)> = history
+        .iter()
+        .flat_map(|(device_id, store)| {
+            store
+                .with_correlation(&id)
+                .map(move |entry| (device_id.clone(), entry.clone()))
+        })
+        .collect();
+    drop(history);
+    entries.sort_by(|(da, a), (db, b)| a.sort_key(da).cmp(&b.sort_key(db)));
+    let mut entries: Vec<LogEntry> = entries.into_iter().map(|(_, entry)| entry).collect();
+    display_times(&mut entries);
+    Ok(entries)
+}
+
+/// Replace the correlation ID patterns (regexes with a capture group) and
+/// persist them; running streams use them from the next entry
+#[tauri::command]
+pub async fn set_correlation_patterns(
+    patterns: Vec<String>,
+    state: State<'_, LogcatState>,
+) -> Result<Settings, String> {
+    let extractor = CorrelationExtractor::new(&patterns)?;
+    let updated = settings::update(serde_json::json!({ "correlationPatterns": patterns }))?;
+    *state.correlation.lock().await = extractor;
+    Ok(updated)
+}
+
+/// Replace the rules grouping multi-line blocks (stack traces, dumps) into
+/// folds; running streams regroup from their next entry
+#[tauri::command]
+pub async fn set_fold_rules(
+    rules: Vec<FoldRule>,
+    state: State<'_, LogcatState>,
+) -> Result<Settings, String> {
+    FoldTracker::new(&rules)?;
+    let updated = settings::update(serde_json::json!({ "foldRules": rules }))?;
+    for device in state.analysis.lock().await.values_mut() {
+        device.folds = FoldTracker::new(&rules)?;
+    }
+    Ok(updated)
+}
+
+/// Replace the span rules and persist them. Running streams start over with
+/// the new rules, dropping spans still open.
+#[tauri::command]
+pub async fn set_span_rules(
+    rules: Vec<SpanRule>,
+    state: State<'_, LogcatState>,
+) -> Result<Settings, String> {
+    SpanTracker::new(&rules)?;
+    let updated = settings::update(serde_json::json!({ "spanRules": rules }))?;
+    for device in state.analysis.lock().await.values_mut() {
+        device.spans = SpanTracker::new(&rules)?;
+    }
+    Ok(updated)
+}
+
+/// Recently completed spans of a device, oldest first
+#[tauri::command]
+pub async fn get_spans(device_id: String, state: State<'_, LogcatState>) -> Result<Vec<Span>, String> {
+    Ok(state
+        .analysis
+        .lock()
+        .await
+        .get(&device_id)
+        .map(|device| device.spans.spans())
+        .unwrap_or_default())
+}
+
+/// Duration percentiles per span rule over a device's recent spans
+#[tauri::command]
+pub async fn get_span_stats(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<SpanStats>, String> {
+    Ok(state
+        .analysis
+        .lock()
+        .await
+        .get(&device_id)
+        .map(|device| device.spans.stats())
+        .unwrap_or_default())
+}
+
+/// Payload of the `pins-changed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinsChanged {
+    pub device_id: String,
+    pub entry_ids: Vec<u64>,
+}
+
+/// Store the device's pins and tell the frontend which IDs bypass its filters
+async fn update_pins(
+    app: &AppHandle,
+    state: &LogcatState,
+    device_id: String,
+    change: impl FnOnce(&mut Vec<LogEntry>) -> Result<(), String>,
+) -> Result<Vec<u64>, String> {
+    let mut pins = state.pins.lock().await;
+    let device_pins = pins.entry(device_id.clone()).or_default();
+    change(device_pins)?;
+    let entry_ids: Vec<u64> = device_pins.iter().map(|entry| entry.id).collect();
+    drop(pins);
+    let payload = PinsChanged {
+        device_id,
+        entry_ids: entry_ids.clone(),
+    };
+    if let Err(e) = app.emit("pins-changed", &payload) {
+        error!("Failed to emit pins: {}", e);
+    }
+    Ok(entry_ids)
+}
+
+/// Pin an entry so it stays visible whatever the filters; returns the pinned IDs.
+/// Pins are kept until unpinned, also across stream restarts.
+#[tauri::command]
+pub async fn pin_entry(
+    app: AppHandle,
+    device_id: String,
+    entry_id: u64,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<u64>, String> {
+    let entry = state
+        .history
+        .lock()
+        .await
+        .get(&device_id)
+        .and_then(|store| store.with_ids(&HashSet::from([entry_id])).next().cloned())
+        .ok_or_else(|| format!("Entry {} is no longer available", entry_id))?;
+    let device = device_id.clone();
+    update_pins(&app, &state, device_id, |pins| {
+        if !pins.iter().any(|pinned| pinned.id == entry_id) {
+            pins.push(entry);
+            pins.sort_by(|a, b| a.sort_key(&device).cmp(&b.sort_key(&device)));
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Remove a pin; returns the remaining pinned IDs
+#[tauri::command]
+pub async fn unpin_entry(
+    app: AppHandle,
+    device_id: String,
+    entry_id: u64,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<u64>, String> {
+    update_pins(&app, &state, device_id, |pins| {
+        pins.retain(|pinned| pinned.id != entry_id);
+        Ok(())
+    })
+    .await
+}
+
+/// Pinned entries of a device in canonical order
+#[tauri::command]
+pub async fn get_pinned_entries(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<LogEntry>, String> {
+    let mut pins = state.pins.lock().await.get(&device_id).cloned().unwrap_or_default();
+    display_times(&mut pins);
+    Ok(pins)
+}
+
+/// Set (or clear with `None`) the device's active search. Later batches are
+/// preceded by their match spans; the returned spans cover `entry_ids` already shown.
+#[tauri::command]
+pub async fn set_highlight_search(
+    device_id: String,
+    config: Option<FilterConfig>,
+    entry_ids: Option<Vec<u64>>,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<EntryMatches>, String> {
+    let regex = match config {
+        Some(config) => LogFilter::compile_search_regex(&config)
+            .map_err(|e| format!("Invalid search pattern: {}", e))?,
+        None => None,
+    };
+    let Some(regex) = regex else {
+        state.highlights.lock().await.remove(&device_id);
+        return Ok(Vec::new());
+    };
+
+    let ids: HashSet<u64> = entry_ids.unwrap_or_default().into_iter().collect();
+    let matches = match state.history.lock().await.get(&device_id) {
+        Some(store) if !ids.is_empty() => highlight::batch_matches(&regex, store.with_ids(&ids)),
+        _ => Vec::new(),
+    };
+    state.highlights.lock().await.insert(device_id, ActiveSearch::new(regex));
+    Ok(matches)
+}
+
+/// Parse a text log file on disk; `format` defaults to "auto"
+/// (logcat formats first, then the registered parser templates)
+#[tauri::command]
+pub async fn parse_log_file(path: String, format: Option<String>) -> Result<Vec<LogEntry>, String> {
+    info!("Parsing log file: {}", path);
+    let format = SourceFormat::parse(format.as_deref().unwrap_or("auto"))?;
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let name = std::path::Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+
+    let mut parser = CustomParser::new(&name, format);
+    Ok(text.lines().filter_map(|line| parser.parse_line(line)).collect())
+}
+
+/// Stop and remove a custom log source
+#[tauri::command]
+pub async fn remove_custom_source(
+    name: String,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Removing custom source: {}", name);
+    let handle = state
+        .custom_sources
+        .lock()
+        .await
+        .remove(&name)
+        .ok_or_else(|| format!("No custom source named: {}", name))?;
+    handle.stop().await;
+    Ok(())
+}
+
+/// List running custom sources as (name, command or port)
+#[tauri::command]
+pub async fn list_custom_sources(
+    state: State<'_, LogcatState>,
+) -> Result<Vec<(String, String)>, String> {
+    let sources = state.custom_sources.lock().await;
+    let mut list: Vec<(String, String)> = sources
+        .iter()
+        .map(|(name, handle)| (name.clone(), handle.target.clone()))
+        .collect();
+    list.sort();
+    Ok(list)
+}
+
+/// Choose which optional entry fields (raw, dateTime, epoch, packageName,
+/// processName) are serialized in `logcat-entries` batches
+#[tauri::command]
+pub async fn set_emit_fields(
+    fields: Vec<String>,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    let mask = FieldMask::from_names(&fields)?;
+    info!("Emitting entry fields: {:?}", fields);
+    state.emit_fields.store(mask.bits(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Subscribe to event channels (`logcat-entries`, `crash`, `system-events`,
+/// `stats`, `markers`); all are on at startup. Returns the subscribed channels.
+#[tauri::command]
+pub async fn subscribe_events(
+    channels: Vec<String>,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<String>, String> {
+    let bits = ChannelMask::parse(&channels)?;
+    let previous = state.event_channels.fetch_or(bits, Ordering::Relaxed);
+    Ok(ChannelMask::from_bits(previous | bits).names())
+}
+
+/// Stop sending the events of channels whose panes aren't shown, sparing
+/// their serialization and deserialization. Returns the subscribed channels.
+#[tauri::command]
+pub async fn unsubscribe_events(
+    channels: Vec<String>,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<String>, String> {
+    let bits = ChannelMask::parse(&channels)?;
+    let previous = state.event_channels.fetch_and(!bits, Ordering::Relaxed);
+    info!("Unsubscribed from event channels: {:?}", channels);
+    Ok(ChannelMask::from_bits(previous & !bits).names())
+}
+
+/// Pause or resume capture on all streams; processes keep running and
+/// entries received while paused are discarded
+#[tauri::command]
+pub async fn set_capture_paused(app: AppHandle, paused: bool) -> Result<(), String> {
+    tray::set_paused(&app, paused);
+    Ok(())
+}
+
+/// Emit a device's entries the moment they arrive instead of in 50-entry /
+/// 100 ms batches, e.g. while stepping through breakpoints. Batching resumes on
+/// its own while the stream runs above 100 entries per second.
+#[tauri::command]
+pub async fn set_low_latency(
+    device_id: String,
+    enabled: bool,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    let streams = state.streams.lock().await;
+    let stream = streams
+        .get(&device_id)
+        .ok_or_else(|| format!("No stream running for {}", device_id))?;
+    info!("Low-latency mode for {}: {}", device_id, enabled);
+    stream.low_latency.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Result of `insert_marker`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerResult {
+    pub note: String,
+    /// Devices whose streams received the marker
+    pub device_ids: Vec<String>,
+    /// Screenshot files saved, one per device that could capture
+    pub screenshots: Vec<String>,
+}
+
+/// Insert a timestamped marker entry into every active stream and save a screenshot
+/// of each device, pinning "the bug just happened" moments during manual testing.
+/// Cancelling `operation_id` skips the remaining screenshots.
+#[tauri::command]
+pub async fn insert_marker(
+    note: Option<String>,
+    operation_id: Option<String>,
+    state: State<'_, LogcatState>,
+) -> Result<MarkerResult, String> {
+    let now = chrono::Local::now();
+    let note = note.unwrap_or_else(|| format!("Marker {}", now.format("%H:%M:%S")));
+    info!("Inserting marker: {}", note);
+
+    let senders: Vec<(String, mpsc::WeakSender<LogEntry>)> = state
+        .streams
+        .lock()
+        .await
+        .iter()
+        .map(|(id, stream)| (id.clone(), stream.sender.clone()))
+        .collect();
+
+    let mut device_ids = Vec::new();
+    for (device_id, sender) in &senders {
+        if let Some(sender) = sender.upgrade() {
+            if sender.send(marker_entry(&note, now)).await.is_ok() {
+                device_ids.push(device_id.clone());
+            }
+        }
+    }
+
+    let mut screenshots = Vec::new();
+    let dir = settings::config_dir().map(|dir| dir.join(retention::SCREENSHOTS_DIR));
+    let operation = operation_id.map(|op| ADB_MANAGER.begin_operation(&op));
+    let cancel = operation.as_ref().map(|op| op.token());
+    for device_id in &device_ids {
+        if cancel.is_some_and(|token| token.is_cancelled()) {
+            break;
+        }
+        let Some(dir) = dir.as_ref() else {
+            break;
+        };
+        let Ok(png) = ADB_MANAGER.screenshot(device_id, cancel).await else {
+            continue;
+        };
+        let safe_id: String = device_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = dir.join(format!("{}_{}.png", safe_id, now.format("%Y%m%d_%H%M%S")));
+        let written = tokio::fs::create_dir_all(dir).await.is_ok()
+            && tokio::fs::write(&path, png).await.is_ok();
+        if written {
+            screenshots.push(path.to_string_lossy().to_string());
+        } else {
+            error!("Failed to save screenshot to {}", path.display());
+        }
+    }
+
+    Ok(MarkerResult {
+        note,
+        device_ids,
+        screenshots,
+    })
+}
+
+/// Synthetic entry marking a point in time
+pub(crate) fn marker_entry(note: &str, now: chrono::DateTime<chrono::Local>) -> LogEntry {
+    let timestamp = now.format("%H:%M:%S%.3f").to_string();
+    LogEntry {
+        id: parser::next_entry_id(),
+        seq: 0,
+        timestamp: timestamp.clone(),
+        date_time: Some(format!("{} {}", now.format("%m-%d"), timestamp)),
+        epoch: Some(now.timestamp_millis() as u64),
+        time: Some(now.into()),
+        pid: 0,
+        tid: 0,
+        level: LogLevel::I,
+        tag: "Marker".to_string(),
+        message: note.to_string(),
+        package_name: None,
+        process_name: None,
+        device_color: None,
+        package_color: None,
+        correlation_id: None,
+        links: Vec::new(),
+        group_id: None,
+        sensitive: false,
+        sanitized: false,
+        marker: true,
+        color_level: None,
+        raw: None,
+    }
+}
+
+/// Stop the calling window's stream, or a specific device's stream
+#[tauri::command]
+pub async fn stop_logcat(
+    window: Window,
+    device_id: Option<String>,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Stopping logcat");
+    match device_id {
+        Some(device_id) => stop_streams(&state, None, Some(&device_id)).await,
+        None => stop_streams(&state, Some(window.label()), None).await,
+    }
+    Ok(())
+}
+
+/// Stop streams owned by `window` and/or for `device_id`
+async fn stop_streams(state: &LogcatState, window: Option<&str>, device_id: Option<&str>) {
+    let stopped: Vec<(String, DeviceStream)> = {
+        let mut streams = state.streams.lock().await;
+        let ids: Vec<String> = streams
+            .iter()
+            .filter(|(id, stream)| {
+                window.map_or(true, |w| stream.window == w)
+                    && device_id.map_or(true, |d| id.as_str() == d)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| streams.remove(&id).map(|stream| (id, stream)))
+            .collect()
+    };
+
+    for (device_id, mut stream) in stopped {
+        // Mark as not running before killing so the exit isn't counted as a drop
+        stream.running.store(false, Ordering::Relaxed);
+        let _ = stream.child.kill().await;
+        state.tasks.stop(&device_id);
+    }
+}
+
+/// Clear logcat buffer on device
+#[tauri::command]
+pub async fn clear_logcat(device_id: String) -> Result<(), String> {
+    info!("Clearing logcat for device: {}", device_id);
+    ADB_MANAGER.clear_logcat(&device_id).await
+}
+
+
+/// Get wakelock and power events extracted from a device's log stream
+#[tauri::command]
+pub async fn get_power_events(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<PowerSummary, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.power.summary())
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+/// Get the activity navigation timeline of a device, optionally for one package.
+/// Create/resume/pause/stop/destroy need the `events` buffer in the stream.
+#[tauri::command]
+pub async fn get_lifecycle_events(
+    device_id: String,
+    package: Option<String>,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<LifecycleEvent>, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.lifecycle.timeline(package.as_deref()))
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+fn startup_history_path() -> Result<std::path::PathBuf, String> {
+    settings::config_dir()
+        .map(|dir| dir.join(startup::STARTUP_FILE))
+        .ok_or_else(|| "Config directory is not available".to_string())
+}
+
+/// Tag a launch with the installed app version and persist it
+async fn record_startup(mut measurement: StartupMeasurement) {
+    measurement.version_name = ADB_MANAGER
+        .package_version(&measurement.device_id, &measurement.package_name)
+        .await;
+    let _guard = STARTUP_HISTORY_LOCK.lock().await;
+    let result = startup_history_path().and_then(|path| startup::append(&path, measurement));
+    if let Err(e) = result {
+        error!("Failed to record startup time: {}", e);
+    }
+}
+
+/// Get the persisted launch times of a package, with per-version statistics
+#[tauri::command]
+pub async fn get_startup_times(package: String) -> Result<StartupHistory, String> {
+    let _guard = STARTUP_HISTORY_LOCK.lock().await;
+    let all = startup::load(&startup_history_path()?)?;
+    Ok(startup::history(all, &package))
+}
+
+fn crash_signatures_path() -> Result<std::path::PathBuf, String> {
+    settings::config_dir()
+        .map(|dir| dir.join(crash::SIGNATURES_FILE))
+        .ok_or_else(|| "Config directory is not available".to_string())
+}
+
+/// Load the persisted crash signatures on startup
+pub fn restore_crash_signatures(app: &AppHandle) {
+    let Ok(path) = crash_signatures_path() else {
+        return;
+    };
+    if !path.exists() {
+        return;
+    }
+    let matcher = crash::load_signatures(&path).and_then(|sigs| SignatureMatcher::new(&sigs));
+    match matcher {
+        Ok(matcher) => *app.state::<LogcatState>().crash_signatures.blocking_lock() = matcher,
+        Err(e) => error!("Failed to restore crash signatures: {}", e),
+    }
+}
+
+/// Import known crash signatures from a JSON file, replacing the current ones.
+/// Returns the number of signatures imported.
+#[tauri::command]
+pub async fn import_crash_signatures(
+    path: String,
+    state: State<'_, LogcatState>,
+) -> Result<usize, String> {
+    info!("Importing crash signatures from: {}", path);
+    let signatures = crash::load_signatures(std::path::Path::new(&path))?;
+    let matcher = SignatureMatcher::new(&signatures)?;
+    crash::save_signatures(&crash_signatures_path()?, &signatures)?;
+    *state.crash_signatures.lock().await = matcher;
+    Ok(signatures.len())
+}
+
+/// Get the imported crash signatures
+#[tauri::command]
+pub async fn get_crash_signatures() -> Result<Vec<CrashSignature>, String> {
+    let path = crash_signatures_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    crash::load_signatures(&path)
+}
+
+/// Get the instrumentation test cases seen on a device, with their entry ranges
+#[tauri::command]
+pub async fn get_test_cases(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<TestCase>, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.tests.cases().iter().cloned().collect())
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+/// Write one log file per detected test case (with its crashes/ANRs) into `out_dir`.
+/// Returns the number of files written.
+#[tauri::command]
+pub async fn export_per_test(
+    device_id: String,
+    out_dir: String,
+    state: State<'_, LogcatState>,
+) -> Result<usize, String> {
+    info!("Exporting per-test logs for {} to: {}", device_id, out_dir);
+    let redactor = export_redactor()?;
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?
+        .tests
+        .export(std::path::Path::new(&out_dir), redactor.as_ref())
+}
+
+/// Get aggregated frame drop / jank statistics for a device
+#[tauri::command]
+pub async fn get_jank_stats(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<JankStats, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.jank.clone())
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+/// Bucket a device's entry counts by time, grouped by "level", "tag",
+/// "tagFamily" or "pid".
+/// `from`/`to` (epoch millis) narrow the window, e.g. to zoom into a spike.
+#[tauri::command]
+pub async fn get_timeseries(
+    device_id: String,
+    bucket_ms: u64,
+    group_by: String,
+    from: Option<u64>,
+    to: Option<u64>,
+    state: State<'_, LogcatState>,
+) -> Result<TimeSeries, String> {
+    let group_by = GroupBy::parse(&group_by)?;
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?
+        .timeseries
+        .aggregate(bucket_ms, group_by, from, to)
+}
+
+/// Recent entry counts per tag, rolled up into tag families (`okhttp.*`,
+/// `Firebase*`, `WM-`), largest first
+#[tauri::command]
+pub async fn get_tag_families(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<FamilyNode>, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.timeseries.tag_families())
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+/// Message templates seen repeatedly with different numbers, most frequent first
+#[tauri::command]
+pub async fn get_message_templates(
+    device_id: String,
+    limit: Option<usize>,
+    state: State<'_, LogcatState>,
+) -> Result<Vec<MessageTemplate>, String> {
+    let analysis = state.analysis.lock().await;
+    Ok(analysis
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?
+        .series
+        .templates(limit.unwrap_or(100)))
+}
+
+/// Recent values of a message template, for trend inspection
+#[tauri::command]
+pub async fn get_message_series(
+    device_id: String,
+    template_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<MessageSeries, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))?
+        .series
+        .series(&template_id)
+        .ok_or_else(|| format!("Unknown message template: {}", template_id))
+}
+
+/// Current thermal throttling status and sensor temperatures of a device
+#[tauri::command]
+pub async fn get_thermal_status(device_id: String) -> Result<ThermalSnapshot, String> {
+    ADB_MANAGER.thermal_snapshot(&device_id).await
+}
+
+/// Entries flagged as likely holding credentials, grouped by kind and tag
+#[tauri::command]
+pub async fn get_secret_report(
+    device_id: String,
+    state: State<'_, LogcatState>,
+) -> Result<SecretReport, String> {
+    let analysis = state.analysis.lock().await;
+    analysis
+        .get(&device_id)
+        .map(|device| device.secrets.report())
+        .ok_or_else(|| format!("No log stream for device: {}", device_id))
+}
+
+/// Export a support bundle (internal logs, config, adb version, devices) as a zip
+#[tauri::command]
+pub async fn export_diagnostics(path: String) -> Result<(), String> {
+    info!("Exporting diagnostics to: {}", path);
+    let bundle = diagnostics::build_bundle(&ADB_MANAGER).await;
+    tokio::fs::write(&path, bundle)
+        .await
+        .map_err(|e| format!("Failed to write diagnostics: {}", e))
+}
+
+fn sessions_dir() -> Result<std::path::PathBuf, String> {
+    settings::config_dir()
+        .map(|dir| dir.join(sessions::SESSIONS_DIR))
+        .ok_or_else(|| "Config directory unavailable".to_string())
+}
+
+/// Save entries as a session in the config directory. Without `metadata`,
+/// the metadata given to `start_logcat` for the device is used. The device's
+/// pinned entries are always included.
+#[tauri::command]
+pub async fn save_session(
+    device_id: String,
+    entries: Vec<LogEntry>,
+    metadata: Option<SessionMetadata>,
+    state: State<'_, LogcatState>,
+) -> Result<SessionInfo, String> {
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => state
+            .session_metadata
+            .lock()
+            .await
+            .get(&device_id)
+            .cloned()
+            .unwrap_or_default(),
+    };
+    let mut entries = entries;
+    let mut pins = state.pins.lock().await.get(&device_id).cloned().unwrap_or_default();
+    display_times(&mut entries);
+    display_times(&mut pins);
+    let redactor = export_redactor()?;
+    let info = sessions::save(
+        &sessions_dir()?,
+        &device_id,
+        metadata,
+        entries,
+        pins,
+        redactor.as_ref(),
+    )?;
+    info!("Saved session {} ({} entries)", info.id, info.entry_count);
+    Ok(info)
+}
+
+/// Saved sessions matching `filter`, newest first
+#[tauri::command]
+pub async fn list_sessions(filter: Option<SessionFilter>) -> Result<Vec<SessionInfo>, String> {
+    sessions::list(&sessions_dir()?, &filter.unwrap_or_default())
+}
+
+/// Disk used by saved sessions and marker screenshots
+#[tauri::command]
+pub async fn get_storage_usage() -> Result<StorageUsage, String> {
+    let dir = settings::config_dir().ok_or("Config directory unavailable")?;
+    let config = settings::current();
+    let items = tokio::task::spawn_blocking(move || retention::scan(&dir))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(retention::usage(
+        &items,
+        retention::limit_bytes(config.max_storage_mb),
+        config.retention_days,
+    ))
+}
+
+/// Apply the retention settings now instead of waiting for the schedule
+#[tauri::command]
+pub async fn prune_storage() -> Result<PruneReport, String> {
+    let dir = settings::config_dir().ok_or("Config directory unavailable")?;
+    tokio::task::spawn_blocking(move || retention::enforce(&dir))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Redactor for file exports, when `redactExports` is enabled
+fn export_redactor() -> Result<Option<Redactor>, String> {
+    let config = settings::current();
+    if !config.redact_exports {
+        return Ok(None);
+    }
+    Redactor::new(&config.redaction_rules).map(Some)
+}
+
+/// Apply the configured redaction rules to entries and report what was replaced,
+/// so the user can review them before sharing
+#[tauri::command]
+pub async fn preview_redaction(entries: Vec<LogEntry>) -> Result<RedactionPreview, String> {
+    let redactor = Redactor::new(&settings::current().redaction_rules)?;
+    Ok(redaction::preview(&redactor, entries))
+}
+
+/// Export entries in pidcat's condensed per-app layout; `color` keeps ANSI escapes
+#[tauri::command]
+pub async fn export_pidcat(
+    path: String,
+    entries: Vec<LogEntry>,
+    package: Option<String>,
+    color: bool,
+) -> Result<(), String> {
+    info!("Exporting {} entries in pidcat format to: {}", entries.len(), path);
+    let mut entries = entries;
+    display_times(&mut entries);
+    if let Some(redactor) = export_redactor()? {
+        for entry in &mut entries {
+            redactor.redact_entry(entry);
+        }
+    }
+    let text = pidcat::render(&entries, package.as_deref(), color);
+    tokio::fs::write(&path, text)
+        .await
+        .map_err(|e| format!("Failed to write export: {}", e))
+}
+
+/// Export entries as Perfetto/Chrome trace-event JSON, with markers, crashes
+/// and spans of the configured span rules, to open in ui.perfetto.dev
+#[tauri::command]
+pub async fn export_perfetto(
+    path: String,
+    device_id: String,
+    entries: Vec<LogEntry>,
+) -> Result<(), String> {
+    info!("Exporting {} entries as Perfetto JSON to: {}", entries.len(), path);
+    let mut entries = entries;
+    if let Some(redactor) = export_redactor()? {
+        for entry in &mut entries {
+            redactor.redact_entry(entry);
+        }
+    }
+    let json = perfetto::render(&device_id, &entries, &settings::current().span_rules)?;
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| format!("Failed to write export: {}", e))
+}
+
+/// Open the source file of a stack frame (`Bar.kt`, or an absolute path) at
+/// `line` in the `editorCommand` editor, searching `projectRoots`; `class`
+/// tells same-named files apart. Returns the opened path.
+#[tauri::command]
+pub async fn open_in_editor(
+    file: String,
+    line: u32,
+    class: Option<String>,
+) -> Result<String, String> {
+    let config = settings::current();
+    let template = config
+        .editor_command
+        .ok_or_else(|| "Set editorCommand in settings first".to_string())?;
+    let roots = config.project_roots;
+    let path = tokio::task::spawn_blocking(move || {
+        editor::resolve(&roots, &file, class.as_deref()).ok_or(file)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|file| format!("{} was not found under the project roots", file))?;
+    let args = editor::command_line(&template, &path, line)?;
+    info!("Opening {}:{} with {}", path.display(), line, args[0]);
+    tokio::process::Command::new(&args[0])
+        .args(&args[1..])
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", args[0], e))?;
+    Ok(path.display().to_string())
+}
+
+/// Get stream health counters in the Prometheus text format
+#[tauri::command]
+pub async fn get_metrics(state: State<'_, LogcatState>) -> Result<String, String> {
+    Ok(state.metrics.lock().await.render())
+}
+
+/// Write the counters to a file, e.g. for node_exporter's textfile collector
+#[tauri::command]
+pub async fn export_metrics(path: String, state: State<'_, LogcatState>) -> Result<(), String> {
+    info!("Exporting metrics to: {}", path);
+    let text = state.metrics.lock().await.render();
+    tokio::fs::write(&path, text)
+        .await
+        .map_err(|e| format!("Failed to write metrics: {}", e))
+}
+
+/// Get the persisted backend settings
+#[tauri::command]
+pub async fn get_settings() -> Result<Settings, String> {
+    Ok(settings::current())
+}
+
+/// Quick toggles plus the query fragment of the enabled ones
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickToggles {
+    pub toggles: Vec<QuickToggle>,
+    /// Exclusion tokens to append to the main filter query
+    pub query: String,
+}
+
+impl QuickToggles {
+    fn new(toggles: Vec<QuickToggle>) -> Self {
+        let query = noise::query(&toggles);
+        QuickToggles { toggles, query }
+    }
+}
+
+/// List the quick toggles; add or edit them through `quickToggles` in settings
+#[tauri::command]
+pub async fn get_quick_toggles() -> Result<QuickToggles, String> {
+    Ok(QuickToggles::new(settings::current().quick_toggles))
+}
+
+/// Enable or disable a quick toggle and persist the choice
+#[tauri::command]
+pub async fn set_quick_toggle(name: String, enabled: bool) -> Result<QuickToggles, String> {
+    let mut toggles = settings::current().quick_toggles;
+    let toggle = toggles
+        .iter_mut()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("Unknown quick toggle: {}", name))?;
+    toggle.enabled = enabled;
+    NoiseFilter::new(&toggles)?;
+    let updated = settings::update(serde_json::json!({ "quickToggles": toggles }))?;
+    Ok(QuickToggles::new(updated.quick_toggles))
+}
+
+/// Convert entry times into the `displayTimezone` setting
+fn display_times(entries: &mut [LogEntry]) {
+    let timezone = settings::current().display_timezone;
+    for entry in entries {
+        timezone.apply(entry);
+    }
+}
+
+/// Choose the timezone of entry times in history queries and exports
+/// ("device", "host" or "utc") and persist it
+#[tauri::command]
+pub async fn set_display_timezone(tz: DisplayTimezone) -> Result<Settings, String> {
+    info!("Display timezone: {:?}", tz);
+    settings::update(serde_json::json!({ "displayTimezone": tz }))
+}
+
+/// Update backend settings with a partial object and persist them
+#[tauri::command]
+pub async fn update_settings(patch: serde_json::Value) -> Result<Settings, String> {
+    info!("Updating settings");
+    settings::update(patch)
+}
+
+/// Save active streams, the workspace, pins, session metadata and the
+/// frontend's `ui` state (filters, layout) so the investigation can be resumed
+/// with `restore_app_state`. With `include_buffers` each device's recent
+/// entries are saved too.
+#[tauri::command]
+pub async fn save_app_state(
+    path: String,
+    ui: Option<serde_json::Value>,
+    include_buffers: bool,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Saving app state to: {}", path);
+    let mut streams: Vec<StreamSnapshot> = state
+        .streams
+        .lock()
+        .await
+        .iter()
+        .map(|(device_id, stream)| StreamSnapshot {
+            device_id: device_id.clone(),
+            window: stream.window.clone(),
+        })
+        .collect();
+    streams.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    let buffers = if include_buffers {
+        state
+            .history
+            .lock()
+            .await
+            .iter()
+            .map(|(device_id, history)| (device_id.clone(), history.entries().cloned().collect()))
+            .collect()
+    } else {
+        Default::default()
+    };
+    let snapshot = AppSnapshot {
+        version: appstate::FORMAT_VERSION,
+        saved_at: chrono::Local::now().to_rfc3339(),
+        streams,
+        active_workspace: settings::current().active_workspace,
+        pins: state.pins.lock().await.clone().into_iter().collect(),
+        session_metadata: state.session_metadata.lock().await.clone().into_iter().collect(),
+        ui: ui.unwrap_or_default(),
+        buffers,
+    };
+    appstate::write(std::path::Path::new(&path), &snapshot)
+}
+
+/// Result of `restore_app_state`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredAppState {
+    /// Frontend state as it was saved
+    pub ui: serde_json::Value,
+    /// Saved entries per device, for the frontend to show before new ones arrive
+    pub buffers: HashMap<String, Vec<LogEntry>>,
+    /// Streams restarted, one result per saved stream
+    pub streams: Vec<DeviceResult>,
+}
+
+/// Restore a `save_app_state` snapshot: reload the workspace, pins and session
+/// metadata, and restart the saved streams on devices that are online
+#[tauri::command]
+pub async fn restore_app_state(
+    app: AppHandle,
+    path: String,
+    state: State<'_, LogcatState>,
+) -> Result<RestoredAppState, String> {
+    info!("Restoring app state from: {}", path);
+    let snapshot = appstate::read(std::path::Path::new(&path))?;
+
+    if let Some(name) = &snapshot.active_workspace {
+        match workspace::load(&workspaces_dir()?, name) {
+            Ok(loaded) => {
+                state.alerts.lock().await.set_rules(&loaded.alert_rules)?;
+                settings::update(serde_json::json!({ "activeWorkspace": name }))?;
+            }
+            Err(e) => error!("Workspace {} not restored: {}", name, e),
+        }
+    }
+    for (device_id, pins) in snapshot.pins {
+        update_pins(&app, &state, device_id, |current| {
+            *current = pins;
+            Ok(())
+        })
+        .await?;
+    }
+    state
+        .session_metadata
+        .lock()
+        .await
+        .extend(snapshot.session_metadata);
+
+    let online = online_devices().await.unwrap_or_default();
+    let mut streams = Vec::new();
+    for stream in snapshot.streams {
+        let device_id = stream.device_id;
+        let result = if !online.contains(&device_id) {
+            Err("Device is not connected".to_string())
+        } else if stream.window == MAIN_WINDOW {
+            start_logcat_internal(&app, MAIN_WINDOW, device_id.clone(), (&[], &[]), false, &state)
+                .await
+                .map(|_| MAIN_WINDOW.to_string())
+        } else {
+            open_device_window(app.clone(), device_id.clone()).await
+        };
+        streams.push(DeviceResult::new(device_id, result));
+    }
+
+    Ok(RestoredAppState {
+        ui: snapshot.ui,
+        buffers: snapshot.buffers.into_iter().collect(),
+        streams,
+    })
+}
+
+/// Directory where workspaces are stored
+fn workspaces_dir() -> Result<std::path::PathBuf, String> {
+    settings::config_dir()
+        .map(|dir| dir.join(workspace::WORKSPACES_DIR))
+        .ok_or_else(|| "Config directory is not available".to_string())
+}
+
+/// List saved workspace names
+#[tauri::command]
+pub async fn list_workspaces() -> Result<Vec<String>, String> {
+    Ok(workspace::list(&workspaces_dir()?))
+}
+
+/// Create or overwrite a workspace
+#[tauri::command]
+pub async fn save_workspace(workspace: Workspace) -> Result<(), String> {
+    info!("Saving workspace: {}", workspace.name);
+    workspace::save(&workspaces_dir()?, &workspace)
+}
+
+/// Load a workspace and make it the active one
+#[tauri::command]
+pub async fn load_workspace(
+    name: String,
+    state: State<'_, LogcatState>,
+) -> Result<Workspace, String> {
+    info!("Loading workspace: {}", name);
+    let loaded = workspace::load(&workspaces_dir()?, &name)?;
+    state.alerts.lock().await.set_rules(&loaded.alert_rules)?;
+    settings::update(serde_json::json!({ "activeWorkspace": name }))?;
+    Ok(loaded)
+}
+
+/// Re-apply the active workspace's alert rules on startup
+pub fn restore_active_workspace(app: &AppHandle) {
+    let Some(name) = settings::current().active_workspace else {
+        return;
+    };
+    let loaded = workspaces_dir().and_then(|dir| workspace::load(&dir, &name));
+    match loaded {
+        Ok(ws) => {
+            let state = app.state::<LogcatState>();
+            if let Err(e) = state.alerts.blocking_lock().set_rules(&ws.alert_rules) {
+                error!("Failed to restore alert rules: {}", e);
+            }
+            info!("Restored workspace: {}", name);
+        }
+        Err(e) => error!("Failed to restore workspace {}: {}", name, e),
+    }
+}
+
+/// Replace the alert rules evaluated against the live stream
+#[tauri::command]
+pub async fn set_alert_rules(
+    rules: Vec<AlertRule>,
+    state: State<'_, LogcatState>,
+) -> Result<(), String> {
+    info!("Setting {} alert rules", rules.len());
+    state.alerts.lock().await.set_rules(&rules)
+}
+
+/// Delete a workspace, clearing it as active if needed
+#[tauri::command]
+pub async fn delete_workspace(name: String) -> Result<(), String> {
+    info!("Deleting workspace: {}", name);
+    workspace::delete(&workspaces_dir()?, &name)?;
+    if settings::current().active_workspace.as_deref() == Some(name.as_str()) {
+        settings::update(serde_json::json!({ "activeWorkspace": null }))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,332 @@
+use std::path::{Path, PathBuf};
+
+use crate::filter::LogFilter;
+use crate::parser::{LogEntry, LogLevel, LogParser};
+use crate::sessions::{SessionInfo, SessionMetadata};
+
+/// File formats `session convert` reads and writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFormat {
+    /// A saved session's `.json` record, with its `.log` file beside it
+    Session,
+    /// One serialized `LogEntry` per line
+    Jsonl,
+    /// Plain logcat threadtime lines
+    Logcat,
+    /// `time,pid,tid,level,tag,package,message` with a header row
+    Csv,
+}
+
+const CSV_HEADER: &str = "time,pid,tid,level,tag,package,message";
+
+impl ConvertFormat {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "session" => Ok(ConvertFormat::Session),
+            "jsonl" => Ok(ConvertFormat::Jsonl),
+            "logcat" | "log" | "txt" => Ok(ConvertFormat::Logcat),
+            "csv" => Ok(ConvertFormat::Csv),
+            _ => Err(format!("Unknown format: {}", name)),
+        }
+    }
+
+    /// Format implied by a file extension; anything unknown is plain logcat
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => ConvertFormat::Session,
+            Some("jsonl") | Some("ndjson") => ConvertFormat::Jsonl,
+            Some("csv") => ConvertFormat::Csv,
+            _ => ConvertFormat::Logcat,
+        }
+    }
+}
+
+/// Entries read from a file, plus the device ID when the file records one
+pub struct ReadResult {
+    pub entries: Vec<LogEntry>,
+    pub device_id: Option<String>,
+    /// Lines that could not be parsed
+    pub skipped: usize,
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    std::fs::read(path)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Parse logcat lines, skipping buffer banners and anything unparsable
+fn parse_logcat(text: &str) -> (Vec<LogEntry>, usize) {
+    let mut parser = LogParser::new();
+    let mut skipped = 0;
+    let entries = text
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("--------- beginning of"))
+        .filter_map(|line| {
+            let entry = parser.parse_line(line);
+            skipped += usize::from(entry.is_none());
+            entry
+        })
+        .collect();
+    (entries, skipped)
+}
+
+pub fn read(path: &Path, format: ConvertFormat) -> Result<ReadResult, String> {
+    let text = read_text(path)?;
+    match format {
+        ConvertFormat::Session => {
+            let info: SessionInfo = serde_json::from_str(&text)
+                .map_err(|e| format!("Invalid session record {}: {}", path.display(), e))?;
+            // Sessions are often moved as a pair, so also look beside the record
+            let mut log_path = PathBuf::from(&info.log_path);
+            if !log_path.exists() {
+                log_path = path.with_extension("log");
+            }
+            let (entries, skipped) = parse_logcat(&read_text(&log_path)?);
+            Ok(ReadResult {
+                entries,
+                device_id: Some(info.device_id),
+                skipped,
+            })
+        }
+        ConvertFormat::Jsonl => {
+            let mut skipped = 0;
+            let entries = text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| {
+                    let entry = serde_json::from_str::<LogEntry>(line).ok();
+                    skipped += usize::from(entry.is_none());
+                    entry
+                })
+                .collect();
+            Ok(ReadResult {
+                entries,
+                device_id: None,
+                skipped,
+            })
+        }
+        ConvertFormat::Logcat => {
+            let (entries, skipped) = parse_logcat(&text);
+            Ok(ReadResult {
+                entries,
+                device_id: None,
+                skipped,
+            })
+        }
+        ConvertFormat::Csv => {
+            let (entries, skipped) = parse_csv(&text);
+            Ok(ReadResult {
+                entries,
+                device_id: None,
+                skipped,
+            })
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(entry: &LogEntry) -> String {
+    [
+        csv_field(entry.date_time.as_deref().unwrap_or(&entry.timestamp)),
+        entry.pid.to_string(),
+        entry.tid.to_string(),
+        format!("{:?}", entry.level),
+        csv_field(&entry.tag),
+        csv_field(entry.package_name.as_deref().unwrap_or("")),
+        csv_field(&entry.message),
+    ]
+    .join(",")
+}
+
+/// Split CSV text into records; quoted fields may hold commas, quotes and newlines
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !quoted => {}
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Build an entry from each row's columns; rows with a bad pid, tid or level
+/// are skipped
+fn parse_csv(text: &str) -> (Vec<LogEntry>, usize) {
+    let parser = LogParser::new();
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for record in csv_records(text).into_iter().skip(1) {
+        let [time, pid, tid, level, tag, package, message] = record.as_slice() else {
+            skipped += 1;
+            continue;
+        };
+        let ids = pid.trim().parse().ok().zip(tid.trim().parse().ok());
+        let level = level.trim().chars().next().and_then(LogLevel::from_char);
+        let (Some(ids), Some(level)) = (ids, level) else {
+            skipped += 1;
+            continue;
+        };
+        let mut entry = parser.column_entry(time, ids, level, tag, message);
+        entry.package_name = (!package.is_empty()).then(|| package.clone());
+        entries.push(entry);
+    }
+    (entries, skipped)
+}
+
+/// Write `entries` to `path`. A session is written as `<path>` plus its
+/// `.log` file, recording `device_id`.
+pub fn write(
+    path: &Path,
+    format: ConvertFormat,
+    entries: &[LogEntry],
+    device_id: &str,
+) -> Result<(), String> {
+    let lines = |line: fn(&LogEntry) -> String| {
+        entries.iter().map(|entry| line(entry) + "\n").collect::<String>()
+    };
+    let text = match format {
+        ConvertFormat::Session => {
+            let log_path = path.with_extension("log");
+            std::fs::write(&log_path, lines(LogEntry::to_line))
+                .map_err(|e| format!("Failed to write {}: {}", log_path.display(), e))?;
+            let info = SessionInfo {
+                id: path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                device_id: device_id.to_string(),
+                saved_at: chrono::Local::now().to_rfc3339(),
+                entry_count: entries.len(),
+                log_path: log_path.display().to_string(),
+                metadata: SessionMetadata::default(),
+                pinned: Vec::new(),
+            };
+            serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?
+        }
+        ConvertFormat::Jsonl => lines(|entry| serde_json::to_string(entry).unwrap_or_default()),
+        ConvertFormat::Logcat => lines(LogEntry::to_line),
+        ConvertFormat::Csv => format!("{}\n{}", CSV_HEADER, lines(csv_row)),
+    };
+    std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Counts reported after a conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertSummary {
+    pub read: usize,
+    pub written: usize,
+    pub skipped: usize,
+}
+
+/// Convert `input` to `output`, keeping the entries `filter` matches
+pub fn convert(
+    (input, from): (&Path, ConvertFormat),
+    (output, to): (&Path, ConvertFormat),
+    filter: &LogFilter,
+) -> Result<ConvertSummary, String> {
+    let read = read(input, from)?;
+    let entries: Vec<LogEntry> = read
+        .entries
+        .iter()
+        .filter(|entry| filter.matches(entry))
+        .cloned()
+        .collect();
+    let device_id = read.device_id.as_deref().unwrap_or("unknown");
+    write(output, to, &entries, device_id)?;
+    Ok(ConvertSummary {
+        read: read.entries.len(),
+        written: entries.len(),
+        skipped: read.skipped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::FilterConfig;
+
+    #[test]
+    fn test_round_trip_with_filter() {
+        let dir = std::env::temp_dir().join(format!("convert-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("capture.log");
+        std::fs::write(
+            &log,
+            "--------- beginning of main\n\
+             12-04 10:30:45.123  1234  1234 I Checkout: order placed, total 12\n\
+             12-04 10:30:45.456  1234  1234 W Checkout: retrying \"payment\"\n\
+             not a logcat line\n",
+        )
+        .unwrap();
+
+        let csv = dir.join("capture.csv");
+        let all = LogFilter::default();
+        let summary = convert((&log, ConvertFormat::Logcat), (&csv, ConvertFormat::Csv), &all).unwrap();
+        assert_eq!(summary, ConvertSummary { read: 2, written: 2, skipped: 1 });
+
+        let warnings = LogFilter::new(FilterConfig {
+            levels: vec![LogLevel::W],
+            ..Default::default()
+        });
+        let session = dir.join("warnings.json");
+        convert((&csv, ConvertFormat::Csv), (&session, ConvertFormat::Session), &warnings).unwrap();
+        let jsonl = dir.join("warnings.jsonl");
+        convert((&session, ConvertFormat::Session), (&jsonl, ConvertFormat::Jsonl), &all).unwrap();
+        let back = read(&jsonl, ConvertFormat::Jsonl).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(back.entries.len(), 1);
+        assert_eq!(back.entries[0].message, "retrying \"payment\"");
+        assert_eq!(back.entries[0].tag, "Checkout");
+        assert_eq!(ConvertFormat::from_path(Path::new("a.ndjson")), ConvertFormat::Jsonl);
+    }
+
+    #[test]
+    fn test_csv_columns() {
+        let text = format!(
+            "{}\n\
+             10:30:45.123,1234,1235,E,Net,com.example,\"first line\nsecond, line\"\n\
+             12-04 10:30:46.000,1234,1234,W,Net,,ok\n\
+             12-04 10:30:47.000,x,1234,W,Net,,bad pid\n",
+            CSV_HEADER
+        );
+        let (entries, skipped) = parse_csv(&text);
+        assert_eq!(skipped, 1);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "first line\nsecond, line");
+        assert_eq!((entries[0].tid, entries[0].level), (1235, LogLevel::E));
+        assert_eq!(entries[0].timestamp, "10:30:45.123");
+        assert!(entries[0].time.is_some());
+        assert_eq!(entries[0].package_name.as_deref(), Some("com.example"));
+        assert_eq!(entries[1].date_time.as_deref(), Some("12-04 10:30:46.000"));
+        assert_eq!(entries[1].package_name, None);
+    }
+}
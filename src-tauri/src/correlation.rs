@@ -0,0 +1,59 @@
+use regex::Regex;
+
+/// Default pattern: `requestId=...`, `trace_id: ...`, `"correlationId":"..."` and similar
+pub const DEFAULT_PATTERN: &str =
+    r#"(?i)\b(?:request|trace|correlation|transaction)[_-]?id"?\s*[=:]\s*"?([\w.:-]+)"#;
+
+/// Compiled correlation patterns. The first pattern whose first capture group
+/// matches an entry's message gives its correlation ID.
+#[derive(Default)]
+pub struct CorrelationExtractor {
+    patterns: Vec<Regex>,
+}
+
+impl CorrelationExtractor {
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let mut compiled = Vec::new();
+        for pattern in patterns {
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid correlation pattern {}: {}", pattern, e))?;
+            if regex.captures_len() < 2 {
+                return Err(format!("Correlation pattern {} needs a capture group", pattern));
+            }
+            compiled.push(regex);
+        }
+        Ok(CorrelationExtractor { patterns: compiled })
+    }
+
+    pub fn extract(&self, message: &str) -> Option<String> {
+        self.patterns.iter().find_map(|regex| {
+            regex
+                .captures(message)
+                .and_then(|caps| caps.get(1))
+                .map(|id| id.as_str().to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_with_default_and_custom_patterns() {
+        let extractor = CorrelationExtractor::new(&[DEFAULT_PATTERN.to_string()]).unwrap();
+        assert_eq!(
+            extractor.extract("POST /orders done requestId=7f3a-19c2 in 120ms").as_deref(),
+            Some("7f3a-19c2")
+        );
+        assert_eq!(
+            extractor.extract(r#"{"traceId":"abc123","span":"db"}"#).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(extractor.extract("no identifiers here"), None);
+
+        let custom = CorrelationExtractor::new(&[r"\[txn ([0-9a-f]+)\]".to_string()]).unwrap();
+        assert_eq!(custom.extract("[txn 00ff] commit").as_deref(), Some("00ff"));
+        assert!(CorrelationExtractor::new(&["txn".to_string()]).is_err());
+    }
+}
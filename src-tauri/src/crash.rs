@@ -0,0 +1,242 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::parser::LogEntry;
+
+/// Signature file name inside the app config directory
+pub const SIGNATURES_FILE: &str = "crash_signatures.json";
+
+/// "Process: com.example, PID: 1234"
+static PROCESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Process: ([\w.:]+), PID: \d+").expect("Invalid crash process regex")
+});
+
+/// Known issue a crash was matched to
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KnownIssue {
+    pub id: String,
+    pub title: String,
+}
+
+/// A Java crash assembled from consecutive `AndroidRuntime` lines
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub device_id: String,
+    pub pid: u32,
+    pub package_name: Option<String>,
+    /// Exception line, e.g. "java.lang.IllegalStateException: boom"
+    pub exception: String,
+    /// Stack frames without the leading "at "
+    pub frames: Vec<String>,
+    pub time: String,
+    /// Entry ID of the `FATAL EXCEPTION` line
+    pub entry_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub known: Option<KnownIssue>,
+}
+
+impl CrashReport {
+    pub fn top_frame(&self) -> Option<&str> {
+        self.frames.first().map(String::as_str)
+    }
+}
+
+/// Assembles crash reports from a device's stream
+#[derive(Debug, Default)]
+pub struct CrashDetector {
+    current: Option<CrashReport>,
+}
+
+impl CrashDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed an entry; returns a crash once its stack trace is complete
+    pub fn observe(&mut self, device_id: &str, entry: &LogEntry) -> Option<CrashReport> {
+        if entry.tag != "AndroidRuntime" {
+            // The crashing process has stopped printing once someone else logs
+            let done = self
+                .current
+                .as_ref()
+                .is_some_and(|c| c.pid != entry.pid && !c.frames.is_empty());
+            return if done { self.current.take() } else { None };
+        }
+
+        if entry.message.starts_with("FATAL EXCEPTION") {
+            let finished = self.current.take();
+            self.current = Some(CrashReport {
+                device_id: device_id.to_string(),
+                pid: entry.pid,
+                package_name: entry.package_name.clone(),
+                exception: String::new(),
+                frames: Vec::new(),
+                time: entry.date_time.clone().unwrap_or_else(|| entry.timestamp.clone()),
+                entry_id: entry.id,
+                known: None,
+            });
+            return finished;
+        }
+
+        let crash = self.current.as_mut()?;
+        let line = entry.message.trim();
+        if let Some(caps) = PROCESS_REGEX.captures(line) {
+            crash.package_name.get_or_insert_with(|| caps[1].to_string());
+        } else if let Some(frame) = line.strip_prefix("at ") {
+            crash.frames.push(frame.to_string());
+        } else if crash.exception.is_empty() {
+            crash.exception = line.to_string();
+        }
+        // "Caused by:" and "... N more" lines belong to the same report
+        None
+    }
+
+    /// Return the pending crash, e.g. when the stream ends
+    pub fn flush(&mut self) -> Option<CrashReport> {
+        self.current.take().filter(|c| !c.exception.is_empty())
+    }
+}
+
+/// Known crash imported from a crash reporting console
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashSignature {
+    pub id: String,
+    pub title: String,
+    /// Regex matched against the exception line
+    pub exception: String,
+    /// Regex matched against the top stack frame
+    #[serde(default)]
+    pub top_frame: Option<String>,
+}
+
+/// Labels crashes with the first matching signature
+#[derive(Debug, Default)]
+pub struct SignatureMatcher {
+    signatures: Vec<(KnownIssue, Regex, Option<Regex>)>,
+}
+
+impl SignatureMatcher {
+    pub fn new(signatures: &[CrashSignature]) -> Result<Self, String> {
+        let compile = |id: &str, pattern: &str| {
+            Regex::new(pattern).map_err(|e| format!("Invalid pattern in signature {}: {}", id, e))
+        };
+        let mut compiled = Vec::with_capacity(signatures.len());
+        for sig in signatures {
+            let exception = compile(&sig.id, &sig.exception)?;
+            let top_frame = match &sig.top_frame {
+                Some(pattern) => Some(compile(&sig.id, pattern)?),
+                None => None,
+            };
+            let issue = KnownIssue {
+                id: sig.id.clone(),
+                title: sig.title.clone(),
+            };
+            compiled.push((issue, exception, top_frame));
+        }
+        Ok(SignatureMatcher {
+            signatures: compiled,
+        })
+    }
+
+    pub fn find(&self, crash: &CrashReport) -> Option<KnownIssue> {
+        self.signatures
+            .iter()
+            .find(|(_, exception, top_frame)| {
+                exception.is_match(&crash.exception)
+                    && top_frame.as_ref().map_or(true, |re| {
+                        crash.top_frame().is_some_and(|frame| re.is_match(frame))
+                    })
+            })
+            .map(|(issue, _, _)| issue.clone())
+    }
+}
+
+/// Load signatures from a JSON array file
+pub fn load_signatures(path: &Path) -> Result<Vec<CrashSignature>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read crash signatures: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid crash signatures: {}", e))
+}
+
+/// Persist signatures as a JSON array
+pub fn save_signatures(path: &Path, signatures: &[CrashSignature]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let text = serde_json::to_string_pretty(signatures).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("Failed to save crash signatures: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    const CRASH: &[&str] = &[
+        "12-04 10:30:45.100  4321  4321 E AndroidRuntime: FATAL EXCEPTION: main",
+        "12-04 10:30:45.100  4321  4321 E AndroidRuntime: Process: com.example.app, PID: 4321",
+        "12-04 10:30:45.100  4321  4321 E AndroidRuntime: java.lang.NullPointerException: view is null",
+        "12-04 10:30:45.100  4321  4321 E AndroidRuntime: \tat com.example.app.MainActivity.onClick(MainActivity.java:42)",
+        "12-04 10:30:45.100  4321  4321 E AndroidRuntime: \tat android.view.View.performClick(View.java:7448)",
+        "12-04 10:30:45.200  1000  1200 W ActivityTaskManager:   Force finishing activity com.example.app/.MainActivity",
+    ];
+
+    fn detect() -> CrashReport {
+        let mut parser = LogParser::new();
+        let mut detector = CrashDetector::new();
+        let mut found = None;
+        for line in CRASH {
+            if let Some(crash) = detector.observe("dev", &parser.parse_line(line).unwrap()) {
+                found = Some(crash);
+            }
+        }
+        found.expect("crash not detected")
+    }
+
+    #[test]
+    fn test_assemble_crash() {
+        let crash = detect();
+        assert_eq!(crash.pid, 4321);
+        assert_eq!(crash.package_name.as_deref(), Some("com.example.app"));
+        assert_eq!(crash.exception, "java.lang.NullPointerException: view is null");
+        assert_eq!(crash.frames.len(), 2);
+        assert_eq!(
+            crash.top_frame(),
+            Some("com.example.app.MainActivity.onClick(MainActivity.java:42)")
+        );
+    }
+
+    #[test]
+    fn test_signature_match() {
+        let crash = detect();
+        let matcher = SignatureMatcher::new(&[
+            CrashSignature {
+                id: "7".to_string(),
+                title: "NPE in settings".to_string(),
+                exception: "NullPointerException".to_string(),
+                top_frame: Some(r"SettingsActivity\.".to_string()),
+            },
+            CrashSignature {
+                id: "123".to_string(),
+                title: "NPE on click".to_string(),
+                exception: "NullPointerException".to_string(),
+                top_frame: Some(r"MainActivity\.onClick".to_string()),
+            },
+        ])
+        .unwrap();
+        assert_eq!(matcher.find(&crash).map(|k| k.id), Some("123".to_string()));
+
+        let unrelated = SignatureMatcher::new(&[CrashSignature {
+            id: "9".to_string(),
+            title: "OOM".to_string(),
+            exception: "OutOfMemoryError".to_string(),
+            top_frame: None,
+        }])
+        .unwrap();
+        assert!(unrelated.find(&crash).is_none());
+    }
+}
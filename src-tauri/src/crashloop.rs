@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::crash::CrashReport;
+use crate::parser::LogEntry;
+
+/// Capture folders directory inside the app config directory
+pub const CRASH_LOOPS_DIR: &str = "crash-loops";
+
+/// History kept before the first crash of a loop, for what led up to it
+const LEAD_MS: u64 = 60_000;
+
+/// Payload of the `crash-loop` event, sent when a package keeps crashing.
+/// The capture is written before the event; the bugreport follows in
+/// `crash-loop-exported`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashLoop {
+    pub device_id: String,
+    pub package_name: String,
+    pub window_mins: u64,
+    /// Crashes within the window, oldest first
+    pub crashes: Vec<CrashReport>,
+    /// Capture folder, once written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+/// Payload of the `crash-loop-exported` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashLoopExported {
+    pub device_id: String,
+    pub package_name: String,
+    pub folder: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bugreport: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Spots a package crashing `threshold` times within `window`
+#[derive(Debug)]
+pub struct CrashLoopDetector {
+    threshold: usize,
+    window: Duration,
+    recent: HashMap<String, VecDeque<(Instant, CrashReport)>>,
+}
+
+impl CrashLoopDetector {
+    /// A threshold of 0 turns detection off
+    pub fn new(threshold: u32, window_mins: u64) -> Self {
+        CrashLoopDetector {
+            threshold: threshold as usize,
+            window: Duration::from_secs(window_mins * 60),
+            recent: HashMap::new(),
+        }
+    }
+
+    /// Record a crash; returns the loop when it completes one. The package's
+    /// crashes are then forgotten, so a continuing loop reports again only
+    /// after another `threshold` crashes.
+    pub fn observe(&mut self, crash: &CrashReport, now: Instant) -> Option<CrashLoop> {
+        if self.threshold == 0 {
+            return None;
+        }
+        let package = crash.package_name.as_ref()?;
+        let recent = self.recent.entry(package.clone()).or_default();
+        while recent
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > self.window)
+        {
+            recent.pop_front();
+        }
+        recent.push_back((now, crash.clone()));
+        if recent.len() < self.threshold {
+            return None;
+        }
+        let crashes = recent.drain(..).map(|(_, crash)| crash).collect();
+        Some(CrashLoop {
+            device_id: crash.device_id.clone(),
+            package_name: package.clone(),
+            window_mins: self.window.as_secs() / 60,
+            crashes,
+            folder: None,
+        })
+    }
+}
+
+/// History entries from shortly before the loop's first crash on
+pub fn window_entries(entries: &[&LogEntry], crash_loop: &CrashLoop) -> Vec<LogEntry> {
+    let first_id = crash_loop.crashes.first().map_or(0, |crash| crash.entry_id);
+    let start = entries
+        .iter()
+        .find(|entry| entry.id == first_id)
+        .and_then(|entry| entry.epoch)
+        .map(|epoch| epoch.saturating_sub(LEAD_MS));
+    entries
+        .iter()
+        .filter(|entry| match (start, entry.epoch) {
+            (Some(start), Some(epoch)) => epoch >= start,
+            // The first crash already left the history: keep all of it
+            (None, _) => true,
+            (Some(_), None) => false,
+        })
+        .map(|entry| (*entry).clone())
+        .collect()
+}
+
+/// Write `history.log` and `crashes.json` to a new timestamped folder under
+/// `dir`, returning the folder
+pub fn write_capture(
+    dir: &Path,
+    crash_loop: &CrashLoop,
+    entries: &[LogEntry],
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<PathBuf, String> {
+    let name: String = format!("{}-{}", now.format("%Y%m%d-%H%M%S"), crash_loop.package_name)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') { c } else { '_' })
+        .collect();
+    let folder = dir.join(name);
+    std::fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create {}: {}", folder.display(), e))?;
+
+    let history: String = entries.iter().map(|entry| entry.to_line() + "\n").collect();
+    std::fs::write(folder.join("history.log"), history)
+        .map_err(|e| format!("Failed to write crash loop history: {}", e))?;
+    let crashes = serde_json::to_string_pretty(&crash_loop.crashes).map_err(|e| e.to_string())?;
+    std::fs::write(folder.join("crashes.json"), crashes)
+        .map_err(|e| format!("Failed to write crash reports: {}", e))?;
+    Ok(folder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crash::CrashDetector;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_loop_detection_and_capture() {
+        let mut parser = LogParser::new();
+        let mut crashes = CrashDetector::new();
+        let mut history = Vec::new();
+        let mut reports = Vec::new();
+        for (time, pid) in [("10:28:00", 100), ("10:30:10", 101), ("10:30:20", 102)] {
+            for message in [
+                "FATAL EXCEPTION: main".to_string(),
+                format!("Process: com.example, PID: {}", pid),
+                "java.lang.IllegalStateException: boom".to_string(),
+                "\tat com.example.Main.onCreate(Main.kt:10)".to_string(),
+            ] {
+                let line = format!("12-04 {}.000 {} {} E AndroidRuntime: {}", time, pid, pid, message);
+                let entry = parser.parse_line(&line).unwrap();
+                history.push(entry.clone());
+                reports.extend(crashes.observe("dev", &entry));
+            }
+        }
+        reports.extend(crashes.flush());
+        assert_eq!(reports.len(), 3);
+
+        let start = Instant::now();
+        let mut detector = CrashLoopDetector::new(3, 5);
+        assert!(detector.observe(&reports[0], start).is_none());
+        // Outside the window: the first crash no longer counts
+        assert!(detector.observe(&reports[1], start + Duration::from_secs(301)).is_none());
+        assert!(detector.observe(&reports[2], start + Duration::from_secs(302)).is_none());
+        let crash_loop = detector
+            .observe(&reports[2], start + Duration::from_secs(303))
+            .unwrap();
+        assert_eq!(crash_loop.package_name, "com.example");
+        assert_eq!(crash_loop.crashes.len(), 3);
+
+        // From a minute before the first crash in the window
+        let entries = window_entries(&history.iter().collect::<Vec<_>>(), &crash_loop);
+        assert_eq!(entries.len(), 8);
+
+        let dir = std::env::temp_dir().join(format!("crashloop-test-{}", std::process::id()));
+        let folder = write_capture(&dir, &crash_loop, &entries, chrono::Local::now()).unwrap();
+        let log = std::fs::read_to_string(folder.join("history.log")).unwrap();
+        let saved = std::fs::read_to_string(folder.join("crashes.json")).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(log.lines().count(), 8);
+        assert!(saved.contains("IllegalStateException"));
+    }
+}
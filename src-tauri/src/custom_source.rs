@@ -0,0 +1,409 @@
+use log::{error, info};
+use std::borrow::Cow;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::process::Child;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+
+use crate::parser::{self, LogEntry, LogLevel, LogParser};
+use crate::sanitize;
+use crate::source::{self, LineParser, LogSource};
+use crate::template::{self, CompiledTemplate, ParserTemplate};
+
+/// Line format of a custom source or imported file
+#[derive(Debug, Clone)]
+pub enum SourceFormat {
+    /// Logcat threadtime/brief lines
+    Logcat,
+    /// Only the given templates
+    Templates(Vec<CompiledTemplate>),
+    /// Logcat first, then every registered template
+    Auto(Vec<CompiledTemplate>),
+}
+
+impl SourceFormat {
+    /// Parse "threadtime", "brief", "auto", "template:<name>" or "regex:<pattern>"
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "threadtime" | "brief" | "logcat" => return Ok(SourceFormat::Logcat),
+            "auto" => return Ok(SourceFormat::Auto(template::registered())),
+            _ => {}
+        }
+        if let Some(name) = format.strip_prefix("template:") {
+            return Ok(SourceFormat::Templates(vec![template::find(name)?]));
+        }
+        let pattern = format
+            .strip_prefix("regex:")
+            .ok_or_else(|| format!("Unknown source format: {}", format))?;
+        let adhoc = ParserTemplate {
+            name: "regex".to_string(),
+            pattern: pattern.to_string(),
+            timestamp_format: None,
+        };
+        Ok(SourceFormat::Templates(vec![adhoc.compile()?]))
+    }
+}
+
+/// Parser for custom sources; lines that don't match become plain info entries
+pub struct CustomParser {
+    name: String,
+    format: SourceFormat,
+    logcat: LogParser,
+}
+
+impl CustomParser {
+    pub fn new(name: &str, format: SourceFormat) -> Self {
+        CustomParser {
+            name: name.to_string(),
+            format,
+            logcat: LogParser::new(),
+        }
+    }
+
+    fn plain_entry(&self, line: &str) -> LogEntry {
+        let now = chrono::Local::now();
+        let timestamp = now.format("%H:%M:%S%.3f").to_string();
+        LogEntry {
+            id: parser::next_entry_id(),
+            seq: 0,
+            timestamp: timestamp.clone(),
+            date_time: Some(format!("{} {}", now.format("%m-%d"), timestamp)),
+            epoch: Some(now.timestamp_millis() as u64),
+            time: Some(now.into()),
+            pid: 0,
+            tid: 0,
+            level: LogLevel::I,
+            tag: self.name.clone(),
+            message: line.to_string(),
+            package_name: None,
+            process_name: None,
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
+            raw: Some(line.to_string()),
+        }
+    }
+
+    fn try_templates(templates: &[CompiledTemplate], line: &str) -> Option<LogEntry> {
+        let mut entry = templates.iter().find_map(|t| t.parse(line, 0))?;
+        // Allocated only on a match; unmatched lines get theirs in `plain_entry`
+        entry.id = parser::next_entry_id();
+        Some(entry)
+    }
+}
+
+impl LineParser for CustomParser {
+    fn parse_line(&mut self, line: &str) -> Option<LogEntry> {
+        let original = line.trim_end();
+        if original.is_empty() {
+            return None;
+        }
+
+        // Templates are written against uncolored output
+        let (uncolored, color_level) = sanitize::strip_color(original);
+        let colored = matches!(uncolored, Cow::Owned(_));
+        let line = uncolored.as_ref();
+        let parsed = match &self.format {
+            SourceFormat::Logcat => self.logcat.parse_line(line),
+            SourceFormat::Templates(templates) => Self::try_templates(templates, line),
+            SourceFormat::Auto(templates) => self
+                .logcat
+                .parse_line(line)
+                .or_else(|| Self::try_templates(templates, line)),
+        };
+        let mut entry = parsed.unwrap_or_else(|| self.plain_entry(line));
+        if entry.tag.is_empty() {
+            entry.tag = self.name.clone();
+        }
+        entry.process_name = Some(self.name.clone());
+        if colored {
+            entry.raw = Some(original.to_string());
+            entry.color_level = color_level;
+            entry.sanitized = true;
+        }
+        sanitize::apply(&mut entry);
+        Some(entry)
+    }
+}
+
+/// A shell-less command line, split on whitespace
+struct CommandSource {
+    name: String,
+    command: String,
+    format: SourceFormat,
+}
+
+impl LogSource for CommandSource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn command(&self) -> (String, Vec<String>) {
+        let mut parts = self.command.split_whitespace().map(str::to_string);
+        let program = parts.next().unwrap_or_default();
+        (program, parts.collect())
+    }
+
+    fn parser(&self) -> Box<dyn LineParser> {
+        Box::new(CustomParser::new(&self.name, self.format.clone()))
+    }
+}
+
+/// Lines appended to a file on a device, read with `tail -F` over adb. With a
+/// package the file is read as that (debuggable) app via `run-as`, so paths
+/// may be relative to its data dir; otherwise the shell user, or root after
+/// `adb root`, must be able to read it.
+pub struct DeviceFileSource {
+    pub adb_path: String,
+    pub device_id: String,
+    pub path: String,
+    pub package: Option<String>,
+    pub format: SourceFormat,
+}
+
+impl DeviceFileSource {
+    /// Check the path and package before they reach `adb shell`, which joins
+    /// its arguments into one shell command line
+    pub fn validate(path: &str, package: Option<&str>) -> Result<(), String> {
+        let only = |text: &str, extra: &str| {
+            !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+        };
+        if let Some(package) = package {
+            if !only(package, "._") {
+                return Err(format!("Invalid package name: {}", package));
+            }
+        } else if !path.starts_with('/') {
+            return Err("Path must be absolute unless a package is given".to_string());
+        }
+        if !only(path, "/._-") {
+            return Err(format!("Unsupported characters in path: {}", path));
+        }
+        if path.split('/').any(|part| part == "..") {
+            return Err("Path must not contain ..".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl LogSource for DeviceFileSource {
+    fn name(&self) -> String {
+        format!("{}:{}", self.device_id, self.path)
+    }
+
+    fn command(&self) -> (String, Vec<String>) {
+        let mut args: Vec<String> = ["-s", &self.device_id, "shell"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        if let Some(package) = &self.package {
+            args.extend(["run-as".to_string(), package.clone()]);
+        }
+        args.extend(["tail", "-n", "0", "-F", &self.path].iter().map(|s| s.to_string()));
+        (self.adb_path.clone(), args)
+    }
+
+    fn parser(&self) -> Box<dyn LineParser> {
+        Box::new(CustomParser::new(&self.name(), self.format.clone()))
+    }
+}
+
+/// Batch of entries from a custom source, emitted as `custom-source-entries`
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomSourceBatch<'a> {
+    pub source: &'a str,
+    pub entries: &'a [LogEntry],
+}
+
+/// Running custom source; dropping it stops the source
+pub struct CustomSourceHandle {
+    pub target: String,
+    child: Option<Child>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl CustomSourceHandle {
+    /// Stop the process/listener and its forwarding task
+    pub async fn stop(mut self) {
+        if let Some(ref mut child) = self.child {
+            let _ = child.kill().await;
+        }
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Start a custom source: a port number listens on 127.0.0.1, anything else is run as a command
+pub async fn start(
+    app: AppHandle,
+    name: String,
+    command_or_port: String,
+    format: SourceFormat,
+) -> Result<CustomSourceHandle, String> {
+    let (tx, rx) = mpsc::channel::<LogEntry>(1000);
+    let mut tasks = Vec::new();
+    let mut child = None;
+
+    if let Ok(port) = command_or_port.trim().parse::<u16>() {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+        info!("Custom source {} listening on 127.0.0.1:{}", name, port);
+        let source_name = name.clone();
+        tasks.push(tokio::spawn(accept_loop(listener, source_name, format, tx)));
+    } else {
+        let source = CommandSource {
+            name: name.clone(),
+            command: command_or_port.clone(),
+            format,
+        };
+        child = Some(source::spawn(&source, tx)?);
+    }
+
+    tasks.push(tokio::spawn(forward(app, name, rx)));
+
+    Ok(CustomSourceHandle {
+        target: command_or_port,
+        child,
+        tasks,
+    })
+}
+
+/// Start a custom source from any `LogSource`, named after it
+pub fn start_source(app: AppHandle, source: &dyn LogSource) -> Result<CustomSourceHandle, String> {
+    let (tx, rx) = mpsc::channel::<LogEntry>(1000);
+    let child = source::spawn(source, tx)?;
+    Ok(CustomSourceHandle {
+        target: source.name(),
+        child: Some(child),
+        tasks: vec![tokio::spawn(forward(app, source.name(), rx))],
+    })
+}
+
+/// Accept TCP clients and feed each connection's lines through its own parser
+async fn accept_loop(
+    listener: TcpListener,
+    name: String,
+    format: SourceFormat,
+    tx: mpsc::Sender<LogEntry>,
+) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!("Custom source {} accept failed: {}", name, e);
+                continue;
+            }
+        };
+        info!("Custom source {} accepted {}", name, addr);
+        let mut parser = CustomParser::new(&name, format.clone());
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            source::pump(BufReader::new(stream), &mut parser, &tx).await;
+        });
+    }
+}
+
+/// Batch entries and emit them to the frontend
+async fn forward(app: AppHandle, name: String, mut rx: mpsc::Receiver<LogEntry>) {
+    let mut batch: Vec<LogEntry> = Vec::with_capacity(100);
+    loop {
+        match timeout(Duration::from_millis(100), rx.recv()).await {
+            Ok(Some(entry)) => {
+                batch.push(entry);
+                if batch.len() < 100 {
+                    continue;
+                }
+            }
+            Ok(None) => break,
+            Err(_) => {}
+        }
+        if !batch.is_empty() {
+            let payload = CustomSourceBatch {
+                source: &name,
+                entries: &batch,
+            };
+            if let Err(e) = app.emit("custom-source-entries", &payload) {
+                error!("Failed to emit custom source entries: {}", e);
+            }
+            batch.clear();
+        }
+    }
+    info!("Custom source {} finished", name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_format_parse() {
+        assert!(matches!(SourceFormat::parse("threadtime"), Ok(SourceFormat::Logcat)));
+        assert!(matches!(
+            SourceFormat::parse("regex:(?P<message>.*)"),
+            Ok(SourceFormat::Templates(_))
+        ));
+        assert!(SourceFormat::parse("regex:(").is_err());
+        assert!(SourceFormat::parse("json").is_err());
+    }
+
+    #[test]
+    fn test_regex_format() {
+        let format = SourceFormat::parse(r"regex:^\[(?P<level>\w+)\] (?P<tag>\w+): (?P<message>.*)$").unwrap();
+        let mut parser = CustomParser::new("server", format);
+        let entry = parser.parse_line("[warn] Db: slow query").unwrap();
+        assert_eq!(entry.level, LogLevel::W);
+        assert_eq!(entry.tag, "Db");
+        assert_eq!(entry.message, "slow query");
+
+        let colored = parser.parse_line("\u{1B}[33m[warn] Db: lock wait\u{1B}[0m").unwrap();
+        assert_eq!((colored.tag.as_str(), colored.message.as_str()), ("Db", "lock wait"));
+        assert_eq!(colored.color_level, Some(LogLevel::W));
+    }
+
+    #[test]
+    fn test_unmatched_line_is_kept() {
+        let mut parser = CustomParser::new("uart", SourceFormat::Logcat);
+        let entry = parser.parse_line("U-Boot 2020.01 booting").unwrap();
+        assert_eq!(entry.tag, "uart");
+        assert_eq!(entry.process_name.as_deref(), Some("uart"));
+        assert_eq!(entry.level, LogLevel::I);
+        assert_eq!(entry.message, "U-Boot 2020.01 booting");
+        assert!(parser.parse_line("next").unwrap().id > entry.id);
+    }
+
+    #[test]
+    fn test_device_file_source() {
+        assert!(DeviceFileSource::validate("files/logs/app.log", Some("com.example.app")).is_ok());
+        assert!(DeviceFileSource::validate("/data/local/tmp/agent.log", None).is_ok());
+        assert!(DeviceFileSource::validate("files/app.log", None).is_err());
+        assert!(DeviceFileSource::validate("files/../../x", Some("com.example.app")).is_err());
+        assert!(DeviceFileSource::validate("/sdcard/a.log; reboot", None).is_err());
+        assert!(DeviceFileSource::validate("files/app.log", Some("com.example;id")).is_err());
+
+        let source = DeviceFileSource {
+            adb_path: "adb".to_string(),
+            device_id: "emulator-5554".to_string(),
+            path: "files/logs/app.log".to_string(),
+            package: Some("com.example.app".to_string()),
+            format: SourceFormat::Logcat,
+        };
+        let (program, args) = source.command();
+        assert_eq!(program, "adb");
+        assert_eq!(
+            args.join(" "),
+            "-s emulator-5554 shell run-as com.example.app tail -n 0 -F files/logs/app.log"
+        );
+        assert_eq!(source.name(), "emulator-5554:files/logs/app.log");
+    }
+}
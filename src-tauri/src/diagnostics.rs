@@ -0,0 +1,121 @@
+use log::{Level, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::adb::AdbManager;
+use crate::archive::ZipWriter;
+use crate::settings;
+
+/// Number of internal log lines kept for the support bundle
+const MAX_RECENT_LOGS: usize = 2000;
+/// Number of error lines kept separately so they survive noisy info logging
+const MAX_RECENT_ERRORS: usize = 200;
+
+static RECENT_LOGS: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT_LOGS)));
+static RECENT_ERRORS: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_RECENT_ERRORS)));
+
+fn push_bounded(buffer: &Mutex<VecDeque<String>>, line: String, max: usize) {
+    if let Ok(mut buffer) = buffer.lock() {
+        if buffer.len() >= max {
+            buffer.pop_front();
+        }
+        buffer.push_back(line);
+    }
+}
+
+/// Logger that forwards to env_logger and keeps recent records in memory
+struct DiagnosticsLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for DiagnosticsLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            let line = format!(
+                "{} {:<5} {}: {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            if record.level() <= Level::Warn {
+                push_bounded(&RECENT_ERRORS, line.clone(), MAX_RECENT_ERRORS);
+            }
+            push_bounded(&RECENT_LOGS, line, MAX_RECENT_LOGS);
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the application logger (env_logger plus in-memory capture)
+pub fn init_logger() {
+    let inner = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .build();
+    let max_level = inner.filter();
+    if log::set_boxed_logger(Box::new(DiagnosticsLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+fn snapshot(buffer: &Mutex<VecDeque<String>>) -> Vec<String> {
+    buffer
+        .lock()
+        .map(|b| b.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Recent internal log lines, oldest first
+pub fn recent_logs() -> Vec<String> {
+    snapshot(&RECENT_LOGS)
+}
+
+/// Recent warnings and errors, oldest first
+pub fn recent_errors() -> Vec<String> {
+    snapshot(&RECENT_ERRORS)
+}
+
+/// Build a zip support bundle with internal logs, configuration, adb version and devices
+pub async fn build_bundle(adb: &AdbManager) -> Vec<u8> {
+    let adb_version = adb
+        .version()
+        .await
+        .unwrap_or_else(|e| format!("unavailable: {}", e));
+    let devices = match adb.get_devices().await {
+        Ok(devices) => serde_json::to_value(devices).unwrap_or_default(),
+        Err(e) => json!({ "error": e }),
+    };
+    let config = json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "adbPath": adb.adb_path(),
+        "settings": settings::current(),
+        "generatedAt": chrono::Local::now().to_rfc3339(),
+    });
+
+    let mut zip = ZipWriter::new();
+    zip.add_file("app.log", recent_logs().join("\n").as_bytes());
+    zip.add_file("errors.log", recent_errors().join("\n").as_bytes());
+    zip.add_file(
+        "config.json",
+        serde_json::to_string_pretty(&config).unwrap_or_default().as_bytes(),
+    );
+    zip.add_file("adb-version.txt", adb_version.as_bytes());
+    zip.add_file(
+        "devices.json",
+        serde_json::to_string_pretty(&devices).unwrap_or_default().as_bytes(),
+    );
+    zip.finish()
+}
@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+
+/// Directories never searched for sources
+const SKIPPED_DIRS: [&str; 4] = ["build", "node_modules", "target", "out"];
+
+/// Deepest directory level searched below a project root
+const MAX_DEPTH: usize = 16;
+
+/// Find the source file a stack frame refers to under the project roots.
+/// With the frame's class, a path ending in its package directories wins over
+/// another file of the same name (e.g. two `Utils.kt`).
+pub fn resolve(roots: &[String], file: &str, class: Option<&str>) -> Option<PathBuf> {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let package_dir: Option<PathBuf> = class.and_then(|class| {
+        let mut parts: Vec<&str> = class.split('.').collect();
+        parts.pop()?;
+        (!parts.is_empty()).then(|| parts.iter().collect())
+    });
+
+    let mut candidates = Vec::new();
+    for root in roots {
+        find_named(Path::new(root), file, 0, &mut candidates);
+    }
+    let in_package = package_dir.as_ref().and_then(|dir| {
+        candidates
+            .iter()
+            .find(|candidate| candidate.parent().is_some_and(|parent| parent.ends_with(dir)))
+    });
+    in_package.or(candidates.first()).cloned()
+}
+
+fn find_named(dir: &Path, name: &str, depth: usize, found: &mut Vec<PathBuf>) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read.flatten() {
+        let path = entry.path();
+        let entry_name = entry.file_name();
+        let entry_name = entry_name.to_string_lossy();
+        match entry.file_type() {
+            Ok(kind)
+                if kind.is_dir()
+                    && !entry_name.starts_with('.')
+                    && !SKIPPED_DIRS.contains(&entry_name.as_ref()) =>
+            {
+                find_named(&path, name, depth + 1, found)
+            }
+            Ok(kind) if kind.is_file() && entry_name == name => found.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Program and arguments from an editor command template. `{file}` and
+/// `{line}` are replaced after splitting on whitespace, so paths with spaces
+/// stay one argument. Examples: `code --goto {file}:{line}`,
+/// `studio --line {line} {file}`, `idea --line {line} {file}`.
+pub fn command_line(template: &str, file: &Path, line: u32) -> Result<Vec<String>, String> {
+    if !template.contains("{file}") {
+        return Err("Editor command must contain {file}".to_string());
+    }
+    let file = file.to_string_lossy();
+    let args: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| arg.replace("{file}", &file).replace("{line}", &line.to_string()))
+        .collect();
+    if args.is_empty() {
+        return Err("Editor command is empty".to_string());
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_package_path() {
+        let root = std::env::temp_dir().join(format!("editor-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let wanted = root.join("app/src/main/java/com/example/app/Utils.kt");
+        let other = root.join("lib/src/main/java/com/example/lib/Utils.kt");
+        let ignored = root.join("app/build/generated/com/example/app/Gen.kt");
+        for path in [&wanted, &other, &ignored] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let roots = vec![root.to_string_lossy().to_string()];
+
+        let resolved = resolve(&roots, "Utils.kt", Some("com.example.app.Utils"));
+        let any = resolve(&roots, "Utils.kt", None);
+        let generated = resolve(&roots, "Gen.kt", None);
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(resolved, Some(wanted));
+        assert!(any.is_some());
+        assert_eq!(generated, None);
+    }
+
+    #[test]
+    fn test_command_line_substitutes_placeholders() {
+        let args = command_line("code --goto {file}:{line}", Path::new("/src/My App/Bar.kt"), 42)
+            .unwrap();
+        assert_eq!(args, vec!["code", "--goto", "/src/My App/Bar.kt:42"]);
+        assert!(command_line("code", Path::new("/a"), 1).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+
+use crate::adb::{AdbManager, DeviceState};
+
+/// Default port of the adb server
+const DEFAULT_SERVER_PORT: u16 = 5037;
+
+/// Oldest platform-tools release with reliable wireless debugging (`adb pair`, mDNS)
+const MIN_PLATFORM_TOOLS: (u32, u32, u32) = (31, 0, 0);
+
+/// Setup checklist for the frontend, gathered in one call
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentStatus {
+    pub adb_path: String,
+    pub adb_found: bool,
+    /// "1.0.41" from "Android Debug Bridge version 1.0.41"
+    pub adb_version: Option<String>,
+    /// "35.0.2" from "Version 35.0.2-12147458"
+    pub platform_tools_version: Option<String>,
+    /// Whether the adb server was already listening before the check
+    pub server_running: bool,
+    pub device_count: usize,
+    pub unauthorized_devices: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Versions reported by `adb version`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdbVersion {
+    pub adb: Option<String>,
+    pub platform_tools: Option<(u32, u32, u32)>,
+}
+
+/// Parse `adb version` output
+pub fn parse_adb_version(output: &str) -> AdbVersion {
+    let mut version = AdbVersion::default();
+    for line in output.lines().map(str::trim) {
+        if let Some(v) = line.strip_prefix("Android Debug Bridge version ") {
+            version.adb = Some(v.trim().to_string());
+        } else if let Some(v) = line.strip_prefix("Version ") {
+            let release = v.split('-').next().unwrap_or_default();
+            let mut parts = release.split('.').map(|p| p.parse::<u32>().ok());
+            if let (Some(Some(major)), Some(Some(minor)), Some(Some(patch))) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                version.platform_tools = Some((major, minor, patch));
+            }
+        }
+    }
+    version
+}
+
+/// Whether something is listening on the adb server port. Checked directly
+/// because any adb command would start the server as a side effect.
+async fn server_listening() -> bool {
+    let port = std::env::var("ANDROID_ADB_SERVER_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(DEFAULT_SERVER_PORT);
+    matches!(
+        timeout(Duration::from_millis(500), TcpStream::connect(("127.0.0.1", port))).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Check adb, the adb server and connected devices
+pub async fn check(adb: &AdbManager) -> EnvironmentStatus {
+    let mut status = EnvironmentStatus {
+        adb_path: adb.adb_path(),
+        adb_found: false,
+        adb_version: None,
+        platform_tools_version: None,
+        server_running: server_listening().await,
+        device_count: 0,
+        unauthorized_devices: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let output = match adb.version().await {
+        Ok(output) => output,
+        Err(e) => {
+            status.warnings.push(format!("adb not usable at {}: {}", status.adb_path, e));
+            return status;
+        }
+    };
+    status.adb_found = true;
+
+    let version = parse_adb_version(&output);
+    status.adb_version = version.adb;
+    match version.platform_tools {
+        Some(tools) => {
+            status.platform_tools_version = Some(format!("{}.{}.{}", tools.0, tools.1, tools.2));
+            if tools < MIN_PLATFORM_TOOLS {
+                status.warnings.push(format!(
+                    "platform-tools {}.{}.{} is outdated, {}.0.0 or newer is recommended",
+                    tools.0, tools.1, tools.2, MIN_PLATFORM_TOOLS.0
+                ));
+            }
+        }
+        None => status
+            .warnings
+            .push("platform-tools version unknown, adb is likely outdated".to_string()),
+    }
+
+    match adb.get_devices().await {
+        Ok(devices) => {
+            status.device_count = devices.len();
+            status.unauthorized_devices = devices
+                .into_iter()
+                .filter(|d| d.state == DeviceState::Unauthorized)
+                .map(|d| d.id)
+                .collect();
+            if !status.unauthorized_devices.is_empty() {
+                status
+                    .warnings
+                    .push("Accept the USB debugging prompt on unauthorized devices".to_string());
+            }
+        }
+        Err(e) => status.warnings.push(format!("Failed to list devices: {}", e)),
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_adb_version() {
+        let output = "Android Debug Bridge version 1.0.41\n\
+                      Version 35.0.2-12147458\n\
+                      Installed as /opt/platform-tools/adb\n";
+        let version = parse_adb_version(output);
+        assert_eq!(version.adb.as_deref(), Some("1.0.41"));
+        assert_eq!(version.platform_tools, Some((35, 0, 2)));
+
+        let legacy = parse_adb_version("Android Debug Bridge version 1.0.32\nRevision 09a0d98bebce-android\n");
+        assert_eq!(legacy.adb.as_deref(), Some("1.0.32"));
+        assert_eq!(legacy.platform_tools, None);
+    }
+}
@@ -1,18 +1,118 @@
 use regex::Regex;
+use regex_syntax::ast::{self, Ast};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::adb::ProcessInfo;
+use crate::noise::NoiseFilter;
 
 use crate::parser::{LogEntry, LogLevel};
+use crate::tagfamily;
+
+/// How a tag or package criterion is compared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    #[default]
+    Contains,
+    Exact,
+    Prefix,
+    Regex,
+    /// The tag family named by the value, e.g. `okhttp.*` or `WM-`
+    Family,
+}
+
+/// Match options of one criterion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MatchOptions {
+    pub mode: MatchMode,
+    pub case_sensitive: bool,
+}
+
+impl MatchOptions {
+    /// Regex implementing these options for `value`
+    fn compile(&self, value: &str) -> Result<Regex, regex::Error> {
+        let pattern = match self.mode {
+            MatchMode::Contains => regex::escape(value),
+            MatchMode::Exact => format!("^(?:{})$", regex::escape(value)),
+            MatchMode::Prefix => format!("^(?:{})", regex::escape(value)),
+            MatchMode::Regex => value.to_string(),
+            MatchMode::Family => tagfamily::pattern(value),
+        };
+        if self.case_sensitive {
+            Regex::new(&pattern)
+        } else {
+            Regex::new(&format!("(?i){}", pattern))
+        }
+    }
+}
+
+/// Entry field the search text is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    Message,
+    Tag,
+    Process,
+    /// The original log line
+    Raw,
+    /// Message, tag and process
+    All,
+}
+
+impl SearchField {
+    /// Values of `entry` this field covers
+    fn values<'a>(&self, entry: &'a LogEntry) -> Vec<Cow<'a, str>> {
+        let message = Cow::Borrowed(entry.message.as_str());
+        let tag = Cow::Borrowed(entry.tag.as_str());
+        let process = entry.process_name.as_deref().map(Cow::Borrowed);
+        match self {
+            SearchField::Message => vec![message],
+            SearchField::Tag => vec![tag],
+            SearchField::Process => process.into_iter().collect(),
+            SearchField::Raw => vec![entry
+                .raw
+                .as_deref()
+                .map_or_else(|| Cow::Owned(entry.to_line()), Cow::Borrowed)],
+            SearchField::All => [Some(message), Some(tag), process].into_iter().flatten().collect(),
+        }
+    }
+}
 
 /// Filter configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilterConfig {
     pub levels: Vec<LogLevel>,
+    /// Entries match when their tag matches any of these
     pub tags: Vec<String>,
+    #[serde(rename = "tagMatch", default)]
+    pub tag_match: MatchOptions,
+    /// Entries whose tag matches any of these are hidden, compared like `tags`
+    #[serde(rename = "excludeTags", default)]
+    pub exclude_tags: Vec<String>,
+    /// Compared with the package resolved from the entry's PID
     #[serde(rename = "packageName")]
     pub package_name: Option<String>,
-    pub pid: Option<u32>,
+    #[serde(rename = "packageMatch", default)]
+    pub package_match: MatchOptions,
+    /// Entries must come from one of these PIDs (or `pid_package`'s)
+    #[serde(default)]
+    pub pids: Vec<u32>,
+    #[serde(rename = "excludePids", default)]
+    pub exclude_pids: Vec<u32>,
+    /// Package whose current PIDs are added to `pids`, re-resolved on each use
+    /// so app restarts are followed
+    #[serde(rename = "pidPackage", default)]
+    pub pid_package: Option<String>,
     #[serde(rename = "searchText")]
     pub search_text: String,
+    /// Fields matched independently by the search text; empty means all
+    #[serde(rename = "searchFields", default)]
+    pub search_fields: Vec<SearchField>,
     #[serde(rename = "isRegex")]
     pub is_regex: bool,
     #[serde(rename = "isCaseSensitive")]
@@ -31,34 +131,374 @@ impl Default for FilterConfig {
                 LogLevel::A,
             ],
             tags: vec![],
+            tag_match: MatchOptions::default(),
+            exclude_tags: vec![],
             package_name: None,
-            pid: None,
+            package_match: MatchOptions::default(),
+            pids: vec![],
+            exclude_pids: vec![],
+            pid_package: None,
             search_text: String::new(),
+            search_fields: vec![],
             is_regex: false,
             is_case_sensitive: false,
         }
     }
 }
 
-/// Log filter engine
+/// Longest a pattern rule may take on one line before it is disabled
+pub const RULE_BUDGET: Duration = Duration::from_millis(5);
+
+/// `LogFilter` times one evaluation of a rule in this many, starting with the first
+pub const TIMING_SAMPLE: u64 = 16;
+
+/// Pattern-based rules whose evaluation cost is tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterRule {
+    Exclusions,
+    Tag,
+    ExcludeTag,
+    Package,
+    Search,
+}
+
+/// Evaluation cost of one rule
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCost {
+    pub rule: FilterRule,
+    pub evaluations: u64,
+    /// Evaluations that were timed; the totals below cover only these
+    pub timed: u64,
+    pub total_nanos: u64,
+    pub max_nanos: u64,
+    /// Went over `RULE_BUDGET` on a line and is skipped since: include rules
+    /// match nothing, exclusions hide nothing
+    pub disabled: bool,
+    /// Why the rule was disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl RuleCost {
+    pub fn new(rule: FilterRule) -> Self {
+        RuleCost {
+            rule,
+            evaluations: 0,
+            timed: 0,
+            total_nanos: 0,
+            max_nanos: 0,
+            disabled: false,
+            reason: None,
+        }
+    }
+
+    /// Add one timed evaluation; true when it put the rule over budget
+    pub fn record(&mut self, elapsed: Duration) -> bool {
+        let nanos = elapsed.as_nanos().min(u128::from(u64::MAX)) as u64;
+        self.evaluations += 1;
+        self.timed += 1;
+        self.total_nanos = self.total_nanos.saturating_add(nanos);
+        self.max_nanos = self.max_nanos.max(nanos);
+        let over = !self.disabled && elapsed > RULE_BUDGET;
+        if over {
+            self.disabled = true;
+            self.reason = Some(format!(
+                "took {} ms on one line, over the {} ms budget",
+                nanos / 1_000_000,
+                RULE_BUDGET.as_millis()
+            ));
+        }
+        over
+    }
+}
+
+/// A problem in a search pattern
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PatternIssue {
+    pub message: String,
+    /// Character offset in the pattern, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+}
+
+/// Result of `validate_filter`
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterValidation {
+    pub valid: bool,
+    pub errors: Vec<PatternIssue>,
+    pub warnings: Vec<PatternIssue>,
+}
+
+/// Bounded repetitions above this count are flagged
+const LARGE_REPETITION: u32 = 1000;
+
+/// Byte offset to character offset
+fn char_position(pattern: &str, offset: usize) -> usize {
+    pattern[..offset.min(pattern.len())].chars().count()
+}
+
+/// Repetitions nested inside another repetition, e.g. `(a+)+`
+fn find_nested_repetitions(node: &Ast, inside_repetition: bool, found: &mut Vec<usize>) {
+    match node {
+        Ast::Repetition(rep) => {
+            if inside_repetition {
+                found.push(rep.span.start.offset);
+            }
+            find_nested_repetitions(&rep.ast, true, found);
+        }
+        Ast::Group(group) => find_nested_repetitions(&group.ast, inside_repetition, found),
+        Ast::Alternation(alt) => {
+            for node in &alt.asts {
+                find_nested_repetitions(node, inside_repetition, found);
+            }
+        }
+        Ast::Concat(concat) => {
+            for node in &concat.asts {
+                find_nested_repetitions(node, inside_repetition, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Bounded repetitions with large counts, e.g. `a{5000}`
+fn find_large_repetitions(node: &Ast, found: &mut Vec<usize>) {
+    match node {
+        Ast::Repetition(rep) => {
+            let count = match &rep.op.kind {
+                ast::RepetitionKind::Range(ast::RepetitionRange::Exactly(n))
+                | ast::RepetitionKind::Range(ast::RepetitionRange::AtLeast(n))
+                | ast::RepetitionKind::Range(ast::RepetitionRange::Bounded(_, n)) => *n,
+                _ => 0,
+            };
+            if count > LARGE_REPETITION {
+                found.push(rep.op.span.start.offset);
+            }
+            find_large_repetitions(&rep.ast, found);
+        }
+        Ast::Group(group) => find_large_repetitions(&group.ast, found),
+        Ast::Alternation(alt) => alt.asts.iter().for_each(|n| find_large_repetitions(n, found)),
+        Ast::Concat(concat) => concat.asts.iter().for_each(|n| find_large_repetitions(n, found)),
+        _ => {}
+    }
+}
+
+/// Check the search pattern of a filter, with positions for syntax errors and
+/// warnings for patterns that are slow to build or match every line
+pub fn validate(config: &FilterConfig) -> FilterValidation {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let pattern = &config.search_text;
+
+    if config.is_regex && !pattern.is_empty() {
+        match ast::parse::Parser::new().parse(pattern) {
+            Ok(tree) => {
+                let mut nested = Vec::new();
+                find_nested_repetitions(&tree, false, &mut nested);
+                for offset in nested {
+                    warnings.push(PatternIssue {
+                        message: "Nested quantifier; the pattern may be slow to compile and rarely does what was meant".to_string(),
+                        position: Some(char_position(pattern, offset)),
+                    });
+                }
+                let mut large = Vec::new();
+                find_large_repetitions(&tree, &mut large);
+                for offset in large {
+                    warnings.push(PatternIssue {
+                        message: format!("Repetition count above {} makes the pattern very large", LARGE_REPETITION),
+                        position: Some(char_position(pattern, offset)),
+                    });
+                }
+            }
+            Err(e) => errors.push(PatternIssue {
+                message: e.kind().to_string(),
+                position: Some(char_position(pattern, e.span().start.offset)),
+            }),
+        }
+    }
+
+    let criteria = config
+        .tags
+        .iter()
+        .chain(&config.exclude_tags)
+        .map(|tag| ("tag", tag, &config.tag_match))
+        .chain(config.package_name.iter().map(|p| ("package", p, &config.package_match)));
+    for (field, value, options) in criteria {
+        if let Err(e) = options.compile(value) {
+            errors.push(PatternIssue {
+                message: format!("Invalid {} pattern {}: {}", field, value, e),
+                position: None,
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        match LogFilter::compile_search_regex(config) {
+            Ok(Some(regex)) if regex.is_match("") => warnings.push(PatternIssue {
+                message: "Pattern matches the empty string, so every line matches".to_string(),
+                position: None,
+            }),
+            Ok(_) => {}
+            // Parses but fails to build, e.g. exceeds the size limit
+            Err(e) => errors.push(PatternIssue {
+                message: e.to_string(),
+                position: None,
+            }),
+        }
+    }
+
+    FilterValidation {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    }
+}
+
+/// Log filter engine. Invalid include patterns match nothing; invalid exclusions hide nothing.
 pub struct LogFilter {
     config: FilterConfig,
-    compiled_regex: Option<Regex>,
+    /// Compiled search text
+    compiled_regex: Result<Option<Regex>, regex::Error>,
+    compiled_tags: Result<Vec<Regex>, regex::Error>,
+    compiled_exclude_tags: Result<Vec<Regex>, regex::Error>,
+    compiled_package: Result<Option<Regex>, regex::Error>,
+    /// Current PIDs of `pid_package`
+    package_pids: HashSet<u32>,
+    /// Enabled quick toggles hiding noise on top of the config
+    exclusions: NoiseFilter,
+    /// Pinned entry IDs, matched regardless of the config
+    pinned: HashSet<u64>,
+    /// Cost per `FilterRule`, in declaration order
+    costs: RefCell<Vec<RuleCost>>,
+    /// Whether rules are timed and disabled over `RULE_BUDGET`
+    budgeted: bool,
+}
+
+/// PIDs of a package's processes, including secondary ones ("com.app:remote")
+pub fn package_pids(processes: &[ProcessInfo], package: &str) -> HashSet<u32> {
+    processes
+        .iter()
+        .filter(|p| {
+            p.name == package
+                || p.name.strip_prefix(package).is_some_and(|rest| rest.starts_with(':'))
+        })
+        .map(|p| p.pid)
+        .collect()
 }
 
 impl LogFilter {
     pub fn new(config: FilterConfig) -> Self {
         let compiled_regex = Self::compile_search_regex(&config);
+        let (compiled_tags, compiled_package) = Self::compile_criteria(&config);
+        let compiled_exclude_tags = Self::compile_tags(&config.exclude_tags, &config.tag_match);
         LogFilter {
             config,
             compiled_regex,
+            compiled_tags,
+            compiled_exclude_tags,
+            compiled_package,
+            package_pids: HashSet::new(),
+            exclusions: NoiseFilter::default(),
+            pinned: HashSet::new(),
+            costs: RefCell::new(Self::fresh_costs()),
+            budgeted: true,
         }
     }
 
+    /// Filter without the time budget, for offline runs (file conversion)
+    /// whose result must not depend on how busy the machine is
+    pub fn unbudgeted(config: FilterConfig) -> Self {
+        LogFilter {
+            budgeted: false,
+            ..Self::new(config)
+        }
+    }
+
+    fn fresh_costs() -> Vec<RuleCost> {
+        [
+            FilterRule::Exclusions,
+            FilterRule::Tag,
+            FilterRule::ExcludeTag,
+            FilterRule::Package,
+            FilterRule::Search,
+        ]
+            .into_iter()
+            .map(RuleCost::new)
+            .collect()
+    }
+
+    /// Cost of the rules evaluated so far
+    pub fn rule_costs(&self) -> Vec<RuleCost> {
+        self.costs
+            .borrow()
+            .iter()
+            .filter(|cost| cost.evaluations > 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Run a rule, timing one evaluation in `TIMING_SAMPLE`. A rule that went
+    /// over budget is disabled and returns `skipped` from then on: `false` for
+    /// include rules, which then match nothing like an invalid one, `true` for
+    /// exclusions, which then hide nothing. `rule_costs` says why.
+    fn timed(&self, rule: FilterRule, skipped: bool, check: impl FnOnce() -> bool) -> bool {
+        let index = rule as usize;
+        {
+            let mut costs = self.costs.borrow_mut();
+            let cost = &mut costs[index];
+            if cost.disabled {
+                return skipped;
+            }
+            if !self.budgeted || cost.evaluations % TIMING_SAMPLE != 0 {
+                cost.evaluations += 1;
+                return check();
+            }
+        }
+        let start = Instant::now();
+        let passed = check();
+        self.costs.borrow_mut()[index].record(start.elapsed());
+        passed
+    }
+
+    /// Entries that always match
+    pub fn set_pinned(&mut self, pinned: HashSet<u64>) {
+        self.pinned = pinned;
+    }
+
+    /// Stack quick toggle exclusions on the filter
+    pub fn set_exclusions(&mut self, exclusions: NoiseFilter) {
+        self.exclusions = exclusions;
+    }
+
+    /// Replace the resolved PIDs of `pid_package`
+    pub fn set_package_pids(&mut self, pids: HashSet<u32>) {
+        self.package_pids = pids;
+    }
+
+    /// Compile tag and package criteria with their match options
+    #[allow(clippy::type_complexity)]
+    fn compile_criteria(
+        config: &FilterConfig,
+    ) -> (Result<Vec<Regex>, regex::Error>, Result<Option<Regex>, regex::Error>) {
+        let tags = Self::compile_tags(&config.tags, &config.tag_match);
+        let package = config
+            .package_name
+            .as_deref()
+            .map(|pkg| config.package_match.compile(pkg))
+            .transpose();
+        (tags, package)
+    }
+
+    fn compile_tags(tags: &[String], options: &MatchOptions) -> Result<Vec<Regex>, regex::Error> {
+        tags.iter().map(|tag| options.compile(tag)).collect()
+    }
+
     /// Compile search regex from config
-    fn compile_search_regex(config: &FilterConfig) -> Option<Regex> {
+    pub fn compile_search_regex(config: &FilterConfig) -> Result<Option<Regex>, regex::Error> {
         if config.search_text.is_empty() {
-            return None;
+            return Ok(None);
         }
 
         let pattern = if config.is_regex {
@@ -73,51 +513,99 @@ impl LogFilter {
             Regex::new(&format!("(?i){}", pattern))
         };
 
-        regex_builder.ok()
+        regex_builder.map(Some)
     }
 
     /// Update filter configuration
     pub fn update_config(&mut self, config: FilterConfig) {
         self.compiled_regex = Self::compile_search_regex(&config);
+        (self.compiled_tags, self.compiled_package) = Self::compile_criteria(&config);
+        self.compiled_exclude_tags = Self::compile_tags(&config.exclude_tags, &config.tag_match);
+        if config.pid_package != self.config.pid_package {
+            self.package_pids.clear();
+        }
+        self.costs = RefCell::new(Self::fresh_costs());
         self.config = config;
     }
 
     /// Check if a log entry matches the filter
     pub fn matches(&self, entry: &LogEntry) -> bool {
+        if self.pinned.contains(&entry.id) {
+            return true;
+        }
+
         // Check log level
         if !self.config.levels.contains(&entry.level) {
             return false;
         }
 
+        if !self.exclusions.is_empty()
+            && !self.timed(FilterRule::Exclusions, true, || !self.exclusions.is_noise(entry))
+        {
+            return false;
+        }
+
         // Check tags
-        if !self.config.tags.is_empty() {
-            let tag_lower = entry.tag.to_lowercase();
-            let matches_tag = self.config.tags.iter().any(|t| {
-                tag_lower.contains(&t.to_lowercase())
-            });
-            if !matches_tag {
+        let Ok(tags) = &self.compiled_tags else {
+            return false;
+        };
+        if !tags.is_empty()
+            && !self.timed(FilterRule::Tag, false, || tags.iter().any(|re| re.is_match(&entry.tag)))
+        {
+            return false;
+        }
+        // An invalid exclusion hides nothing
+        if let Ok(excluded) = &self.compiled_exclude_tags {
+            if !excluded.is_empty()
+                && !self.timed(FilterRule::ExcludeTag, true, || {
+                    !excluded.iter().any(|re| re.is_match(&entry.tag))
+                })
+            {
                 return false;
             }
         }
 
-        // Check package name
-        if let Some(ref pkg) = self.config.package_name {
-            if !entry.tag.to_lowercase().contains(&pkg.to_lowercase()) {
+        // Check package name (entries without a resolved package don't match)
+        let Ok(package) = &self.compiled_package else {
+            return false;
+        };
+        if let Some(re) = package {
+            let matched = self.timed(FilterRule::Package, false, || {
+                entry.package_name.as_deref().is_some_and(|p| re.is_match(p))
+            });
+            if !matched {
                 return false;
             }
         }
 
-        // Check PID
-        if let Some(pid) = self.config.pid {
-            if entry.pid != pid {
-                return false;
-            }
+        // Check PIDs
+        let restricted = !self.config.pids.is_empty() || self.config.pid_package.is_some();
+        if restricted
+            && !self.config.pids.contains(&entry.pid)
+            && !self.package_pids.contains(&entry.pid)
+        {
+            return false;
+        }
+        if self.config.exclude_pids.contains(&entry.pid) {
+            return false;
         }
 
         // Check search text
-        if let Some(ref regex) = self.compiled_regex {
-            let search_target = format!("{} {}", entry.tag, entry.message);
-            if !regex.is_match(&search_target) {
+        let Ok(compiled) = &self.compiled_regex else {
+            return false;
+        };
+        if let Some(regex) = compiled {
+            let fields = match self.config.search_fields.as_slice() {
+                [] => &[SearchField::All][..],
+                fields => fields,
+            };
+            let found = self.timed(FilterRule::Search, false, || {
+                fields
+                    .iter()
+                    .flat_map(|field| field.values(entry))
+                    .any(|value| regex.is_match(&value))
+            });
+            if !found {
                 return false;
             }
         }
@@ -152,16 +640,92 @@ mod tests {
     fn create_test_entry(level: LogLevel, tag: &str, message: &str) -> LogEntry {
         LogEntry {
             id: 0,
+            seq: 0,
             timestamp: "12:00:00.000".to_string(),
+            date_time: None,
+            epoch: None,
+            time: None,
             pid: 1234,
             tid: 5678,
             level,
             tag: tag.to_string(),
             message: message.to_string(),
+            package_name: None,
+            process_name: None,
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
             raw: None,
         }
     }
 
+    #[test]
+    fn test_rule_costs_and_budget() {
+        let filter = LogFilter::new(FilterConfig {
+            search_text: "timeout".to_string(),
+            ..Default::default()
+        });
+        assert!(filter.matches(&create_test_entry(LogLevel::E, "Net", "timeout")));
+        assert!(!filter.matches(&create_test_entry(LogLevel::E, "Net", "ok")));
+        let costs = filter.rule_costs();
+        assert_eq!(costs.len(), 1);
+        assert_eq!(costs[0].rule, FilterRule::Search);
+        assert_eq!(costs[0].evaluations, 2);
+        assert_eq!(costs[0].timed, 1);
+
+        let mut cost = RuleCost::new(FilterRule::Search);
+        assert!(!cost.record(Duration::from_micros(10)));
+        assert!(cost.record(RULE_BUDGET * 2));
+        assert!(!cost.record(RULE_BUDGET * 2));
+        assert!(cost.disabled);
+        assert!(cost.reason.is_some());
+
+        // A disabled include rule matches nothing, like an invalid one
+        filter.costs.borrow_mut()[FilterRule::Search as usize].record(RULE_BUDGET * 2);
+        assert!(!filter.matches(&create_test_entry(LogLevel::E, "Net", "timeout")));
+    }
+
+    #[test]
+    fn test_disabled_exclusion_hides_nothing() {
+        let filter = LogFilter::new(FilterConfig {
+            tags: vec!["Net".to_string(), "chatty".to_string()],
+            exclude_tags: vec!["chatty".to_string()],
+            ..Default::default()
+        });
+        assert!(!filter.matches(&create_test_entry(LogLevel::I, "chatty", "uid=1000")));
+        filter.costs.borrow_mut()[FilterRule::ExcludeTag as usize].record(RULE_BUDGET * 2);
+        assert!(filter.matches(&create_test_entry(LogLevel::I, "chatty", "uid=1000")));
+        // The include tags are tracked apart and still apply
+        assert!(!filter.matches(&create_test_entry(LogLevel::I, "Db", "query")));
+        assert!(filter.matches(&create_test_entry(LogLevel::I, "Net", "connect")));
+
+        let invalid = LogFilter::new(FilterConfig {
+            exclude_tags: vec!["(".to_string()],
+            tag_match: MatchOptions {
+                mode: MatchMode::Regex,
+                case_sensitive: false,
+            },
+            ..Default::default()
+        });
+        assert!(invalid.matches(&create_test_entry(LogLevel::I, "chatty", "uid=1000")));
+    }
+
+    #[test]
+    fn test_unbudgeted_filter_is_not_timed() {
+        let filter = LogFilter::unbudgeted(FilterConfig {
+            search_text: "timeout".to_string(),
+            ..Default::default()
+        });
+        assert!(filter.matches(&create_test_entry(LogLevel::E, "Net", "timeout")));
+        assert_eq!(filter.rule_costs()[0].timed, 0);
+    }
+
     #[test]
     fn test_level_filter() {
         let config = FilterConfig {
@@ -223,5 +787,166 @@ mod tests {
         assert!(filter.matches(&matching));
         assert!(!filter.matches(&not_matching));
     }
-}
 
+    #[test]
+    fn test_invalid_regex_matches_nothing() {
+        let config = FilterConfig {
+            search_text: "(unclosed".to_string(),
+            is_regex: true,
+            ..Default::default()
+        };
+        let filter = LogFilter::new(config.clone());
+        assert!(!filter.matches(&create_test_entry(LogLevel::D, "Test", "(unclosed")));
+
+        let validation = validate(&config);
+        assert!(!validation.valid);
+        assert_eq!(validation.errors[0].position, Some(0));
+    }
+
+    #[test]
+    fn test_validate_warnings() {
+        let nested = validate(&FilterConfig {
+            search_text: "(a+)+b".to_string(),
+            is_regex: true,
+            ..Default::default()
+        });
+        assert!(nested.valid);
+        assert_eq!(nested.warnings.len(), 1);
+        assert_eq!(nested.warnings[0].position, Some(1));
+
+        let everything = validate(&FilterConfig {
+            search_text: "foo|".to_string(),
+            is_regex: true,
+            ..Default::default()
+        });
+        assert!(everything.warnings[0].message.contains("every line"));
+    }
+
+    #[test]
+    fn test_tag_match_modes() {
+        let filter_with = |mode: MatchMode, tag: &str| {
+            LogFilter::new(FilterConfig {
+                tags: vec![tag.to_string()],
+                tag_match: MatchOptions {
+                    mode,
+                    case_sensitive: false,
+                },
+                ..Default::default()
+            })
+        };
+        let entry = create_test_entry(LogLevel::D, "MainActivity", "test");
+
+        assert!(filter_with(MatchMode::Exact, "mainactivity").matches(&entry));
+        assert!(!filter_with(MatchMode::Exact, "Main").matches(&entry));
+        assert!(filter_with(MatchMode::Prefix, "Main").matches(&entry));
+        assert!(!filter_with(MatchMode::Prefix, "Activity").matches(&entry));
+        assert!(filter_with(MatchMode::Regex, "^Main.*y$").matches(&entry));
+        assert!(filter_with(MatchMode::Family, "Main*").matches(&entry));
+
+        let without_okhttp = LogFilter::new(FilterConfig {
+            exclude_tags: vec!["okhttp.*".to_string()],
+            tag_match: MatchOptions {
+                mode: MatchMode::Family,
+                case_sensitive: false,
+            },
+            ..Default::default()
+        });
+        assert!(!without_okhttp.matches(&create_test_entry(LogLevel::D, "okhttp.Http2", "x")));
+        assert!(!without_okhttp.matches(&create_test_entry(LogLevel::D, "OkHttp", "x")));
+        assert!(without_okhttp.matches(&create_test_entry(LogLevel::D, "okhttpx", "x")));
+    }
+
+    #[test]
+    fn test_package_matches_enriched_field() {
+        let filter = LogFilter::new(FilterConfig {
+            package_name: Some("com.example.app".to_string()),
+            package_match: MatchOptions {
+                mode: MatchMode::Exact,
+                case_sensitive: true,
+            },
+            ..Default::default()
+        });
+        let mut entry = create_test_entry(LogLevel::D, "com.example.app", "tag is not the package");
+        assert!(!filter.matches(&entry));
+
+        entry.package_name = Some("com.example.app".to_string());
+        assert!(filter.matches(&entry));
+        entry.package_name = Some("com.example.app.debug".to_string());
+        assert!(!filter.matches(&entry));
+    }
+
+    #[test]
+    fn test_pid_include_exclude() {
+        let mut entry = create_test_entry(LogLevel::D, "Test", "message");
+        let filter = LogFilter::new(FilterConfig {
+            pids: vec![1234, 4321],
+            exclude_pids: vec![4321],
+            ..Default::default()
+        });
+        assert!(filter.matches(&entry));
+        entry.pid = 4321;
+        assert!(!filter.matches(&entry));
+        entry.pid = 999;
+        assert!(!filter.matches(&entry));
+    }
+
+    #[test]
+    fn test_pid_package_resolution() {
+        let process = |pid: u32, name: &str| ProcessInfo {
+            pid,
+            name: name.to_string(),
+            package_name: None,
+        };
+        let processes = vec![
+            process(100, "com.example.app"),
+            process(101, "com.example.app:sync"),
+            process(102, "com.example.application"),
+        ];
+        let mut filter = LogFilter::new(FilterConfig {
+            pid_package: Some("com.example.app".to_string()),
+            ..Default::default()
+        });
+        let mut entry = create_test_entry(LogLevel::D, "Test", "message");
+        entry.pid = 101;
+        // Nothing resolved yet, so no PID is allowed
+        assert!(!filter.matches(&entry));
+
+        filter.set_package_pids(package_pids(&processes, "com.example.app"));
+        assert!(filter.matches(&entry));
+        entry.pid = 102;
+        assert!(!filter.matches(&entry));
+    }
+
+    #[test]
+    fn test_search_fields_match_independently() {
+        let mut entry = create_test_entry(LogLevel::D, "Net", "request sent");
+        entry.process_name = Some("com.example.app".to_string());
+        let filter_with = |text: &str, fields: Vec<SearchField>| {
+            LogFilter::new(FilterConfig {
+                search_text: text.to_string(),
+                search_fields: fields,
+                ..Default::default()
+            })
+        };
+
+        // The old "{tag} {message}" target matched across the boundary
+        assert!(!filter_with("Net request", vec![]).matches(&entry));
+        assert!(filter_with("example", vec![]).matches(&entry));
+        assert!(!filter_with("Net", vec![SearchField::Message]).matches(&entry));
+        assert!(filter_with("Net", vec![SearchField::Message, SearchField::Tag]).matches(&entry));
+        assert!(filter_with("1234", vec![SearchField::Raw]).matches(&entry));
+    }
+
+    #[test]
+    fn test_pinned_entries_bypass_filter() {
+        let mut filter = LogFilter::new(FilterConfig {
+            levels: vec![LogLevel::E],
+            ..Default::default()
+        });
+        let mut entry = create_test_entry(LogLevel::D, "Test", "the crucial line");
+        entry.id = 7;
+        assert!(!filter.matches(&entry));
+        filter.set_pinned(HashSet::from([7]));
+        assert!(filter.matches(&entry));
+    }
+}
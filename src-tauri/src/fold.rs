@@ -0,0 +1,219 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::parser::LogEntry;
+
+/// A group with no new line for this long is closed and summarized
+pub const IDLE_CLOSE: Duration = Duration::from_millis(500);
+
+/// Consecutive lines of one process and tag that fold into one group: a line
+/// matching `start` followed by lines matching `continuation`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldRule {
+    pub name: String,
+    pub start: String,
+    pub continuation: String,
+}
+
+/// Stack traces, Java and native
+pub fn default_rules() -> Vec<FoldRule> {
+    vec![
+        FoldRule {
+            name: "stack-trace".to_string(),
+            start: r"^(FATAL EXCEPTION|[\w$.]+(Exception|Error)\b)".to_string(),
+            continuation: r"^\s*(at |Caused by:|Suppressed:|\.\.\. \d+ more|Process: )".to_string(),
+        },
+        FoldRule {
+            name: "native-backtrace".to_string(),
+            start: r"^backtrace:".to_string(),
+            continuation: r"^\s*#\d+ pc ".to_string(),
+        },
+    ]
+}
+
+/// A closed group of two or more lines; payload of the `fold-group` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FoldGroup {
+    pub device_id: String,
+    /// Shared `groupId` of the entries, the ID of the first one
+    pub group_id: u64,
+    pub rule: String,
+    pub last_entry_id: u64,
+    pub count: usize,
+    /// One line standing for the folded block
+    pub summary: String,
+}
+
+struct CompiledRule {
+    name: String,
+    start: Regex,
+    continuation: Regex,
+}
+
+struct OpenGroup {
+    rule: usize,
+    tag: String,
+    first_message: String,
+    group_id: u64,
+    last_entry_id: u64,
+    count: usize,
+    last_seen: Instant,
+}
+
+/// Assigns `group_id`s to the entries of one device's stream
+#[derive(Default)]
+pub struct FoldTracker {
+    rules: Vec<CompiledRule>,
+    /// Group being built per PID, so interleaved processes don't break it
+    open: HashMap<u32, OpenGroup>,
+}
+
+impl FoldTracker {
+    pub fn new(rules: &[FoldRule]) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let regex = |pattern: &str| {
+                    Regex::new(pattern)
+                        .map_err(|e| format!("Invalid pattern in fold rule {}: {}", rule.name, e))
+                };
+                Ok(CompiledRule {
+                    name: rule.name.clone(),
+                    start: regex(&rule.start)?,
+                    continuation: regex(&rule.continuation)?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(FoldTracker {
+            rules,
+            open: HashMap::new(),
+        })
+    }
+
+    /// Set the entry's `group_id` if it starts or continues a block; returns
+    /// the group of its process that it ended, if any
+    pub fn observe(
+        &mut self,
+        device_id: &str,
+        entry: &mut LogEntry,
+        now: Instant,
+    ) -> Option<FoldGroup> {
+        if let Some(group) = self.open.get_mut(&entry.pid) {
+            if group.tag == entry.tag
+                && self.rules[group.rule].continuation.is_match(&entry.message)
+            {
+                group.count += 1;
+                group.last_entry_id = entry.id;
+                group.last_seen = now;
+                entry.group_id = Some(group.group_id);
+                return None;
+            }
+        }
+        let closed = self
+            .open
+            .remove(&entry.pid)
+            .and_then(|group| self.summarize(device_id, group));
+        let started = self.rules.iter().position(|rule| rule.start.is_match(&entry.message));
+        if let Some(rule) = started {
+            entry.group_id = Some(entry.id);
+            self.open.insert(
+                entry.pid,
+                OpenGroup {
+                    rule,
+                    tag: entry.tag.clone(),
+                    first_message: entry.message.clone(),
+                    group_id: entry.id,
+                    last_entry_id: entry.id,
+                    count: 1,
+                    last_seen: now,
+                },
+            );
+        }
+        closed
+    }
+
+    /// Close groups that got no line for `IDLE_CLOSE`, e.g. the trace of a
+    /// process that died
+    pub fn close_idle(&mut self, device_id: &str, now: Instant) -> Vec<FoldGroup> {
+        let idle: Vec<u32> = self
+            .open
+            .iter()
+            .filter(|(_, group)| now.saturating_duration_since(group.last_seen) >= IDLE_CLOSE)
+            .map(|(pid, _)| *pid)
+            .collect();
+        let groups: Vec<OpenGroup> =
+            idle.into_iter().filter_map(|pid| self.open.remove(&pid)).collect();
+        groups
+            .into_iter()
+            .filter_map(|group| self.summarize(device_id, group))
+            .collect()
+    }
+
+    /// Groups of a single line aren't folds and are dropped
+    fn summarize(&self, device_id: &str, group: OpenGroup) -> Option<FoldGroup> {
+        if group.count < 2 {
+            return None;
+        }
+        let hidden = group.count - 1;
+        Some(FoldGroup {
+            device_id: device_id.to_string(),
+            group_id: group.group_id,
+            rule: self.rules[group.rule].name.clone(),
+            last_entry_id: group.last_entry_id,
+            count: group.count,
+            summary: format!(
+                "{} (+{} line{})",
+                group.first_message,
+                hidden,
+                if hidden == 1 { "" } else { "s" }
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_folds_interleaved_stack_trace() {
+        let mut tracker = FoldTracker::new(&default_rules()).unwrap();
+        let mut parser = LogParser::new();
+        let now = Instant::now();
+        let mut closed = Vec::new();
+        let mut groups = Vec::new();
+        for line in [
+            "12-04 10:30:46.000   100   100 W System.err: java.io.IOException: timeout",
+            "12-04 10:30:46.000   100   100 W System.err: \tat com.example.Net.call(Net.kt:12)",
+            "12-04 10:30:46.000   200   200 I Other: unrelated",
+            "12-04 10:30:46.000   100   100 W System.err: \tat com.example.Repo.load(Repo.kt:30)",
+            "12-04 10:30:46.001   100   100 I Sync: done",
+            "12-04 10:30:46.002   300   300 E Crash: java.lang.IllegalStateException",
+        ] {
+            let mut entry = parser.parse_line(line).unwrap();
+            closed.extend(tracker.observe("emulator-5554", &mut entry, now));
+            groups.push(entry.group_id);
+        }
+
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].count, 3);
+        assert_eq!(closed[0].summary, "java.io.IOException: timeout (+2 lines)");
+        assert_eq!(groups[1], groups[0]);
+        assert_eq!(groups[3], groups[0]);
+        assert_eq!((groups[2], groups[4]), (None, None));
+
+        // A lone start line closes without a fold
+        assert!(tracker.close_idle("emulator-5554", now + IDLE_CLOSE).is_empty());
+        assert!(FoldTracker::new(&[FoldRule {
+            name: "bad".to_string(),
+            start: "(".to_string(),
+            continuation: "x".to_string(),
+        }])
+        .is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use log::{error, info};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration};
+
+use crate::adb::AdbManager;
+
+/// How often a followed package is polled
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What changed for a followed package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FollowEventKind {
+    /// Installed, or reinstalled (the APK path changed)
+    Installed,
+    Uninstalled,
+    /// First process after an install
+    FirstLaunch,
+    /// A process started (again) after the previous one died
+    Started,
+    /// The process was replaced without a gap between polls
+    PidChanged,
+    Died,
+}
+
+/// Payload of the `package-follow` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowEvent {
+    pub device_id: String,
+    pub package_name: String,
+    pub kind: FollowEventKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_pid: Option<u32>,
+}
+
+/// Observed package state at one poll
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageSnapshot {
+    /// APK path from `pm path`, None when not installed
+    pub apk_path: Option<String>,
+    /// PID of the main process
+    pub pid: Option<u32>,
+}
+
+/// Check a package name (`^[A-Za-z][A-Za-z0-9_.]*$`) before it reaches
+/// `adb shell`, which joins its arguments into one shell command line
+pub fn validate_package(package: &str) -> Result<(), String> {
+    let mut chars = package.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid package name: {}", package))
+    }
+}
+
+/// Turns successive snapshots into follow events
+#[derive(Debug)]
+pub struct PackageFollower {
+    device_id: String,
+    package: String,
+    last: Option<PackageSnapshot>,
+    awaiting_first_launch: bool,
+}
+
+impl PackageFollower {
+    pub fn new(device_id: &str, package: &str) -> Self {
+        PackageFollower {
+            device_id: device_id.to_string(),
+            package: package.to_string(),
+            last: None,
+            awaiting_first_launch: false,
+        }
+    }
+
+    fn event(&self, kind: FollowEventKind, pid: Option<u32>, previous_pid: Option<u32>) -> FollowEvent {
+        FollowEvent {
+            device_id: self.device_id.clone(),
+            package_name: self.package.clone(),
+            kind,
+            pid,
+            previous_pid,
+        }
+    }
+
+    /// Compare with the previous snapshot; the first one only sets the baseline
+    pub fn step(&mut self, snapshot: PackageSnapshot) -> Vec<FollowEvent> {
+        let mut events = Vec::new();
+        let Some(last) = self.last.replace(snapshot.clone()) else {
+            return events;
+        };
+
+        if snapshot.apk_path != last.apk_path {
+            if snapshot.apk_path.is_some() {
+                events.push(self.event(FollowEventKind::Installed, None, None));
+                self.awaiting_first_launch = true;
+            } else {
+                events.push(self.event(FollowEventKind::Uninstalled, None, None));
+                self.awaiting_first_launch = false;
+            }
+        }
+
+        match (last.pid, snapshot.pid) {
+            (None, Some(pid)) => {
+                let kind = if self.awaiting_first_launch {
+                    FollowEventKind::FirstLaunch
+                } else {
+                    FollowEventKind::Started
+                };
+                self.awaiting_first_launch = false;
+                events.push(self.event(kind, Some(pid), None));
+            }
+            (Some(old), Some(new)) if old != new => {
+                events.push(self.event(FollowEventKind::PidChanged, Some(new), Some(old)));
+            }
+            (Some(old), None) => {
+                events.push(self.event(FollowEventKind::Died, None, Some(old)));
+            }
+            _ => {}
+        }
+
+        events
+    }
+}
+
+/// Poll a package until the task is aborted, emitting `package-follow` events.
+/// Polls that fail (device offline) are skipped so following survives reconnects.
+pub fn spawn(
+    app: AppHandle,
+    adb: &'static AdbManager,
+    device_id: String,
+    package: String,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Following {} on {}", package, device_id);
+        let mut follower = PackageFollower::new(&device_id, &package);
+        let mut ticker = interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let snapshot = match adb.package_snapshot(&device_id, &package).await {
+                Ok(snapshot) => snapshot,
+                Err(_) => continue,
+            };
+            for event in follower.step(snapshot) {
+                if let Err(e) = app.emit("package-follow", &event) {
+                    error!("Failed to emit follow event: {}", e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(path: Option<&str>, pid: Option<u32>) -> PackageSnapshot {
+        PackageSnapshot {
+            apk_path: path.map(str::to_string),
+            pid,
+        }
+    }
+
+    fn kinds(events: Vec<FollowEvent>) -> Vec<FollowEventKind> {
+        events.into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn test_reinstall_cycle() {
+        let mut follower = PackageFollower::new("dev", "com.example");
+        assert!(follower.step(snapshot(Some("/data/app/a/base.apk"), Some(100))).is_empty());
+
+        assert_eq!(
+            kinds(follower.step(snapshot(None, None))),
+            vec![FollowEventKind::Uninstalled, FollowEventKind::Died]
+        );
+        assert_eq!(
+            kinds(follower.step(snapshot(Some("/data/app/b/base.apk"), None))),
+            vec![FollowEventKind::Installed]
+        );
+        let launch = follower.step(snapshot(Some("/data/app/b/base.apk"), Some(200)));
+        assert_eq!(launch[0].kind, FollowEventKind::FirstLaunch);
+        assert_eq!(launch[0].pid, Some(200));
+
+        let changed = follower.step(snapshot(Some("/data/app/b/base.apk"), Some(300)));
+        assert_eq!(changed[0].kind, FollowEventKind::PidChanged);
+        assert_eq!(changed[0].previous_pid, Some(200));
+    }
+
+    #[test]
+    fn test_restart_is_not_first_launch() {
+        let mut follower = PackageFollower::new("dev", "com.example");
+        follower.step(snapshot(Some("/data/app/a/base.apk"), Some(100)));
+        follower.step(snapshot(Some("/data/app/a/base.apk"), None));
+        assert_eq!(
+            kinds(follower.step(snapshot(Some("/data/app/a/base.apk"), Some(101)))),
+            vec![FollowEventKind::Started]
+        );
+    }
+
+    #[test]
+    fn test_validate_package() {
+        assert!(validate_package("com.example.app_2").is_ok());
+        assert!(validate_package("com.example; reboot").is_err());
+        assert!(validate_package("$(id)").is_err());
+        assert!(validate_package("1com").is_err());
+        assert!(validate_package("").is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use regex::Regex;
+use serde::Serialize;
+use std::time::Instant;
+
+use crate::filter::{FilterRule, RuleCost};
+use crate::parser::LogEntry;
+
+/// One match in a message, in UTF-16 code units so it indexes JS strings directly
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct MatchSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Match spans of one entry, sent as `logcat-highlights` before its batch
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryMatches {
+    pub id: u64,
+    pub spans: Vec<MatchSpan>,
+}
+
+/// All non-empty matches of `regex` in `text`
+pub fn match_spans(regex: &Regex, text: &str) -> Vec<MatchSpan> {
+    let mut spans = Vec::new();
+    // Byte offsets are converted incrementally, matches come in order
+    let mut byte_pos = 0;
+    let mut utf16_pos = 0;
+    let mut to_utf16 = |byte: usize| {
+        utf16_pos += text[byte_pos..byte].encode_utf16().count();
+        byte_pos = byte;
+        utf16_pos
+    };
+    for m in regex.find_iter(text).filter(|m| !m.is_empty()) {
+        let start = to_utf16(m.start());
+        let end = to_utf16(m.end());
+        spans.push(MatchSpan { start, end });
+    }
+    spans
+}
+
+/// Spans for the entries whose message matches; others are omitted
+pub fn batch_matches<'a>(
+    regex: &Regex,
+    entries: impl IntoIterator<Item = &'a LogEntry>,
+) -> Vec<EntryMatches> {
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let spans = match_spans(regex, &entry.message);
+            (!spans.is_empty()).then_some(EntryMatches { id: entry.id, spans })
+        })
+        .collect()
+}
+
+/// A device's live search and what it has cost so far
+pub struct ActiveSearch {
+    pub regex: Regex,
+    pub cost: RuleCost,
+}
+
+impl ActiveSearch {
+    pub fn new(regex: Regex) -> Self {
+        ActiveSearch {
+            regex,
+            cost: RuleCost::new(FilterRule::Search),
+        }
+    }
+
+    /// Like `batch_matches`, timing each message. Stops at the first line over
+    /// `filter::RULE_BUDGET`, leaving `cost.disabled` set.
+    pub fn batch_matches(&mut self, entries: &[LogEntry]) -> Vec<EntryMatches> {
+        let mut matches = Vec::new();
+        for entry in entries {
+            let start = Instant::now();
+            let spans = match_spans(&self.regex, &entry.message);
+            if self.cost.record(start.elapsed()) {
+                break;
+            }
+            if !spans.is_empty() {
+                matches.push(EntryMatches { id: entry.id, spans });
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans_use_utf16_offsets() {
+        let regex = Regex::new("(?i)err").unwrap();
+        let spans = match_spans(&regex, "连接 ERR 😀 err");
+        // "连接 " is 3 units, the emoji is a surrogate pair
+        assert_eq!(
+            spans,
+            vec![MatchSpan { start: 3, end: 6 }, MatchSpan { start: 10, end: 13 }]
+        );
+        assert!(match_spans(&Regex::new("x*").unwrap(), "abc").is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+use crate::filter::{LogFilter, RuleCost};
+use crate::parser::LogEntry;
+
+/// Matching entries returned by a preview when no sample size is given
+pub const DEFAULT_SAMPLE: usize = 20;
+
+/// Result of `preview_filter`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterPreview {
+    /// Entries the filter was evaluated against
+    pub total: usize,
+    pub matched: usize,
+    /// Most recent matching entries, oldest first
+    pub sample: Vec<LogEntry>,
+    /// Time spent per pattern rule; over-budget rules are flagged `disabled`
+    pub rule_costs: Vec<RuleCost>,
+}
+
+/// What an adjacent entry must share with the starting entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Relation {
+    Pid,
+    Tid,
+    Tag,
+}
+
+impl Relation {
+    fn related(self, a: &LogEntry, b: &LogEntry) -> bool {
+        match self {
+            Relation::Pid => a.pid == b.pid,
+            Relation::Tid => a.pid == b.pid && a.tid == b.tid,
+            Relation::Tag => a.tag == b.tag,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Next,
+    Previous,
+}
+
+/// Bounded copy of a device's recent entries for server-side queries
+pub struct EntryHistory {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+impl EntryHistory {
+    pub fn new(capacity: usize) -> Self {
+        EntryHistory {
+            entries: VecDeque::with_capacity(capacity.min(4096)),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, entry: LogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Nearest stored entry before or after `entry_id` sharing its PID, TID or tag.
+    /// `None` when the entry was evicted or nothing related follows.
+    pub fn adjacent(
+        &self,
+        entry_id: u64,
+        relation: Relation,
+        direction: Direction,
+    ) -> Option<&LogEntry> {
+        let index = self.entries.iter().position(|e| e.id == entry_id)?;
+        let origin = &self.entries[index];
+        let related = |e: &&LogEntry| relation.related(origin, e);
+        match direction {
+            Direction::Next => self.entries.range(index + 1..).find(related),
+            Direction::Previous => self.entries.range(..index).rev().find(related),
+        }
+    }
+
+    /// All stored entries, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// Stored entries with the given IDs, oldest first
+    pub fn with_ids<'a>(&'a self, ids: &'a HashSet<u64>) -> impl Iterator<Item = &'a LogEntry> {
+        self.entries.iter().filter(|entry| ids.contains(&entry.id))
+    }
+
+    /// Stored entries carrying the correlation ID, oldest first
+    pub fn with_correlation<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a LogEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.correlation_id.as_deref() == Some(id))
+    }
+
+    /// Count matches of a candidate filter and sample the latest ones
+    pub fn preview(&self, filter: &LogFilter, sample_size: usize) -> FilterPreview {
+        let mut matched = 0;
+        let mut sample = Vec::new();
+        for entry in self.entries.iter().rev() {
+            if filter.matches(entry) {
+                matched += 1;
+                if sample.len() < sample_size {
+                    sample.push(entry.clone());
+                }
+            }
+        }
+        sample.reverse();
+        FilterPreview {
+            total: self.entries.len(),
+            matched,
+            sample,
+            rule_costs: filter.rule_costs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::FilterConfig;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_preview_counts_and_samples() {
+        let mut parser = LogParser::new();
+        let mut history = EntryHistory::new(3);
+        for line in [
+            "12-04 10:30:45.100  100  100 I Net: request 1",
+            "12-04 10:30:45.200  100  100 D Ui: frame",
+            "12-04 10:30:45.300  100  100 I Net: request 2",
+            "12-04 10:30:45.400  100  100 I Net: request 3",
+        ] {
+            history.push(parser.parse_line(line).unwrap());
+        }
+
+        let filter = LogFilter::new(FilterConfig {
+            search_text: "request".to_string(),
+            ..Default::default()
+        });
+        let preview = history.preview(&filter, 1);
+        // The first entry was evicted
+        assert_eq!(preview.total, 3);
+        assert_eq!(preview.matched, 2);
+        assert_eq!(preview.sample[0].message, "request 3");
+    }
+
+    #[test]
+    fn test_adjacent_by_relation() {
+        let mut parser = LogParser::new();
+        let mut history = EntryHistory::new(10);
+        for line in [
+            "12-04 10:30:45.100  100  101 I Net: request",
+            "12-04 10:30:45.200  200  200 D Ui: frame",
+            "12-04 10:30:45.300  100  102 I Db: query",
+            "12-04 10:30:45.400  100  101 I Net: response",
+        ] {
+            history.push(parser.parse_line(line).unwrap());
+        }
+        let first = history.entries[0].id;
+        let last = history.entries[3].id;
+        let message = |id: u64, relation: Relation, direction: Direction| {
+            history
+                .adjacent(id, relation, direction)
+                .map(|e| e.message.as_str())
+        };
+
+        assert_eq!(message(first, Relation::Pid, Direction::Next), Some("query"));
+        assert_eq!(message(first, Relation::Tid, Direction::Next), Some("response"));
+        assert_eq!(message(last, Relation::Tag, Direction::Previous), Some("request"));
+        assert_eq!(message(first, Relation::Pid, Direction::Previous), None);
+    }
+}
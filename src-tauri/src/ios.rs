@@ -0,0 +1,179 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tokio::process::Command;
+
+use crate::adb::{Device, DeviceState, Transport};
+use crate::palette;
+use crate::parser::{self, LogEntry, LogLevel};
+use crate::sanitize;
+use crate::source::{LineParser, LogSource};
+
+/// idevicesyslog line:
+/// "Mar 14 10:23:45.123456 iPhone SpringBoard(FrontBoard)[58] <Notice>: message"
+static SYSLOG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(\w{3})\s+(\d{1,2})\s+(\d{2}:\d{2}:\d{2})(?:\.(\d+))?\s+\S+\s+([^\[(\s]+)(?:\(([^)]*)\))?\[(\d+)\]\s+<(\w+)>:\s?(.*)$",
+    )
+    .expect("Invalid iOS syslog regex")
+});
+
+fn month_number(name: &str) -> Option<u32> {
+    let months = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    months.iter().position(|m| *m == name).map(|i| i as u32 + 1)
+}
+
+/// Map os_log levels onto Android levels
+fn level_from_syslog(level: &str) -> LogLevel {
+    match level {
+        "Debug" => LogLevel::D,
+        "Info" | "Notice" => LogLevel::I,
+        "Warning" => LogLevel::W,
+        "Error" => LogLevel::E,
+        "Fault" | "Critical" | "Alert" | "Emergency" => LogLevel::A,
+        _ => LogLevel::V,
+    }
+}
+
+/// Parser for `idevicesyslog` output
+#[derive(Default)]
+pub struct IosSyslogParser;
+
+impl IosSyslogParser {
+    pub fn new() -> Self {
+        IosSyslogParser
+    }
+}
+
+impl LineParser for IosSyslogParser {
+    fn parse_line(&mut self, line: &str) -> Option<LogEntry> {
+        let caps = SYSLOG_REGEX.captures(line.trim_end())?;
+        let month = month_number(&caps[1])?;
+        let day: u32 = caps[2].parse().ok()?;
+        // Keep millisecond precision like logcat's threadtime format
+        let millis = caps
+            .get(4)
+            .map(|m| format!("{:0<3}", &m.as_str()[..m.as_str().len().min(3)]))
+            .unwrap_or_else(|| "000".to_string());
+        let timestamp = format!("{}.{}", &caps[3], millis);
+        let process = caps[5].to_string();
+        // "SpringBoard(FrontBoard)" -> tag "FrontBoard", otherwise the process name
+        let tag = caps
+            .get(6)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| process.clone());
+
+        let mut entry = LogEntry {
+            id: parser::next_entry_id(),
+            seq: 0,
+            timestamp: timestamp.clone(),
+            date_time: Some(format!("{:02}-{:02} {}", month, day, timestamp)),
+            epoch: Some(chrono::Local::now().timestamp_millis() as u64),
+            time: None,
+            pid: caps[7].parse().unwrap_or(0),
+            tid: 0,
+            level: level_from_syslog(&caps[8]),
+            tag,
+            message: caps[9].to_string(),
+            package_name: None,
+            process_name: Some(process),
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
+            raw: Some(line.to_string()),
+        };
+        sanitize::apply(&mut entry);
+        Some(entry)
+    }
+}
+
+/// iOS device syslog via libimobiledevice's `idevicesyslog`
+pub struct IosSyslogSource {
+    pub udid: String,
+}
+
+impl LogSource for IosSyslogSource {
+    fn name(&self) -> String {
+        format!("idevicesyslog ({})", self.udid)
+    }
+
+    fn command(&self) -> (String, Vec<String>) {
+        (
+            "idevicesyslog".to_string(),
+            vec!["-u".to_string(), self.udid.clone()],
+        )
+    }
+
+    fn parser(&self) -> Box<dyn LineParser> {
+        Box::new(IosSyslogParser::new())
+    }
+}
+
+/// List iOS devices via `idevice_id -l`
+pub async fn list_devices() -> Result<Vec<Device>, String> {
+    let output = Command::new("idevice_id")
+        .arg("-l")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run idevice_id (is libimobiledevice installed?): {}", e))?;
+
+    if !output.status.success() {
+        return Err("idevice_id command failed".to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .map(str::trim)
+        .filter(|udid| !udid.is_empty())
+        .map(|udid| Device {
+            id: udid.to_string(),
+            name: format!("iOS ({})", udid),
+            model: "iOS".to_string(),
+            state: DeviceState::Device,
+            is_emulator: false,
+            transport: Transport::Usb,
+            transport_id: None,
+            color_index: palette::color_index(udid),
+            groups: Vec::new(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_syslog_line() {
+        let mut parser = IosSyslogParser::new();
+        let entry = parser
+            .parse_line("Mar 14 10:23:45.123456 iPhone SpringBoard(FrontBoard)[58] <Notice>: Scene created")
+            .unwrap();
+        assert_eq!(entry.timestamp, "10:23:45.123");
+        assert_eq!(entry.date_time.as_deref(), Some("03-14 10:23:45.123"));
+        assert_eq!(entry.pid, 58);
+        assert_eq!(entry.level, LogLevel::I);
+        assert_eq!(entry.tag, "FrontBoard");
+        assert_eq!(entry.process_name.as_deref(), Some("SpringBoard"));
+        assert_eq!(entry.message, "Scene created");
+    }
+
+    #[test]
+    fn test_parse_syslog_without_subsystem() {
+        let mut parser = IosSyslogParser::new();
+        let entry = parser
+            .parse_line("Mar  4 09:00:01 iPhone kernel[0] <Error>: Sandbox: deny")
+            .unwrap();
+        assert_eq!(entry.timestamp, "09:00:01.000");
+        assert_eq!(entry.tag, "kernel");
+        assert_eq!(entry.level, LogLevel::E);
+    }
+}
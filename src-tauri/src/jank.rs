@@ -0,0 +1,161 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::parser::LogEntry;
+
+/// Frame budget at 60Hz, used to convert skipped frames into a duration
+const FRAME_MS: f64 = 1000.0 / 60.0;
+
+/// Kind of jank report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JankKind {
+    /// `Choreographer: Skipped N frames!`
+    SkippedFrames,
+    /// `OpenGLRenderer: Davey! duration=Nms`
+    Davey,
+}
+
+/// Jank severity derived from the stall duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JankSeverity {
+    /// Under 250ms
+    Minor,
+    /// 250ms up to the 700ms "Davey" threshold
+    Moderate,
+    /// 700ms or more
+    Severe,
+}
+
+impl JankSeverity {
+    pub fn from_duration(duration_ms: u64) -> Self {
+        match duration_ms {
+            0..=249 => JankSeverity::Minor,
+            250..=699 => JankSeverity::Moderate,
+            _ => JankSeverity::Severe,
+        }
+    }
+}
+
+/// A structured jank event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JankEvent {
+    pub kind: JankKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frames: Option<u32>,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    pub severity: JankSeverity,
+    pub pid: u32,
+    #[serde(rename = "packageName", skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
+    pub time: String,
+    #[serde(rename = "entryId")]
+    pub entry_id: u64,
+}
+
+/// Aggregated jank statistics for one device
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JankStats {
+    #[serde(rename = "totalEvents")]
+    pub total_events: u32,
+    #[serde(rename = "skippedFrames")]
+    pub skipped_frames: u64,
+    #[serde(rename = "worstMs")]
+    pub worst_ms: u64,
+    #[serde(rename = "bySeverity")]
+    pub by_severity: HashMap<JankSeverity, u32>,
+    /// Event count per package (or process ID when the package is unknown)
+    #[serde(rename = "byPackage")]
+    pub by_package: HashMap<String, u32>,
+}
+
+impl JankStats {
+    /// Add an event to the aggregate
+    pub fn record(&mut self, event: &JankEvent) {
+        self.total_events += 1;
+        self.skipped_frames += event.frames.unwrap_or(0) as u64;
+        self.worst_ms = self.worst_ms.max(event.duration_ms);
+        *self.by_severity.entry(event.severity).or_insert(0) += 1;
+        let key = event
+            .package_name
+            .clone()
+            .unwrap_or_else(|| event.pid.to_string());
+        *self.by_package.entry(key).or_insert(0) += 1;
+    }
+}
+
+static SKIPPED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Skipped (\d+) frames").expect("Invalid skipped frames regex")
+});
+
+static DAVEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Davey! duration=(\d+)ms").expect("Invalid Davey regex")
+});
+
+/// Parse a jank event out of a log entry, if it is one
+pub fn parse_jank_event(entry: &LogEntry) -> Option<JankEvent> {
+    let (kind, frames, duration_ms) = if entry.tag == "Choreographer" {
+        let caps = SKIPPED_REGEX.captures(&entry.message)?;
+        let frames: u32 = caps[1].parse().ok()?;
+        let duration = (frames as f64 * FRAME_MS).round() as u64;
+        (JankKind::SkippedFrames, Some(frames), duration)
+    } else {
+        let caps = DAVEY_REGEX.captures(&entry.message)?;
+        (JankKind::Davey, None, caps[1].parse().ok()?)
+    };
+
+    Some(JankEvent {
+        kind,
+        frames,
+        duration_ms,
+        severity: JankSeverity::from_duration(duration_ms),
+        pid: entry.pid,
+        package_name: entry.package_name.clone(),
+        time: entry.date_time.clone().unwrap_or_else(|| entry.timestamp.clone()),
+        entry_id: entry.id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    fn parse(line: &str) -> Option<JankEvent> {
+        let mut parser = LogParser::new();
+        parse_jank_event(&parser.parse_line(line).unwrap())
+    }
+
+    #[test]
+    fn test_parse_skipped_frames() {
+        let event = parse("12-04 10:30:45.000  4567  4567 I Choreographer: Skipped 34 frames!  The application may be doing too much work on its main thread.").unwrap();
+        assert_eq!(event.kind, JankKind::SkippedFrames);
+        assert_eq!(event.frames, Some(34));
+        assert_eq!(event.duration_ms, 567);
+        assert_eq!(event.severity, JankSeverity::Moderate);
+    }
+
+    #[test]
+    fn test_parse_davey() {
+        let event = parse("12-04 10:30:45.000  4567  4600 I OpenGLRenderer: Davey! duration=812ms; Flags=0, IntendedVsync=123").unwrap();
+        assert_eq!(event.kind, JankKind::Davey);
+        assert_eq!(event.duration_ms, 812);
+        assert_eq!(event.severity, JankSeverity::Severe);
+    }
+
+    #[test]
+    fn test_stats_aggregate() {
+        let mut stats = JankStats::default();
+        stats.record(&parse("12-04 10:30:45.000  4567  4567 I Choreographer: Skipped 5 frames!").unwrap());
+        stats.record(&parse("12-04 10:30:46.000  4567  4600 I OpenGLRenderer: Davey! duration=900ms;").unwrap());
+        assert_eq!(stats.total_events, 2);
+        assert_eq!(stats.skipped_frames, 5);
+        assert_eq!(stats.worst_ms, 900);
+        assert_eq!(stats.by_severity[&JankSeverity::Minor], 1);
+        assert_eq!(stats.by_package["4567"], 2);
+    }
+}
@@ -1,6 +1,62 @@
 // Library exports for Tauri
 pub mod adb;
+pub mod alert;
+pub mod analysis;
+pub mod appstate;
+pub mod archive;
+pub mod audit;
+pub mod cancel;
+pub mod channels;
+pub mod cli;
 pub mod commands;
+pub mod convert;
+pub mod correlation;
+pub mod crash;
+pub mod crashloop;
+pub mod custom_source;
+pub mod diagnostics;
+pub mod editor;
+pub mod environment;
 pub mod filter;
+pub mod fold;
+pub mod follow;
+pub mod highlight;
+pub mod history;
+pub mod ios;
+pub mod jank;
+pub mod lifecycle;
+pub mod links;
+pub mod logd;
+pub mod metrics;
+pub mod noise;
+pub mod pairing;
+pub mod palette;
 pub mod parser;
+pub mod perfetto;
+pub mod pidcat;
+pub mod power;
+pub mod profile;
+pub mod projection;
+pub mod props;
+pub mod redaction;
+pub mod retention;
+pub mod sanitize;
+pub mod secrets;
+pub mod series;
+pub mod sessions;
+pub mod settings;
+pub mod sink;
+pub mod source;
+pub mod spans;
+pub mod startup;
+pub mod streamstats;
+pub mod tagfamily;
+pub mod tasks;
+pub mod template;
+pub mod testrun;
+pub mod thermal;
+pub mod timeseries;
+pub mod timezone;
+pub mod tray;
+pub mod workspace;
 
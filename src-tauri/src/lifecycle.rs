@@ -0,0 +1,205 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::parser::LogEntry;
+
+/// Maximum number of lifecycle events kept per device
+const MAX_LIFECYCLE_EVENTS: usize = 5000;
+
+/// Activity lifecycle transition kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LifecycleKind {
+    Start,
+    Displayed,
+    Create,
+    Resume,
+    Pause,
+    Stop,
+    Destroy,
+}
+
+/// A single navigation/lifecycle event on the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub kind: LifecycleKind,
+    #[serde(rename = "packageName")]
+    pub package_name: String,
+    pub activity: String,
+    /// Launch time reported by `Displayed` lines
+    #[serde(rename = "durationMs", skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    pub time: String,
+    #[serde(rename = "entryId")]
+    pub entry_id: u64,
+}
+
+/// "START u0 {act=... cmp=com.example/.MainActivity ...} from uid 10100"
+static START_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"START u\d+ \{.*?cmp=([\w.]+)/([\w.$]+)").expect("Invalid START regex")
+});
+
+/// "Displayed com.example/.MainActivity: +1s23ms" / "Displayed com.example/.MainActivity for user 0: +523ms"
+static DISPLAYED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Displayed ([\w.]+)/([\w.$]+)(?: for user \d+)?: \+(?:(\d+)s)?(?:(\d+)ms)?")
+        .expect("Invalid Displayed regex")
+});
+
+/// Event log payload: "[0,com.example.MainActivity,RESUME_ACTIVITY]"
+static EVENT_PAYLOAD_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[\d+,([\w.$]+)").expect("Invalid lifecycle event regex")
+});
+
+/// Map an event-log tag (`am_on_resume_called`, `wm_on_stop_called`, ...) to a kind
+fn event_tag_kind(tag: &str) -> Option<LifecycleKind> {
+    let name = tag.strip_prefix("am_").or_else(|| tag.strip_prefix("wm_"))?;
+    match name {
+        "on_create_called" => Some(LifecycleKind::Create),
+        "on_resume_called" => Some(LifecycleKind::Resume),
+        "on_paused_called" => Some(LifecycleKind::Pause),
+        "on_stop_called" => Some(LifecycleKind::Stop),
+        "on_destroy_called" => Some(LifecycleKind::Destroy),
+        _ => None,
+    }
+}
+
+/// Expand the short ".MainActivity" component form to a fully-qualified class name
+fn qualify_activity(package: &str, activity: &str) -> String {
+    if activity.starts_with('.') {
+        format!("{}{}", package, activity)
+    } else {
+        activity.to_string()
+    }
+}
+
+/// Parse a lifecycle event out of a log entry, if it is one
+pub fn parse_lifecycle_event(entry: &LogEntry) -> Option<LifecycleEvent> {
+    let time = entry.date_time.clone().unwrap_or_else(|| entry.timestamp.clone());
+
+    if entry.tag == "ActivityTaskManager" || entry.tag == "ActivityManager" {
+        if let Some(caps) = START_REGEX.captures(&entry.message) {
+            return Some(LifecycleEvent {
+                kind: LifecycleKind::Start,
+                package_name: caps[1].to_string(),
+                activity: qualify_activity(&caps[1], &caps[2]),
+                duration_ms: None,
+                time,
+                entry_id: entry.id,
+            });
+        }
+        if let Some(caps) = DISPLAYED_REGEX.captures(&entry.message) {
+            let secs: u64 = caps.get(3).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+            let millis: u64 = caps.get(4).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+            return Some(LifecycleEvent {
+                kind: LifecycleKind::Displayed,
+                package_name: caps[1].to_string(),
+                activity: qualify_activity(&caps[1], &caps[2]),
+                duration_ms: Some(secs * 1000 + millis),
+                time,
+                entry_id: entry.id,
+            });
+        }
+        return None;
+    }
+
+    let kind = event_tag_kind(&entry.tag)?;
+    let caps = EVENT_PAYLOAD_REGEX.captures(&entry.message)?;
+    let activity = caps[1].to_string();
+    // Event log lines come from the app process, so prefer the resolved package name
+    let package_name = entry.package_name.clone().unwrap_or_else(|| {
+        activity
+            .rsplit_once('.')
+            .map(|(pkg, _)| pkg.to_string())
+            .unwrap_or_default()
+    });
+
+    Some(LifecycleEvent {
+        kind,
+        package_name,
+        activity,
+        duration_ms: None,
+        time,
+        entry_id: entry.id,
+    })
+}
+
+/// Collects the navigation timeline for one device
+#[derive(Debug, Default)]
+pub struct LifecycleTracker {
+    events: VecDeque<LifecycleEvent>,
+}
+
+impl LifecycleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a log entry into the tracker, returning the event it produced
+    pub fn observe(&mut self, entry: &LogEntry) -> Option<LifecycleEvent> {
+        let event = parse_lifecycle_event(entry)?;
+        if self.events.len() >= MAX_LIFECYCLE_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        Some(event)
+    }
+
+    /// Timeline for a package, or for all packages when `package` is None
+    pub fn timeline(&self, package: Option<&str>) -> Vec<LifecycleEvent> {
+        self.events
+            .iter()
+            .filter(|e| package.map_or(true, |p| e.package_name == p))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    fn parse(line: &str) -> Option<LifecycleEvent> {
+        let mut parser = LogParser::new();
+        parse_lifecycle_event(&parser.parse_line(line).unwrap())
+    }
+
+    #[test]
+    fn test_parse_start() {
+        let event = parse("12-04 10:30:45.000  1000  1500 I ActivityTaskManager: START u0 {act=android.intent.action.MAIN cat=[android.intent.category.LAUNCHER] flg=0x10200000 cmp=com.example.app/.MainActivity} from uid 10100").unwrap();
+        assert_eq!(event.kind, LifecycleKind::Start);
+        assert_eq!(event.package_name, "com.example.app");
+        assert_eq!(event.activity, "com.example.app.MainActivity");
+    }
+
+    #[test]
+    fn test_parse_displayed() {
+        let event = parse("12-04 10:30:46.000  1000  1500 I ActivityTaskManager: Displayed com.example.app/.MainActivity for user 0: +1s23ms").unwrap();
+        assert_eq!(event.kind, LifecycleKind::Displayed);
+        assert_eq!(event.duration_ms, Some(1023));
+    }
+
+    #[test]
+    fn test_parse_event_log_resume() {
+        let event = parse("12-04 10:30:46.100  4567  4567 I am_on_resume_called: [0,com.example.app.MainActivity,RESUME_ACTIVITY]").unwrap();
+        assert_eq!(event.kind, LifecycleKind::Resume);
+        assert_eq!(event.package_name, "com.example.app");
+        assert_eq!(event.activity, "com.example.app.MainActivity");
+    }
+
+    #[test]
+    fn test_timeline_filters_by_package() {
+        let mut parser = LogParser::new();
+        let mut tracker = LifecycleTracker::new();
+        for line in [
+            "12-04 10:30:45.000  1000  1500 I ActivityTaskManager: Displayed com.example.a/.Main: +300ms",
+            "12-04 10:30:46.000  1000  1500 I ActivityTaskManager: Displayed com.example.b/.Main: +400ms",
+        ] {
+            tracker.observe(&parser.parse_line(line).unwrap());
+        }
+        assert_eq!(tracker.timeline(Some("com.example.b")).len(), 1);
+        assert_eq!(tracker.timeline(None).len(), 2);
+    }
+}
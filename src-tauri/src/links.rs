@@ -0,0 +1,131 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// What a link in a message points to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+    /// Stack frame `at com.foo.Bar.baz(Bar.kt:42)`
+    Source,
+    /// http(s) URL
+    Url,
+    /// `content://`, `file://` or `android.resource://` URI
+    ContentUri,
+    /// Component of an intent, `cmp=com.foo/.MainActivity`
+    Component,
+}
+
+/// A clickable part of a message. Offsets are UTF-16 code units, like `MatchSpan`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Link {
+    pub kind: LinkKind,
+    pub start: usize,
+    pub end: usize,
+    /// Target text: the URL, URI, component or `file:line`
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// Fully qualified class of a stack frame
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+}
+
+static SOURCE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bat ([\w$.]+)\.[\w$<>-]+\(([\w$-]+\.(?:kt|java|scala|groovy)):(\d+)\)")
+        .expect("Invalid source reference regex")
+});
+
+static URI_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:(https?)|content|file|android\.resource)://[^\s"'<>\]{}]+"#)
+        .expect("Invalid URI regex")
+});
+
+static COMPONENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bcmp=([\w.]+/[\w.$]+)").expect("Invalid component regex")
+});
+
+/// Links found in a message, in order
+pub fn detect(message: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let utf16 = |byte: usize| message[..byte].encode_utf16().count();
+    if message.contains("at ") {
+        for caps in SOURCE_REGEX.captures_iter(message) {
+            let whole = caps.get(0).expect("group 0 always matches");
+            // Skip the "at " so only the frame is underlined
+            let start = whole.start() + 3;
+            links.push(Link {
+                kind: LinkKind::Source,
+                start: utf16(start),
+                end: utf16(whole.end()),
+                text: format!("{}:{}", &caps[2], &caps[3]),
+                file: Some(caps[2].to_string()),
+                line: caps[3].parse().ok(),
+                class: Some(caps[1].to_string()),
+            });
+        }
+    }
+    if message.contains("://") {
+        for caps in URI_REGEX.captures_iter(message) {
+            let whole = caps.get(0).expect("group 0 always matches");
+            // Trailing sentence punctuation is rarely part of the URI
+            let text = whole.as_str().trim_end_matches(['.', ',', ';', ':', ')']);
+            links.push(Link {
+                kind: if caps.get(1).is_some() { LinkKind::Url } else { LinkKind::ContentUri },
+                start: utf16(whole.start()),
+                end: utf16(whole.start() + text.len()),
+                text: text.to_string(),
+                file: None,
+                line: None,
+                class: None,
+            });
+        }
+    }
+    if message.contains("cmp=") {
+        for caps in COMPONENT_REGEX.captures_iter(message) {
+            let component = caps.get(1).expect("group 1 always matches");
+            links.push(Link {
+                kind: LinkKind::Component,
+                start: utf16(component.start()),
+                end: utf16(component.end()),
+                text: component.as_str().to_string(),
+                file: None,
+                line: None,
+                class: None,
+            });
+        }
+    }
+    links.sort_by_key(|link| link.start);
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_frames_urls_and_components() {
+        let frame = detect("\tat com.example.app.CheckoutViewModel.submit(CheckoutViewModel.kt:42)");
+        assert_eq!(frame.len(), 1);
+        assert_eq!(frame[0].kind, LinkKind::Source);
+        assert_eq!(frame[0].file.as_deref(), Some("CheckoutViewModel.kt"));
+        assert_eq!(frame[0].line, Some(42));
+        assert_eq!(frame[0].class.as_deref(), Some("com.example.app.CheckoutViewModel"));
+        assert_eq!(frame[0].start, 4);
+
+        let links = detect("请求 https://api.example.com/v1/orders?id=7. Loaded content://media/external/images/1");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].text, "https://api.example.com/v1/orders?id=7");
+        // UTF-16 offsets: "请求 " is three code units
+        assert_eq!(links[0].start, 3);
+        assert_eq!(links[1].kind, LinkKind::ContentUri);
+
+        let intent = detect("START u0 {act=android.intent.action.MAIN cmp=com.example.app/.MainActivity} from uid 2000");
+        assert_eq!(intent[0].kind, LinkKind::Component);
+        assert_eq!(intent[0].text, "com.example.app/.MainActivity");
+        assert!(detect("plain message").is_empty());
+    }
+}
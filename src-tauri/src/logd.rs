@@ -0,0 +1,202 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// "main: ring buffer is 256 KiB (62 KiB consumed), max entry is 5120 B, ..."
+/// (older releases print "256Kb (254Kb consumed)")
+static RING_BUFFER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\w+): ring buffer is (\d+)\s*([KMG]?)(?:iB|b|B)")
+        .expect("Invalid ring buffer regex")
+});
+
+/// "Chattiest UIDs in main log buffer:"
+static UID_SECTION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^Chattiest UIDs in (\w+) log buffer:").expect("Invalid UID section regex")
+});
+
+/// Usage of one logcat buffer
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferStats {
+    pub name: String,
+    /// Bytes/lines logged since logd started
+    pub total_bytes: u64,
+    pub total_lines: u64,
+    /// Bytes/lines currently held
+    pub now_bytes: u64,
+    pub now_lines: u64,
+    /// Ring buffer size from `logcat -g`
+    pub capacity_bytes: Option<u64>,
+}
+
+/// Log volume of one UID in one buffer
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UidUsage {
+    pub buffer: String,
+    pub uid: u32,
+    pub package: String,
+    pub bytes: u64,
+    /// Lines pruned by logd's quota, when reported
+    pub pruned: Option<u64>,
+}
+
+/// Parsed `logcat -g` and `logcat -S` output
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogdStats {
+    pub buffers: Vec<BufferStats>,
+    /// Chattiest UIDs, in the order logd lists them
+    pub uids: Vec<UidUsage>,
+}
+
+impl LogdStats {
+    fn buffer_mut(&mut self, name: &str) -> &mut BufferStats {
+        let index = match self.buffers.iter().position(|b| b.name == name) {
+            Some(index) => index,
+            None => {
+                self.buffers.push(BufferStats {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.buffers.len() - 1
+            }
+        };
+        &mut self.buffers[index]
+    }
+}
+
+/// "1234/56" -> (1234, 56)
+fn parse_size_num(token: &str) -> Option<(u64, u64)> {
+    let (size, num) = token.split_once('/')?;
+    Some((size.parse().ok()?, num.parse().ok()?))
+}
+
+fn scale(unit: &str) -> u64 {
+    match unit {
+        "K" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => 1,
+    }
+}
+
+/// Parse the combined output of `logcat -g; logcat -S`
+pub fn parse_stats(output: &str) -> LogdStats {
+    let mut stats = LogdStats::default();
+    let mut columns: Vec<String> = Vec::new();
+    let mut uid_buffer: Option<String> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            uid_buffer = None;
+            continue;
+        }
+
+        if let Some(caps) = RING_BUFFER_REGEX.captures(trimmed) {
+            let bytes = caps[2].parse::<u64>().unwrap_or(0) * scale(&caps[3]);
+            stats.buffer_mut(&caps[1]).capacity_bytes = Some(bytes);
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("size/num") {
+            columns = rest
+                .split_whitespace()
+                .filter(|c| *c != "total")
+                .map(str::to_string)
+                .collect();
+            continue;
+        }
+
+        if let Some(caps) = UID_SECTION_REGEX.captures(trimmed) {
+            uid_buffer = Some(caps[1].to_string());
+            continue;
+        }
+
+        let mut tokens = trimmed.split_whitespace();
+        let first = tokens.next().unwrap_or_default();
+        match first {
+            "Total" | "Now" => {
+                // Values follow the header's column order; the trailing total is dropped by zip
+                let values: Vec<(u64, u64)> = tokens.filter_map(parse_size_num).collect();
+                for (name, (bytes, lines)) in columns.iter().zip(values) {
+                    let buffer = stats.buffer_mut(name);
+                    if first == "Total" {
+                        buffer.total_bytes = bytes;
+                        buffer.total_lines = lines;
+                    } else {
+                        buffer.now_bytes = bytes;
+                        buffer.now_lines = lines;
+                    }
+                }
+            }
+            _ => {
+                let (Some(buffer), Ok(uid)) = (&uid_buffer, first.parse::<u32>()) else {
+                    continue;
+                };
+                let package = tokens.next().unwrap_or_default().to_string();
+                let mut numbers = tokens.filter_map(|t| t.parse::<u64>().ok());
+                let Some(bytes) = numbers.next() else {
+                    continue;
+                };
+                stats.uids.push(UidUsage {
+                    buffer: buffer.clone(),
+                    uid,
+                    package,
+                    bytes,
+                    pruned: numbers.next(),
+                });
+            }
+        }
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+main: ring buffer is 256 KiB (180 KiB consumed), max entry is 5120 B, max payload is 4068 B
+system: ring buffer is 256 KiB (90 KiB consumed), max entry is 5120 B, max payload is 4068 B
+size/num main               system             crash              total
+Total    1337862/12345      402010/3012        9132/40            1749004/15397
+Now      184320/1620        92160/700          9132/40            285612/2360
+Logspan  1:02:03.456        1:02:03.456
+
+Chattiest UIDs in main log buffer:                           Size  Pruned
+UID   PACKAGE                                                BYTES LINES
+10123 com.example.chatty                                     120034  5120
+1000  system                                                  40211
+
+Chattiest PIDs in main log buffer:                           Size  Pruned
+  PID/UID   COMMAND LINE                                     BYTES LINES
+ 4321/10123 com.example.chatty                               120034
+";
+
+    #[test]
+    fn test_parse_stats() {
+        let stats = parse_stats(OUTPUT);
+        let main = &stats.buffers[0];
+        assert_eq!(main.name, "main");
+        assert_eq!(main.capacity_bytes, Some(256 * 1024));
+        assert_eq!((main.total_bytes, main.total_lines), (1337862, 12345));
+        assert_eq!((main.now_bytes, main.now_lines), (184320, 1620));
+        assert_eq!(stats.buffers[2].name, "crash");
+        assert_eq!(stats.buffers[2].capacity_bytes, None);
+
+        assert_eq!(stats.uids.len(), 2);
+        assert_eq!(stats.uids[0].package, "com.example.chatty");
+        assert_eq!(stats.uids[0].bytes, 120034);
+        assert_eq!(stats.uids[0].pruned, Some(5120));
+        assert_eq!(stats.uids[1].pruned, None);
+    }
+
+    #[test]
+    fn test_legacy_ring_buffer_units() {
+        let stats = parse_stats("main: ring buffer is 2Mb (1Mb consumed), max entry is 5120b, max payload is 4076b\n");
+        assert_eq!(stats.buffers[0].capacity_bytes, Some(2 * 1024 * 1024));
+    }
+}
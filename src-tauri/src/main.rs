@@ -2,17 +2,110 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod adb;
+mod alert;
+mod analysis;
+mod appstate;
+mod archive;
+mod audit;
+mod cancel;
+mod channels;
+mod cli;
 mod commands;
+mod convert;
+mod correlation;
+mod crash;
+mod crashloop;
+mod custom_source;
+mod diagnostics;
+mod editor;
+mod environment;
 mod filter;
+mod fold;
+mod follow;
+mod highlight;
+mod history;
+mod ios;
+mod jank;
+mod lifecycle;
+mod links;
+mod logd;
+mod metrics;
+mod noise;
+mod pairing;
+mod palette;
 mod parser;
+mod perfetto;
+mod pidcat;
+mod power;
+mod profile;
+mod projection;
+mod props;
+mod redaction;
+mod retention;
+mod sanitize;
+mod secrets;
+mod series;
+mod sessions;
+mod settings;
+mod sink;
+mod source;
+mod spans;
+mod startup;
+mod streamstats;
+mod tagfamily;
+mod tasks;
+mod template;
+mod testrun;
+mod thermal;
+mod timeseries;
+mod timezone;
+mod tray;
+mod workspace;
 
 use adb::AdbManager;
 use commands::LogcatState;
 use log::info;
+use tauri::{Manager, WindowEvent};
+
+/// Release builds on Windows use the GUI subsystem and start without a
+/// console, so attach to the console of the shell that ran us; otherwise the
+/// command-line output is lost. Fails harmlessly when started from Explorer.
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: takes no pointers; on failure the process simply has no console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(all(windows, not(debug_assertions))))]
+fn attach_parent_console() {}
 
 fn main() {
-    // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // `logcat session convert ...` runs headless and exits
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 {
+        attach_parent_console();
+    }
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    match cli::launch_profile(&args) {
+        Ok(Some(launch)) => profile::set_cli_profile(launch),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+
+    // Initialize logger (also captured in memory for diagnostics export)
+    diagnostics::init_logger();
 
     info!("Starting Logcat application");
 
@@ -22,22 +115,139 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::get_devices,
             commands::start_logcat,
+            commands::attach_logcat,
+            commands::wait_for_boot,
             commands::stop_logcat,
+            commands::start_logcat_all,
+            commands::clear_logcat_all,
+            commands::start_logcat_group,
+            commands::apply_group_filter,
+            commands::open_device_window,
+            commands::insert_marker,
+            commands::get_ios_devices,
+            commands::start_ios_syslog,
+            commands::add_custom_source,
+            commands::tail_device_file,
+            commands::remove_custom_source,
+            commands::list_custom_sources,
+            commands::parse_log_file,
+            commands::validate_filter,
+            commands::preview_filter,
+            commands::set_highlight_search,
+            commands::find_adjacent,
+            commands::get_entries_by_correlation,
+            commands::set_correlation_patterns,
+            commands::set_span_rules,
+            commands::set_fold_rules,
+            commands::get_spans,
+            commands::get_span_stats,
+            commands::pin_entry,
+            commands::unpin_entry,
+            commands::get_pinned_entries,
+            commands::set_emit_fields,
+            commands::subscribe_events,
+            commands::unsubscribe_events,
+            commands::set_capture_paused,
+            commands::set_low_latency,
             commands::clear_logcat,
             commands::get_processes,
             commands::check_adb,
+            commands::get_environment_status,
+            commands::restart_adb_with_revoked_keys,
+            commands::switch_to_tcpip,
+            commands::create_qr_pairing,
+            commands::complete_qr_pairing,
+            commands::get_device_capabilities,
+            commands::get_logd_stats,
+            commands::get_root_status,
+            commands::get_debug_props,
+            commands::get_prop,
+            commands::set_prop,
+            commands::set_debug_toggle,
+            commands::get_adb_history,
+            commands::rerun_adb_command,
+            commands::get_task_health,
+            commands::get_stream_stats,
+            commands::cancel_operation,
+            commands::restart_adbd_root,
+            commands::restart_adbd_unroot,
+            commands::follow_package,
+            commands::unfollow_package,
+            commands::get_power_events,
+            commands::get_lifecycle_events,
+            commands::get_jank_stats,
+            commands::get_startup_times,
+            commands::get_test_cases,
+            commands::export_per_test,
+            commands::import_crash_signatures,
+            commands::get_crash_signatures,
+            commands::get_timeseries,
+            commands::get_tag_families,
+            commands::get_message_templates,
+            commands::get_message_series,
+            commands::get_secret_report,
+            commands::get_thermal_status,
+            commands::export_diagnostics,
+            commands::export_pidcat,
+            commands::export_perfetto,
+            commands::open_in_editor,
+            commands::save_session,
+            commands::list_sessions,
+            commands::get_storage_usage,
+            commands::prune_storage,
+            commands::preview_redaction,
+            commands::get_metrics,
+            commands::export_metrics,
+            commands::get_settings,
+            commands::update_settings,
+            commands::get_quick_toggles,
+            commands::set_quick_toggle,
+            commands::set_display_timezone,
+            commands::list_workspaces,
+            commands::save_workspace,
+            commands::load_workspace,
+            commands::save_app_state,
+            commands::restore_app_state,
+            commands::delete_workspace,
+            commands::set_alert_rules,
         ])
         .setup(|app| {
             info!("Tauri app setup complete");
 
+            // Load persisted settings before anything talks to adb
+            match app.path().app_config_dir() {
+                Ok(dir) => settings::init(dir),
+                Err(e) => log::error!("Failed to resolve config dir: {}", e),
+            }
+            commands::restore_active_workspace(app.handle());
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                commands::apply_launch_profile(&handle).await;
+            });
+            commands::restore_crash_signatures(app.handle());
+            retention::spawn_scheduler(app.handle().clone());
+
+            if let Err(e) = tray::init(app.handle()) {
+                log::error!("Failed to create tray icon: {}", e);
+            }
+
             // Start device monitoring task
             let app_handle = app.handle().clone();
             let adb_manager = AdbManager::new();
-            adb_manager.start_device_monitor(app_handle);
+            adb_manager.start_device_monitor(app_handle, &app.state::<LogcatState>().tasks);
             info!("Device monitor started");
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Keep streams alive in the background instead of closing the main window
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == commands::MAIN_WINDOW && settings::current().close_to_tray {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
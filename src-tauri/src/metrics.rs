@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::parser::{LogEntry, LogLevel};
+
+const LEVELS: [LogLevel; 6] = [
+    LogLevel::V,
+    LogLevel::D,
+    LogLevel::I,
+    LogLevel::W,
+    LogLevel::E,
+    LogLevel::A,
+];
+
+/// Cumulative counters for one device
+#[derive(Debug, Default, Clone)]
+struct DeviceCounters {
+    entries: u64,
+    levels: [u64; 6],
+    crashes: u64,
+    drops: u64,
+    unparsed: u64,
+    /// Live filter rule -> (evaluations, time spent)
+    filter_costs: BTreeMap<&'static str, (u64, Duration)>,
+    filter_disabled: u64,
+}
+
+/// Java (`FATAL EXCEPTION`) or native (tombstone header) crash
+fn is_crash(entry: &LogEntry) -> bool {
+    match entry.tag.as_str() {
+        "AndroidRuntime" => entry.message.starts_with("FATAL EXCEPTION"),
+        "DEBUG" | "libc" => entry.message.starts_with("*** *** ***"),
+        _ => false,
+    }
+}
+
+/// Stream health counters, rendered in the Prometheus text exposition format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    devices: BTreeMap<String, DeviceCounters>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn observe(&mut self, device_id: &str, entry: &LogEntry) {
+        let counters = self.devices.entry(device_id.to_string()).or_default();
+        counters.entries += 1;
+        counters.levels[entry.level as usize] += 1;
+        if is_crash(entry) {
+            counters.crashes += 1;
+        }
+    }
+
+    /// The stream ended without being stopped (device unplugged, logcat killed)
+    pub fn record_drop(&mut self, device_id: &str) {
+        self.devices.entry(device_id.to_string()).or_default().drops += 1;
+    }
+
+    /// A line matched no logcat format (emitted as a raw entry or dropped)
+    pub fn record_unparsed(&mut self, device_id: &str) {
+        self.devices.entry(device_id.to_string()).or_default().unparsed += 1;
+    }
+
+    /// Time a live filter rule spent on `evaluations` lines
+    pub fn record_filter_cost(&mut self, device_id: &str, rule: &'static str, evaluations: u64, elapsed: Duration) {
+        let counters = self.devices.entry(device_id.to_string()).or_default();
+        let cost = counters.filter_costs.entry(rule).or_default();
+        cost.0 += evaluations;
+        cost.1 += elapsed;
+    }
+
+    /// A live filter rule went over its time budget and was turned off
+    pub fn record_filter_disabled(&mut self, device_id: &str) {
+        self.devices.entry(device_id.to_string()).or_default().filter_disabled += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP logcat_entries_total Log entries ingested.");
+        let _ = writeln!(out, "# TYPE logcat_entries_total counter");
+        for (device, c) in &self.devices {
+            let _ = writeln!(out, "logcat_entries_total{{device=\"{}\"}} {}", escape(device), c.entries);
+        }
+
+        let _ = writeln!(out, "# HELP logcat_level_entries_total Log entries ingested per level.");
+        let _ = writeln!(out, "# TYPE logcat_level_entries_total counter");
+        for (device, c) in &self.devices {
+            for level in LEVELS {
+                let _ = writeln!(
+                    out,
+                    "logcat_level_entries_total{{device=\"{}\",level=\"{:?}\"}} {}",
+                    escape(device),
+                    level,
+                    c.levels[level as usize]
+                );
+            }
+        }
+
+        let _ = writeln!(out, "# HELP logcat_crashes_total Java and native crashes detected.");
+        let _ = writeln!(out, "# TYPE logcat_crashes_total counter");
+        for (device, c) in &self.devices {
+            let _ = writeln!(out, "logcat_crashes_total{{device=\"{}\"}} {}", escape(device), c.crashes);
+        }
+
+        let _ = writeln!(out, "# HELP logcat_stream_drops_total Streams that ended without being stopped.");
+        let _ = writeln!(out, "# TYPE logcat_stream_drops_total counter");
+        for (device, c) in &self.devices {
+            let _ = writeln!(out, "logcat_stream_drops_total{{device=\"{}\"}} {}", escape(device), c.drops);
+        }
+
+        let _ = writeln!(out, "# HELP logcat_unparsed_lines_total Lines that matched no logcat format.");
+        let _ = writeln!(out, "# TYPE logcat_unparsed_lines_total counter");
+        for (device, c) in &self.devices {
+            let _ = writeln!(out, "logcat_unparsed_lines_total{{device=\"{}\"}} {}", escape(device), c.unparsed);
+        }
+
+        let _ = writeln!(out, "# HELP logcat_filter_evaluations_total Lines evaluated by live filter rules.");
+        let _ = writeln!(out, "# TYPE logcat_filter_evaluations_total counter");
+        for (device, c) in &self.devices {
+            for (rule, (evaluations, _)) in &c.filter_costs {
+                let _ = writeln!(
+                    out,
+                    "logcat_filter_evaluations_total{{device=\"{}\",rule=\"{}\"}} {}",
+                    escape(device),
+                    rule,
+                    evaluations
+                );
+            }
+        }
+
+        let _ = writeln!(out, "# HELP logcat_filter_seconds_total Time spent evaluating live filter rules.");
+        let _ = writeln!(out, "# TYPE logcat_filter_seconds_total counter");
+        for (device, c) in &self.devices {
+            for (rule, (_, elapsed)) in &c.filter_costs {
+                let _ = writeln!(
+                    out,
+                    "logcat_filter_seconds_total{{device=\"{}\",rule=\"{}\"}} {}",
+                    escape(device),
+                    rule,
+                    elapsed.as_secs_f64()
+                );
+            }
+        }
+
+        let _ = writeln!(out, "# HELP logcat_filter_rules_disabled_total Live filter rules disabled for exceeding the time budget.");
+        let _ = writeln!(out, "# TYPE logcat_filter_rules_disabled_total counter");
+        for (device, c) in &self.devices {
+            let _ = writeln!(out, "logcat_filter_rules_disabled_total{{device=\"{}\"}} {}", escape(device), c.filter_disabled);
+        }
+
+        out
+    }
+}
+
+/// Escape a Prometheus label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_render_counters() {
+        let mut parser = LogParser::new();
+        let mut metrics = Metrics::new();
+        let crash = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main")
+            .unwrap();
+        let info = parser
+            .parse_line("12-04 10:30:45.124  1234  1234 I MainActivity: onCreate")
+            .unwrap();
+        metrics.observe("emulator-5554", &crash);
+        metrics.observe("emulator-5554", &info);
+        metrics.record_drop("emulator-5554");
+        metrics.record_unparsed("emulator-5554");
+        metrics.record_filter_cost("emulator-5554", "search", 2, Duration::from_millis(3));
+
+        let text = metrics.render();
+        assert!(text.contains("logcat_entries_total{device=\"emulator-5554\"} 2"));
+        assert!(text.contains("logcat_level_entries_total{device=\"emulator-5554\",level=\"E\"} 1"));
+        assert!(text.contains("logcat_crashes_total{device=\"emulator-5554\"} 1"));
+        assert!(text.contains("logcat_stream_drops_total{device=\"emulator-5554\"} 1"));
+        assert!(text.contains("logcat_unparsed_lines_total{device=\"emulator-5554\"} 1"));
+        assert!(text.contains("logcat_filter_evaluations_total{device=\"emulator-5554\",rule=\"search\"} 2"));
+        assert!(text.contains("logcat_filter_seconds_total{device=\"emulator-5554\",rule=\"search\"} 0.003"));
+    }
+}
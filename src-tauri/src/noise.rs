@@ -0,0 +1,137 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parser::LogEntry;
+
+/// Entry field a quick toggle's pattern is matched against
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ToggleField {
+    Tag,
+    Message,
+}
+
+/// One-click exclusion of a common noise source. Enabled toggles hide the
+/// entries they match on top of the main filter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickToggle {
+    pub name: String,
+    pub field: ToggleField,
+    /// Regex without whitespace (use `\s`), so it fits in one query token
+    pub pattern: String,
+    pub enabled: bool,
+}
+
+impl QuickToggle {
+    fn new(name: &str, field: ToggleField, pattern: &str) -> Self {
+        QuickToggle {
+            name: name.to_string(),
+            field,
+            pattern: pattern.to_string(),
+            enabled: false,
+        }
+    }
+
+    /// The toggle as an exclusion token of the frontend query syntax
+    pub fn query(&self) -> String {
+        let key = match self.field {
+            ToggleField::Tag => "tag",
+            ToggleField::Message => "message",
+        };
+        format!("-{}~:{}", key, self.pattern)
+    }
+}
+
+/// Curated toggles shipped out of the box, all disabled
+pub fn default_toggles() -> Vec<QuickToggle> {
+    vec![
+        QuickToggle::new("Choreographer frame skips", ToggleField::Message, r"^Skipped\s\d+\sframes!"),
+        QuickToggle::new("chatty", ToggleField::Tag, "^chatty$"),
+        QuickToggle::new("libEGL", ToggleField::Tag, "^libEGL$"),
+        QuickToggle::new("ViewRootImpl", ToggleField::Tag, r"^ViewRootImpl(\[.*\])?$"),
+        QuickToggle::new("OpenGLRenderer", ToggleField::Tag, "^OpenGLRenderer$"),
+        QuickToggle::new(
+            "GC messages",
+            ToggleField::Message,
+            r"^(Background|Explicit)\s(concurrent\s)?(copying|mark\ssweep)\sGC",
+        ),
+    ]
+}
+
+/// Compiled enabled toggles
+#[derive(Default)]
+pub struct NoiseFilter {
+    rules: Vec<(ToggleField, Regex)>,
+}
+
+impl NoiseFilter {
+    pub fn new(toggles: &[QuickToggle]) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for toggle in toggles.iter().filter(|t| t.enabled) {
+            if toggle.pattern.chars().any(char::is_whitespace) {
+                return Err(format!("Quick toggle {} must not contain whitespace", toggle.name));
+            }
+            let regex = Regex::new(&toggle.pattern)
+                .map_err(|e| format!("Invalid pattern in quick toggle {}: {}", toggle.name, e))?;
+            rules.push((toggle.field, regex));
+        }
+        Ok(NoiseFilter { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether an enabled toggle hides the entry
+    pub fn is_noise(&self, entry: &LogEntry) -> bool {
+        self.rules.iter().any(|(field, regex)| match field {
+            ToggleField::Tag => regex.is_match(&entry.tag),
+            ToggleField::Message => regex.is_match(&entry.message),
+        })
+    }
+}
+
+/// Exclusion tokens of the enabled toggles, appended to the main query
+pub fn query(toggles: &[QuickToggle]) -> String {
+    toggles
+        .iter()
+        .filter(|t| t.enabled)
+        .map(QuickToggle::query)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_enabled_toggles_stack() {
+        let mut toggles = default_toggles();
+        toggles[0].enabled = true;
+        toggles[1].enabled = true;
+        let noise = NoiseFilter::new(&toggles).unwrap();
+        let mut parser = LogParser::new();
+        let skipped = parser
+            .parse_line("12-04 10:30:45.100  100  100 I Choreographer: Skipped 42 frames!  The application may be doing too much work on its main thread.")
+            .unwrap();
+        let egl = parser
+            .parse_line("12-04 10:30:45.200  100  100 D libEGL: loaded /vendor/lib64/egl/libEGL_adreno.so")
+            .unwrap();
+
+        assert!(noise.is_noise(&skipped));
+        assert!(!noise.is_noise(&egl));
+        assert_eq!(
+            query(&toggles),
+            r"-message~:^Skipped\s\d+\sframes! -tag~:^chatty$"
+        );
+
+        let all: Vec<QuickToggle> = default_toggles()
+            .into_iter()
+            .map(|t| QuickToggle { enabled: true, ..t })
+            .collect();
+        assert!(NoiseFilter::new(&all).is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// mDNS service type advertised by a phone showing "Pair device with QR code"
+const PAIRING_SERVICE: &str = "_adb-tls-pairing._tcp";
+
+const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// What the QR code shown to the phone encodes
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingPayload {
+    /// mDNS instance name the phone advertises once it has scanned the code
+    pub service_name: String,
+    pub password: String,
+    /// Text to render as the QR code, in the format Android Studio uses
+    pub qr_content: String,
+}
+
+/// Payload of the `qr-pairing` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingStatus {
+    pub service_name: String,
+    /// "waiting", "pairing", "paired" or "failed"
+    pub stage: String,
+    pub message: Option<String>,
+}
+
+/// One line of `adb mdns services`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdnsService {
+    pub name: String,
+    pub service_type: String,
+    /// "ip:port"
+    pub address: String,
+}
+
+/// Random token from `alphabet`. Seeded by the std hasher's per-process random
+/// keys; the value only has to be unguessable while the QR code is shown.
+fn random_token(len: usize, alphabet: &[u8]) -> String {
+    let state = RandomState::new();
+    (0..len)
+        .map(|i| {
+            let mut hasher = state.build_hasher();
+            hasher.write_usize(i);
+            alphabet[(hasher.finish() % alphabet.len() as u64) as usize] as char
+        })
+        .collect()
+}
+
+/// New service name and password for one pairing attempt
+pub fn new_payload() -> PairingPayload {
+    let service_name = format!("studio-{}", random_token(10, ALPHANUMERIC));
+    let password = random_token(12, ALPHANUMERIC);
+    PairingPayload {
+        qr_content: format!("WIFI:T:ADB;S:{};P:{};;", service_name, password),
+        service_name,
+        password,
+    }
+}
+
+/// Services listed by `adb mdns services`, skipping the header
+pub fn parse_mdns_services(output: &str) -> Vec<MdnsService> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (name, service_type, address) = (parts.next()?, parts.next()?, parts.next()?);
+            service_type.starts_with("_adb").then(|| MdnsService {
+                name: name.to_string(),
+                service_type: service_type.trim_end_matches('.').to_string(),
+                address: address.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Address of the pairing service advertised under `service_name`
+pub fn pairing_address(services: &[MdnsService], service_name: &str) -> Option<String> {
+    services
+        .iter()
+        .find(|s| s.name == service_name && s.service_type == PAIRING_SERVICE)
+        .map(|s| s.address.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_and_mdns_lookup() {
+        let payload = new_payload();
+        assert_eq!(payload.password.len(), 12);
+        assert!(payload.qr_content.starts_with(&format!("WIFI:T:ADB;S:{};", payload.service_name)));
+        assert_ne!(new_payload().password, payload.password);
+
+        let output = format!(
+            "List of discovered mdns services\n\
+             adb-R58M123ABC-x1y2z3\t_adb-tls-connect._tcp.\t192.168.1.23:37381\n\
+             {}\t_adb-tls-pairing._tcp.\t192.168.1.23:41235\n",
+            payload.service_name
+        );
+        let services = parse_mdns_services(&output);
+        assert_eq!(services.len(), 2);
+        assert_eq!(
+            pairing_address(&services, &payload.service_name),
+            Some("192.168.1.23:41235".to_string())
+        );
+        assert_eq!(pairing_address(&services, "adb-R58M123ABC-x1y2z3"), None);
+    }
+}
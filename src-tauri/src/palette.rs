@@ -0,0 +1,30 @@
+/// Number of colours in the frontend's device/package palette
+pub const PALETTE_SIZE: u8 = 12;
+
+/// Palette index for a device serial or package name
+pub fn color_index(key: &str) -> u8 {
+    (fnv1a(key) % u64::from(PALETTE_SIZE)) as u8
+}
+
+/// FNV-1a rather than the std hasher, whose keys are random per process, so a
+/// device keeps its colour across sessions and windows
+pub fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_index_is_stable() {
+        assert_eq!(color_index("emulator-5554"), color_index("emulator-5554"));
+        assert!(color_index("R58M123ABC") < PALETTE_SIZE);
+        let distinct: std::collections::HashSet<u8> = (5554..5574)
+            .map(|port| color_index(&format!("emulator-{}", port)))
+            .collect();
+        assert!(distinct.len() > 1);
+    }
+}
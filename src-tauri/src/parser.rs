@@ -1,9 +1,16 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-/// Log level enum matching Android's log levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+use crate::links::Link;
+use crate::sanitize;
+
+/// Log level enum matching Android's log levels, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum LogLevel {
     V, // Verbose
     D, // Debug
@@ -31,11 +38,18 @@ impl LogLevel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     pub id: u64,
+    /// Position in its device's stream, breaks ties between equal `epoch`s
+    #[serde(default)]
+    pub seq: u64,
     pub timestamp: String,
     #[serde(rename = "dateTime", skip_serializing_if = "Option::is_none")]
     pub date_time: Option<String>,
+    /// Milliseconds since the Unix epoch, from `time` when known
     #[serde(skip_serializing_if = "Option::is_none")]
     pub epoch: Option<u64>,
+    /// Device wall-clock time with the device's UTC offset (RFC 3339 on the wire)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<FixedOffset>>,
     pub pid: u32,
     pub tid: u32,
     pub level: LogLevel,
@@ -45,18 +59,127 @@ pub struct LogEntry {
     pub package_name: Option<String>,
     #[serde(rename = "processName", skip_serializing_if = "Option::is_none")]
     pub process_name: Option<String>,
+    /// Palette index of the source device, set when the entry is forwarded
+    #[serde(rename = "deviceColor", default, skip_serializing_if = "Option::is_none")]
+    pub device_color: Option<u8>,
+    /// Palette index of `package_name`
+    #[serde(rename = "packageColor", default, skip_serializing_if = "Option::is_none")]
+    pub package_color: Option<u8>,
+    /// Request/trace ID captured by the correlation patterns
+    #[serde(rename = "correlationId", default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Source references, URLs and intent components in the message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<Link>,
+    /// Fold group (e.g. a stack trace) the entry belongs to: the ID of its first entry
+    #[serde(rename = "groupId", default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<u64>,
+    /// Set when the message looks like it holds a credential
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
+    /// Set when control or bidi characters were removed from the tag or
+    /// message; `raw` keeps the line as printed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sanitized: bool,
+    /// Synthetic entry marking a point in time, see `insert_marker`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub marker: bool,
+    /// Level implied by the color of a `-v color` line
+    #[serde(rename = "colorLevel", default, skip_serializing_if = "Option::is_none")]
+    pub color_level: Option<LogLevel>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<String>,
 }
 
+impl LogEntry {
+    /// Device-side time of the entry in milliseconds. Entries without `time`
+    /// (e.g. sent back by the frontend) fall back to `date_time` in the current year.
+    pub fn device_time_millis(&self) -> Option<i64> {
+        if let Some(time) = self.time {
+            return Some(time.timestamp_millis());
+        }
+        let date_time = self.date_time.as_deref()?;
+        let normalized = date_time.split_whitespace().collect::<Vec<_>>().join(" ");
+        let with_year = format!("{}-{}", chrono::Local::now().year(), normalized);
+        chrono::NaiveDateTime::parse_from_str(&with_year, "%Y-%m-%d %H:%M:%S%.3f")
+            .ok()
+            .map(|t| t.and_utc().timestamp_millis())
+    }
+
+    /// Canonical order of entries, also across devices: (epoch, device, seq)
+    pub fn sort_key<'a>(&self, device_id: &'a str) -> (u64, &'a str, u64) {
+        (self.epoch.unwrap_or(0), device_id, self.seq)
+    }
+
+    /// Log line as written to files: the original line when kept and clean,
+    /// else threadtime-like
+    pub fn to_line(&self) -> String {
+        self.raw.clone().filter(|_| !self.sanitized).unwrap_or_else(|| {
+            format!(
+                "{} {:5} {:5} {:?} {}: {}",
+                self.date_time.as_deref().unwrap_or(&self.timestamp),
+                self.pid,
+                self.tid,
+                self.level,
+                self.tag,
+                self.message
+            )
+        })
+    }
+}
+
 /// Regex patterns for parsing logcat output
-/// Format: "MM-DD HH:MM:SS.mmm PID TID LEVEL TAG: MESSAGE"
+/// Format: "MM-DD HH:MM:SS.mmm [UID] PID TID LEVEL TAG: MESSAGE"
+/// (the UID column appears with `-v uid`, numeric or a name like "u0_a123")
 static LOGCAT_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
-        r"^(\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\.\d{3})\s+(\d+)\s+(\d+)\s+([VDIWEFA])\s+([^:]+):\s*(.*)$"
+        r"^(\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\.\d{3})\s+(?:\w+\s+)?(\d+)\s+(\d+)\s+([VDIWEFA])\s+([^:]+):\s*(.*)$"
     ).expect("Invalid logcat regex")
 });
 
+/// Buffer identifier some OEM builds put before the timestamp ("main ", "[system] ")
+static BUFFER_PREFIX_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[?(?:main|system|crash|events|radio|kernel|security|stats)\]?\s+(\d)")
+        .expect("Invalid buffer prefix regex")
+});
+
+/// MTK module prefix before the tag: "D [name:surfaceflinger&]SurfaceFlinger: ..."
+static NAME_PREFIX_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\s[VDIWEFA]\s+)\[name:[^\]]*\]\s*").expect("Invalid name prefix regex")
+});
+
+/// Level column of threadtime/time lines, with an MTK module prefix after it
+static LEVEL_COLUMN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\s[VDIWEFA]\s+(?:\[name:[^\]]*\])?").expect("Invalid level column regex")
+});
+
+/// Length of the header columns: everything before the `:` ending the tag
+fn header_len(line: &str) -> usize {
+    let start = LEVEL_COLUMN_REGEX.find(line).map_or(0, |m| m.end());
+    line[start..].find(':').map_or(line.len(), |i| start + i)
+}
+
+/// Undo known OEM deviations from the stock formats. Only the header columns
+/// are touched; the message stays as the device logged it.
+fn normalize_vendor_line(line: &str) -> Cow<'_, str> {
+    let (header, message) = line.split_at(header_len(line));
+    let mut header = Cow::Borrowed(header);
+    // Non-breaking spaces used as column padding
+    if header.contains(['\u{a0}', '\u{202f}']) {
+        header = Cow::Owned(header.replace(['\u{a0}', '\u{202f}'], " "));
+    }
+    if let Cow::Owned(stripped) = BUFFER_PREFIX_REGEX.replace(&header, "$1") {
+        header = Cow::Owned(stripped);
+    }
+    if let Cow::Owned(stripped) = NAME_PREFIX_REGEX.replace(&header, "$1") {
+        header = Cow::Owned(stripped);
+    }
+    match header {
+        Cow::Borrowed(_) => Cow::Borrowed(line),
+        Cow::Owned(header) => Cow::Owned(header + message),
+    }
+}
+
 /// Alternative format: "HH:MM:SS.mmm PID TID LEVEL TAG: MESSAGE"
 static LOGCAT_ALT_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
@@ -71,38 +194,97 @@ static LOGCAT_BRIEF_REGEX: Lazy<Regex> = Lazy::new(|| {
     ).expect("Invalid brief logcat regex")
 });
 
+/// Entry IDs shared by every parser and source. Seeded with the launch time
+/// (ms × 1000) so IDs don't collide across devices, stream restarts or saved
+/// sessions of earlier launches, while staying below 2^53 for JavaScript.
+static NEXT_ENTRY_ID: Lazy<AtomicU64> =
+    Lazy::new(|| AtomicU64::new(chrono::Utc::now().timestamp_millis().max(0) as u64 * 1000));
+
+/// Allocate an entry ID; IDs increase in allocation order
+pub fn next_entry_id() -> u64 {
+    NEXT_ENTRY_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Last sequence number per device, kept across stream restarts
+static DEVICE_SEQS: Lazy<std::sync::Mutex<HashMap<String, u64>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Allocate the next sequence number of a device's entries (starting at 1)
+pub fn next_device_seq(device_id: &str) -> u64 {
+    let mut seqs = DEVICE_SEQS.lock().unwrap_or_else(|e| e.into_inner());
+    let seq = seqs.entry(device_id.to_string()).or_insert(0);
+    *seq += 1;
+    *seq
+}
+
 /// Parser for logcat output
 pub struct LogParser {
-    next_id: u64,
+    /// UTC offset of the device clock that printed the timestamps
+    offset: FixedOffset,
 }
 
 impl LogParser {
+    /// Parser for timestamps in the host's timezone
     pub fn new() -> Self {
-        LogParser { next_id: 0 }
+        Self::with_offset(*chrono::Local::now().offset())
+    }
+
+    pub fn with_offset(offset: FixedOffset) -> Self {
+        LogParser { offset }
+    }
+
+    fn now(&self) -> DateTime<FixedOffset> {
+        Utc::now().with_timezone(&self.offset)
+    }
+
+    /// Time of a "MM-DD HH:MM:SS.mmm" timestamp. Logcat omits the year: the
+    /// current one is assumed, or the previous one when that would put the
+    /// entry in the future (logs from before new year).
+    fn device_time(&self, month_day_time: &str) -> Option<DateTime<FixedOffset>> {
+        let now = self.now();
+        let normalized = month_day_time.split_whitespace().collect::<Vec<_>>().join(" ");
+        let in_year = |year: i32| {
+            NaiveDateTime::parse_from_str(&format!("{}-{}", year, normalized), "%Y-%m-%d %H:%M:%S%.3f")
+                .ok()
+                .and_then(|t| t.and_local_timezone(self.offset).single())
+        };
+        match in_year(now.year()) {
+            Some(time) if time - now > chrono::Duration::days(1) => in_year(now.year() - 1),
+            time => time,
+        }
     }
 
     /// Parse a single line of logcat output
     pub fn parse_line(&mut self, line: &str) -> Option<LogEntry> {
         // Skip empty lines
-        let line = line.trim();
-        if line.is_empty() {
+        let original = line.trim();
+        if original.is_empty() {
             return None;
         }
 
         // Skip "beginning of" messages
-        if line.starts_with("--------- beginning of") {
+        if original.starts_with("--------- beginning of") {
             return None;
         }
 
+        // `raw` keeps the line as the device printed it. `-v color` wraps
+        // lines in color escapes, which would defeat every pattern.
+        let (uncolored, color_level) = sanitize::strip_color(original);
+        let colored = matches!(uncolored, Cow::Owned(_));
+        let normalized = normalize_vendor_line(&uncolored);
+        let line = normalized.as_ref();
+
         // Try standard format first (with date: MM-DD HH:MM:SS.mmm)
         if let Some(caps) = LOGCAT_REGEX.captures(line) {
             let timestamp_str = caps[1].to_string();
-            let now = chrono::Local::now();
-            let entry = LogEntry {
-                id: self.next_id,
+            let time = self.device_time(&timestamp_str);
+            let mut entry = LogEntry {
+                id: next_entry_id(),
+                seq: 0,
                 timestamp: timestamp_str.split_whitespace().last().unwrap_or(&timestamp_str).to_string(),
                 date_time: Some(timestamp_str.clone()),
-                epoch: Some(now.timestamp_millis() as u64),
+                epoch: Some(time.unwrap_or_else(|| self.now()).timestamp_millis() as u64),
+                time,
                 pid: caps[2].parse().unwrap_or(0),
                 tid: caps[3].parse().unwrap_or(0),
                 level: LogLevel::from_char(caps[4].chars().next().unwrap_or('D'))
@@ -111,21 +293,34 @@ impl LogParser {
                 message: caps[6].to_string(),
                 package_name: None,  // Will be filled by AdbManager
                 process_name: None,  // Will be filled by AdbManager
-                raw: Some(line.to_string()),
+                device_color: None,
+                package_color: None,
+                correlation_id: None,
+                links: Vec::new(),
+                group_id: None,
+                sensitive: false,
+                sanitized: colored,
+                marker: false,
+                color_level,
+                raw: Some(original.to_string()),
             };
-            self.next_id += 1;
+            sanitize::apply(&mut entry);
             return Some(entry);
         }
 
         // Try alternative format (without date: HH:MM:SS.mmm)
         if let Some(caps) = LOGCAT_ALT_REGEX.captures(line) {
-            let now = chrono::Local::now();
+            let now = self.now();
             let timestamp_str = caps[1].to_string();
-            let entry = LogEntry {
-                id: self.next_id,
+            let date_time = format!("{} {}", now.format("%m-%d"), timestamp_str);
+            let time = self.device_time(&date_time);
+            let mut entry = LogEntry {
+                id: next_entry_id(),
+                seq: 0,
                 timestamp: timestamp_str.clone(),
-                date_time: Some(format!("{} {}", now.format("%m-%d"), timestamp_str)),
-                epoch: Some(now.timestamp_millis() as u64),
+                date_time: Some(date_time),
+                epoch: Some(time.unwrap_or(now).timestamp_millis() as u64),
+                time,
                 pid: caps[2].parse().unwrap_or(0),
                 tid: caps[3].parse().unwrap_or(0),
                 level: LogLevel::from_char(caps[4].chars().next().unwrap_or('D'))
@@ -134,21 +329,33 @@ impl LogParser {
                 message: caps[6].to_string(),
                 package_name: None,
                 process_name: None,
-                raw: Some(line.to_string()),
+                device_color: None,
+                package_color: None,
+                correlation_id: None,
+                links: Vec::new(),
+                group_id: None,
+                sensitive: false,
+                sanitized: colored,
+                marker: false,
+                color_level,
+                raw: Some(original.to_string()),
             };
-            self.next_id += 1;
+            sanitize::apply(&mut entry);
             return Some(entry);
         }
 
         // Try brief format
         if let Some(caps) = LOGCAT_BRIEF_REGEX.captures(line) {
-            let now = chrono::Local::now();
+            // No timestamp in the line, use the receive time
+            let now = self.now();
             let timestamp_str = now.format("%H:%M:%S%.3f").to_string();
-            let entry = LogEntry {
-                id: self.next_id,
+            let mut entry = LogEntry {
+                id: next_entry_id(),
+                seq: 0,
                 timestamp: timestamp_str.clone(),
                 date_time: Some(format!("{} {}", now.format("%m-%d"), timestamp_str)),
                 epoch: Some(now.timestamp_millis() as u64),
+                time: Some(now),
                 pid: caps[3].parse().unwrap_or(0),
                 tid: 0,
                 level: LogLevel::from_char(caps[1].chars().next().unwrap_or('D'))
@@ -157,28 +364,119 @@ impl LogParser {
                 message: caps[4].to_string(),
                 package_name: None,
                 process_name: None,
-                raw: Some(line.to_string()),
+                device_color: None,
+                package_color: None,
+                correlation_id: None,
+                links: Vec::new(),
+                group_id: None,
+                sensitive: false,
+                sanitized: colored,
+                marker: false,
+                color_level,
+                raw: Some(original.to_string()),
             };
-            self.next_id += 1;
+            sanitize::apply(&mut entry);
             return Some(entry);
         }
 
-        // If no pattern matches, return as a debug message with "Unknown" tag
-        // This handles continuation lines or unusual formats
+        // No format matched; the stream keeps the line via `unparsed_entry`
+        // (verbatim, info level) when `emit_unparsed` is on, else drops it
         None
     }
 
+    /// Entry from fields already split apart, e.g. a CSV row. `date_time` is
+    /// "MM-DD HH:MM:SS.mmm", or a bare "HH:MM:SS.mmm" taken as today; the
+    /// message is kept whole, newlines included.
+    pub fn column_entry(
+        &self,
+        date_time: &str,
+        (pid, tid): (u32, u32),
+        level: LogLevel,
+        tag: &str,
+        message: &str,
+    ) -> LogEntry {
+        let date_time = date_time.trim();
+        let date_time = match date_time.split_whitespace().count() {
+            0 => None,
+            1 => Some(format!("{} {}", self.now().format("%m-%d"), date_time)),
+            _ => Some(date_time.to_string()),
+        };
+        let time = date_time.as_deref().and_then(|dt| self.device_time(dt));
+        LogEntry {
+            id: next_entry_id(),
+            seq: 0,
+            timestamp: date_time
+                .as_deref()
+                .and_then(|dt| dt.split_whitespace().last())
+                .unwrap_or_default()
+                .to_string(),
+            date_time,
+            epoch: Some(time.unwrap_or_else(|| self.now()).timestamp_millis() as u64),
+            time,
+            pid,
+            tid,
+            level,
+            tag: tag.to_string(),
+            message: message.to_string(),
+            package_name: None,
+            process_name: None,
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
+            raw: None,
+        }
+    }
+
+    /// Entry for a line no format matched, kept verbatim as an info message.
+    /// `None` for blank lines and buffer headers, which aren't content.
+    pub fn unparsed_entry(&mut self, line: &str, tag: &str) -> Option<LogEntry> {
+        let line = line.trim_end();
+        if line.trim().is_empty() || line.starts_with("--------- beginning of") {
+            return None;
+        }
+        let now = self.now();
+        let timestamp = now.format("%H:%M:%S%.3f").to_string();
+        let mut entry = LogEntry {
+            id: next_entry_id(),
+            seq: 0,
+            timestamp: timestamp.clone(),
+            date_time: Some(format!("{} {}", now.format("%m-%d"), timestamp)),
+            epoch: Some(now.timestamp_millis() as u64),
+            time: Some(now),
+            pid: 0,
+            tid: 0,
+            level: LogLevel::I,
+            tag: tag.to_string(),
+            message: line.to_string(),
+            package_name: None,
+            process_name: None,
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
+            raw: Some(line.to_string()),
+        };
+        sanitize::apply(&mut entry);
+        Some(entry)
+    }
+
     /// Parse multiple lines and return all valid entries
     pub fn parse_lines(&mut self, text: &str) -> Vec<LogEntry> {
         text.lines()
             .filter_map(|line| self.parse_line(line))
             .collect()
     }
-
-    /// Reset the parser state
-    pub fn reset(&mut self) {
-        self.next_id = 0;
-    }
 }
 
 impl Default for LogParser {
@@ -208,6 +506,46 @@ mod tests {
         assert!(entry.process_name.is_none());
     }
 
+    #[test]
+    fn test_sort_key_breaks_ties_by_device_and_seq() {
+        let mut parser = LogParser::new();
+        let line = "12-04 10:30:45.123  1234  5678 D Tag: same millisecond";
+        let mut entries: Vec<(&str, LogEntry)> = ["b", "a", "a"]
+            .into_iter()
+            .map(|device| {
+                let mut entry = parser.parse_line(line).unwrap();
+                entry.seq = next_device_seq(&format!("sort-test-{}", device));
+                (device, entry)
+            })
+            .collect();
+        entries.reverse();
+        entries.sort_by(|(da, a), (db, b)| a.sort_key(da).cmp(&b.sort_key(db)));
+
+        let order: Vec<(&str, u64)> = entries.iter().map(|(d, e)| (*d, e.seq)).collect();
+        assert_eq!(order, vec![("a", 1), ("a", 2), ("b", 1)]);
+    }
+
+    #[test]
+    fn test_device_time_uses_offset() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let mut parser = LogParser::with_offset(offset);
+        let now = Utc::now().with_timezone(&offset);
+        let line = format!("{}  1234  5678 D Tag: message", now.format("%m-%d %H:%M:%S%.3f"));
+        let entry = parser.parse_line(&line).unwrap();
+
+        let time = entry.time.unwrap();
+        assert_eq!(*time.offset(), offset);
+        assert_eq!(time.year(), now.year());
+        assert_eq!(entry.epoch, Some(time.timestamp_millis() as u64));
+        assert!(serde_json::to_string(&entry).unwrap().contains("+08:00"));
+
+        // A date after today belongs to the previous year
+        let tomorrow_next_month = now + chrono::Duration::days(40);
+        let line = format!("{}  1234  5678 D Tag: old", tomorrow_next_month.format("%m-%d %H:%M:%S%.3f"));
+        let old = parser.parse_line(&line).unwrap().time.unwrap();
+        assert!(old < now);
+    }
+
     #[test]
     fn test_parse_brief_format() {
         let mut parser = LogParser::new();
@@ -220,6 +558,97 @@ mod tests {
         assert_eq!(entry.message, "onCreate called");
     }
 
+    /// Lines reported from OEM devices: (line, pid, tid, tag, message)
+    const OEM_SAMPLES: &[(&str, u32, u32, &str, &str)] = &[
+        // Samsung, `-v uid` column with an app UID name
+        (
+            "12-04 10:30:45.123 u0_a231  4321  4390 I ViewRootImpl@a1b2c3d[MainActivity]: ViewPostIme pointer 0",
+            4321,
+            4390,
+            "ViewRootImpl@a1b2c3d[MainActivity]",
+            "ViewPostIme pointer 0",
+        ),
+        // Samsung, numeric UID column
+        (
+            "12-04 10:30:45.124  1000  1523  1610 D SamsungAlarmManager: setExact Listener",
+            1523,
+            1610,
+            "SamsungAlarmManager",
+            "setExact Listener",
+        ),
+        // MTK module prefix
+        (
+            "12-04 10:30:45.125   512   512 D [name:surfaceflinger&]SurfaceFlinger: onMessageRefresh",
+            512,
+            512,
+            "SurfaceFlinger",
+            "onMessageRefresh",
+        ),
+        // Xiaomi, buffer identifier before the timestamp
+        (
+            "main 12-04 10:30:45.126  2731  2731 I MiuiGallery: load album",
+            2731,
+            2731,
+            "MiuiGallery",
+            "load album",
+        ),
+        (
+            "[system] 12-04 10:30:45.127  1833  2001 W ActivityManager: Slow operation",
+            1833,
+            2001,
+            "ActivityManager",
+            "Slow operation",
+        ),
+        // Wide PID fields
+        (
+            "12-04 10:30:45.128 4194302 4194310 E chromium: [ERROR:gpu_init.cc(523)] Passthrough",
+            4194302,
+            4194310,
+            "chromium",
+            "[ERROR:gpu_init.cc(523)] Passthrough",
+        ),
+        // Non-breaking space padding
+        (
+            "12-04\u{a0}10:30:45.129\u{a0}\u{a0}987\u{a0}\u{a0}988 I Tag\u{a0}: padded",
+            987,
+            988,
+            "Tag",
+            "padded",
+        ),
+    ];
+
+    #[test]
+    fn test_vendor_normalization_spares_message() {
+        let mut parser = LogParser::new();
+        let line = "12-04\u{a0}10:30:45.130  987  988 I Tag: 12\u{202f}kB D [name:x] kept";
+        let entry = parser.parse_line(line).unwrap();
+        assert_eq!(entry.tag, "Tag");
+        assert_eq!(entry.message, "12\u{202f}kB D [name:x] kept");
+    }
+
+    #[test]
+    fn test_oem_samples() {
+        let mut parser = LogParser::new();
+        for (line, pid, tid, tag, message) in OEM_SAMPLES {
+            let entry = parser
+                .parse_line(line)
+                .unwrap_or_else(|| panic!("Unparsed: {}", line));
+            assert_eq!((entry.pid, entry.tid), (*pid, *tid), "{}", line);
+            assert_eq!(entry.tag, *tag, "{}", line);
+            assert_eq!(entry.message, *message, "{}", line);
+            assert_eq!(entry.raw.as_deref(), Some(line.trim()));
+        }
+    }
+
+    #[test]
+    fn test_ids_unique_across_parsers() {
+        let line = "12-04 10:30:45.123  1234  5678 D MainActivity: onCreate called";
+        let first = LogParser::new().parse_line(line).unwrap();
+        let second = LogParser::new().parse_line(line).unwrap();
+        assert!(second.id > first.id);
+        assert!(second.id < 1 << 53);
+    }
+
     #[test]
     fn test_skip_beginning_marker() {
         let mut parser = LogParser::new();
@@ -227,5 +656,90 @@ mod tests {
         let entry = parser.parse_line(line);
         assert!(entry.is_none());
     }
-}
 
+    #[test]
+    fn test_unparsed_entry() {
+        let mut parser = LogParser::new();
+        let line = "[vendor] thermal zone 3 throttling";
+        assert!(parser.parse_line(line).is_none());
+        let entry = parser.unparsed_entry(line, "RAW").unwrap();
+        assert_eq!(entry.level, LogLevel::I);
+        assert_eq!(entry.tag, "RAW");
+        assert_eq!(entry.message, line);
+        assert!(parser.unparsed_entry("--------- beginning of main", "RAW").is_none());
+        assert!(parser.unparsed_entry("   ", "RAW").is_none());
+    }
+
+    mod props {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn level() -> impl Strategy<Value = LogLevel> {
+            prop_oneof![
+                Just(LogLevel::V),
+                Just(LogLevel::D),
+                Just(LogLevel::I),
+                Just(LogLevel::W),
+                Just(LogLevel::E),
+                Just(LogLevel::A),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn parse_line_never_panics(line in "\\PC{0,200}") {
+                let mut parser = LogParser::new();
+                let _ = parser.parse_line(&line);
+                let _ = parser.unparsed_entry(&line, "RAW");
+            }
+
+            #[test]
+            fn formatted_entry_parses_back(
+                (month, day) in (1u32..=12, 1u32..=28),
+                (hour, minute, second, milli) in (0u32..24, 0u32..60, 0u32..60, 0u32..1000),
+                pid in any::<u32>(),
+                tid in any::<u32>(),
+                level in level(),
+                tag in "[A-Za-z][A-Za-z0-9_.]{0,22}",
+                message in "([!-~]([ -~]{0,60}[!-~])?)?",
+            ) {
+                let date_time = format!(
+                    "{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+                    month, day, hour, minute, second, milli
+                );
+                let entry = LogEntry {
+                    id: 0,
+                    seq: 0,
+                    timestamp: date_time[6..].to_string(),
+                    date_time: Some(date_time.clone()),
+                    epoch: None,
+                    time: None,
+                    pid,
+                    tid,
+                    level,
+                    tag: tag.clone(),
+                    message: message.clone(),
+                    package_name: None,
+                    process_name: None,
+                    device_color: None,
+                    package_color: None,
+                    correlation_id: None,
+                    links: Vec::new(),
+                    group_id: None,
+                    sensitive: false,
+                    sanitized: false,
+                    marker: false,
+                    color_level: None,
+                    raw: None,
+                };
+
+                let parsed = LogParser::new().parse_line(&entry.to_line()).unwrap();
+                prop_assert_eq!(parsed.date_time, Some(date_time));
+                prop_assert_eq!(parsed.timestamp, entry.timestamp);
+                prop_assert_eq!((parsed.pid, parsed.tid, parsed.level), (pid, tid, level));
+                prop_assert_eq!(parsed.tag, tag);
+                prop_assert_eq!(parsed.message, message);
+            }
+        }
+    }
+}
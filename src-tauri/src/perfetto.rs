@@ -0,0 +1,136 @@
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::crash::{CrashDetector, CrashReport};
+use crate::parser::LogEntry;
+use crate::spans::{SpanRule, SpanTracker};
+
+/// Render entries as Chrome/Perfetto trace-event JSON, openable in
+/// ui.perfetto.dev. Entries become instant events on their process and thread
+/// tracks, markers global instants, and crashes and `span_rules` spans are
+/// re-derived from the entries so imported files export the same way.
+/// Entries without a device time are skipped.
+pub fn render(
+    device_id: &str,
+    entries: &[LogEntry],
+    span_rules: &[SpanRule],
+) -> Result<String, String> {
+    let mut events = Vec::new();
+    let mut processes: BTreeMap<u32, String> = BTreeMap::new();
+    let mut threads: HashMap<u64, (u32, u32)> = HashMap::new();
+    let mut crashes = CrashDetector::new();
+    let mut spans = SpanTracker::new(span_rules)?;
+    let micros = |entry: &LogEntry| entry.device_time_millis().map(|ms| ms * 1000);
+
+    for entry in entries {
+        let Some(ts) = micros(entry) else {
+            continue;
+        };
+        threads.insert(entry.id, (entry.pid, entry.tid));
+        if entry.marker {
+            events.push(json!({
+                "ph": "i", "s": "g", "cat": "marker",
+                "name": entry.message, "ts": ts, "pid": 0, "tid": 0,
+            }));
+            continue;
+        }
+        if let Some(name) = entry.package_name.as_ref().or(entry.process_name.as_ref()) {
+            processes.insert(entry.pid, name.clone());
+        }
+        events.push(json!({
+            "ph": "i", "s": "t", "cat": format!("log,{:?}", entry.level),
+            "name": entry.tag, "ts": ts, "pid": entry.pid, "tid": entry.tid,
+            "args": { "level": entry.level, "message": entry.message },
+        }));
+
+        for span in spans.observe(device_id, entry) {
+            let (pid, tid) = threads
+                .get(&span.start_entry_id)
+                .copied()
+                .unwrap_or((entry.pid, entry.tid));
+            events.push(json!({
+                "ph": "X", "cat": "span", "name": span.rule,
+                "ts": span.start_epoch as i64 * 1000, "dur": span.duration_ms * 1000,
+                "pid": pid, "tid": tid, "args": { "key": span.key },
+            }));
+        }
+        if let Some(crash) = crashes.observe(device_id, entry) {
+            events.push(crash_event(&crash, ts));
+        }
+    }
+    if let (Some(crash), Some(ts)) = (crashes.flush(), entries.last().and_then(micros)) {
+        events.push(crash_event(&crash, ts));
+    }
+
+    for (pid, name) in processes {
+        events.push(json!({
+            "ph": "M", "name": "process_name", "pid": pid, "args": { "name": name },
+        }));
+    }
+    let trace = json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "metadata": { "device": device_id },
+    });
+    serde_json::to_string(&trace).map_err(|e| e.to_string())
+}
+
+/// Process-scoped instant at the crash, with the stack in its args
+fn crash_event(crash: &CrashReport, ts: i64) -> Value {
+    json!({
+        "ph": "i", "s": "p", "cat": "crash", "name": crash.exception,
+        "ts": ts, "pid": crash.pid, "tid": crash.pid,
+        "args": { "frames": crash.frames },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+    use crate::spans::SpanKey;
+
+    #[test]
+    fn test_render_events() {
+        let mut parser = LogParser::new();
+        let entries: Vec<LogEntry> = [
+            "12-04 10:30:45.000   100   101 I Sync: Begin sync",
+            "12-04 10:30:45.250   100   101 I Sync: Sync complete",
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: FATAL EXCEPTION: main",
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: java.lang.IllegalStateException: boom",
+            "12-04 10:30:46.000   100   100 E AndroidRuntime: \tat com.example.Foo.bar(Foo.kt:42)",
+        ]
+        .into_iter()
+        .map(|line| parser.parse_line(line).unwrap())
+        .collect();
+        let rules = vec![SpanRule {
+            name: "sync".to_string(),
+            start: "^Begin sync".to_string(),
+            end: "^Sync complete".to_string(),
+            key: SpanKey::Pid,
+        }];
+
+        let trace: Value =
+            serde_json::from_str(&render("emulator-5554", &entries, &rules).unwrap()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let of = |cat: &str| events.iter().filter(|e| e["cat"] == cat).collect::<Vec<_>>();
+        assert_eq!(events.iter().filter(|e| e["ph"] == "i" && e["s"] == "t").count(), 5);
+        assert_eq!(of("span")[0]["dur"], 250_000);
+        assert_eq!(of("span")[0]["tid"], 101);
+        assert_eq!(of("crash")[0]["name"], "java.lang.IllegalStateException: boom");
+    }
+
+    #[test]
+    fn test_only_flagged_markers() {
+        let mut parser = LogParser::new();
+        let logged = parser
+            .parse_line("12-04 10:30:47.000     0     0 I Marker: logged by the device")
+            .unwrap();
+        let mut marker = logged.clone();
+        marker.marker = true;
+
+        let trace: Value = serde_json::from_str(&render("dev", &[logged, marker], &[]).unwrap()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.iter().filter(|e| e["cat"] == "marker").count(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::parser::{LogEntry, LogLevel};
+
+/// Width of the right-aligned tag column, as in pidcat
+const TAG_WIDTH: usize = 23;
+
+const BLACK: u8 = 0;
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const YELLOW: u8 = 3;
+const BLUE: u8 = 4;
+const MAGENTA: u8 = 5;
+const CYAN: u8 = 6;
+const WHITE: u8 = 7;
+
+/// Colors handed out to tags in rotation
+const TAG_COLORS: [u8; 6] = [RED, GREEN, YELLOW, BLUE, MAGENTA, CYAN];
+
+/// Framework tags that are always drawn in white
+const KNOWN_TAGS: [&str; 8] = [
+    "dalvikvm",
+    "Process",
+    "ActivityManager",
+    "ActivityThread",
+    "AndroidRuntime",
+    "jdwp",
+    "StrictMode",
+    "DEBUG",
+];
+
+fn colorize(text: &str, fg: u8, bg: Option<u8>) -> String {
+    match bg {
+        Some(bg) => format!("\x1b[3{};4{}m{}\x1b[0m", fg, bg, text),
+        None => format!("\x1b[3{}m{}\x1b[0m", fg, text),
+    }
+}
+
+fn level_colors(level: LogLevel) -> (u8, u8) {
+    match level {
+        LogLevel::V => (WHITE, BLACK),
+        LogLevel::D => (BLACK, BLUE),
+        LogLevel::I => (BLACK, GREEN),
+        LogLevel::W => (BLACK, YELLOW),
+        LogLevel::E | LogLevel::A => (BLACK, RED),
+    }
+}
+
+/// Formats entries like pidcat: aligned tags, a boxed level, repeated tags blanked
+pub struct PidcatFormatter {
+    color: bool,
+    last_tag: Option<String>,
+    tag_colors: HashMap<String, u8>,
+}
+
+impl PidcatFormatter {
+    pub fn new(color: bool) -> Self {
+        PidcatFormatter {
+            color,
+            last_tag: None,
+            tag_colors: HashMap::new(),
+        }
+    }
+
+    fn tag_color(&mut self, tag: &str) -> u8 {
+        if KNOWN_TAGS.contains(&tag) {
+            return WHITE;
+        }
+        let next = TAG_COLORS[self.tag_colors.len() % TAG_COLORS.len()];
+        *self.tag_colors.entry(tag.to_string()).or_insert(next)
+    }
+
+    pub fn format_entry(&mut self, entry: &LogEntry) -> String {
+        let tag = entry.tag.trim();
+        // Consecutive lines from the same tag only show it once
+        let tag_column = if self.last_tag.as_deref() == Some(tag) {
+            " ".repeat(TAG_WIDTH)
+        } else {
+            let shown: String = tag.chars().take(TAG_WIDTH).collect();
+            let padded = format!("{:>width$}", shown, width = TAG_WIDTH);
+            if self.color {
+                let color = self.tag_color(tag);
+                colorize(&padded, color, None)
+            } else {
+                padded
+            }
+        };
+        self.last_tag = Some(tag.to_string());
+
+        let level = format!(" {:?} ", entry.level);
+        let level = if self.color {
+            let (fg, bg) = level_colors(entry.level);
+            colorize(&level, fg, Some(bg))
+        } else {
+            level
+        };
+
+        format!("{} {} {}", tag_column, level, entry.message)
+    }
+}
+
+/// Render entries, optionally keeping only one package, as pidcat-style text
+pub fn render(entries: &[LogEntry], package: Option<&str>, color: bool) -> String {
+    let mut formatter = PidcatFormatter::new(color);
+    let mut out = String::new();
+    for entry in entries {
+        if package.is_some() && entry.package_name.as_deref() != package {
+            continue;
+        }
+        out.push_str(&formatter.format_entry(entry));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_plain_layout() {
+        let mut parser = LogParser::new();
+        let mut entries = vec![
+            parser
+                .parse_line("12-04 10:30:45.123  1234  1234 D MainActivity: onCreate")
+                .unwrap(),
+            parser
+                .parse_line("12-04 10:30:45.124  1234  1234 D MainActivity: onStart")
+                .unwrap(),
+            parser
+                .parse_line("12-04 10:30:45.125  4321  4321 I Other: hello")
+                .unwrap(),
+        ];
+        entries[0].package_name = Some("com.example".to_string());
+        entries[1].package_name = Some("com.example".to_string());
+
+        let text = render(&entries, Some("com.example"), false);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], format!("{:>23}  D  onCreate", "MainActivity"));
+        assert_eq!(lines[1], format!("{}  D  onStart", " ".repeat(23)));
+    }
+
+    #[test]
+    fn test_colored_level_box() {
+        let mut parser = LogParser::new();
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 E AndroidRuntime: FATAL EXCEPTION: main")
+            .unwrap();
+        let line = PidcatFormatter::new(true).format_entry(&entry);
+        assert!(line.contains("\x1b[30;41m E \x1b[0m"));
+        assert!(line.contains("\x1b[37m"));
+    }
+}
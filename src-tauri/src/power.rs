@@ -0,0 +1,248 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::parser::LogEntry;
+
+/// Maximum number of completed power events kept per device
+const MAX_POWER_EVENTS: usize = 5000;
+
+/// Kind of power-related event extracted from logcat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PowerEventKind {
+    WakeLock,
+    Sleep,
+    Wake,
+}
+
+/// A structured power event with an optional duration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerEvent {
+    pub kind: PowerEventKind,
+    /// Wakelock tag, or the reason for sleep/wake transitions
+    pub tag: String,
+    pub flags: Option<String>,
+    pub uid: Option<u32>,
+    pub pid: Option<u32>,
+    #[serde(rename = "startTime")]
+    pub start_time: String,
+    #[serde(rename = "endTime")]
+    pub end_time: Option<String>,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: Option<u64>,
+    #[serde(rename = "entryId")]
+    pub entry_id: u64,
+}
+
+/// Total hold time for one wakelock tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WakeLockStat {
+    pub tag: String,
+    pub count: u32,
+    #[serde(rename = "totalMs")]
+    pub total_ms: u64,
+    #[serde(rename = "maxMs")]
+    pub max_ms: u64,
+}
+
+/// Summary returned by `get_power_events`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerSummary {
+    pub events: Vec<PowerEvent>,
+    /// Wakelocks acquired but not yet released
+    pub held: Vec<PowerEvent>,
+    /// Per-tag statistics, sorted by total hold time (descending)
+    pub wakelocks: Vec<WakeLockStat>,
+}
+
+/// "acquireWakeLockInternal: lock=123, flags=0x1, tag="Foo", ws=null, uid=10123, pid=4567"
+static ACQUIRE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"acquireWakeLockInternal: lock=(\w+), flags=(0x[0-9a-fA-F]+), tag="([^"]*)".*?uid=(\d+), pid=(\d+)"#,
+    )
+    .expect("Invalid wakelock acquire regex")
+});
+
+/// "releaseWakeLockInternal: lock=123 [Foo], flags=0x0"
+static RELEASE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"releaseWakeLockInternal: lock=(\w+) \[([^\]]*)\]")
+        .expect("Invalid wakelock release regex")
+});
+
+/// "Going to sleep due to power_button (uid 1000)..."
+static SLEEP_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Going to sleep due to (\S+)(?: \(uid (\d+)\))?")
+        .expect("Invalid sleep regex")
+});
+
+/// "Waking up from sleep (uid 1000 reason=...)" / "Waking up from Asleep (uid=1000, reason=WAKE_REASON_POWER_BUTTON, details=...)"
+static WAKE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Waking up from \w+ \(uid[ =](\d+)(?:,? reason=([\w.]+))?")
+        .expect("Invalid wake regex")
+});
+
+/// Tracks wakelock acquire/release pairs and screen transitions for one device
+#[derive(Debug, Default)]
+pub struct PowerTracker {
+    open: HashMap<String, (PowerEvent, Option<i64>)>,
+    events: VecDeque<PowerEvent>,
+}
+
+impl PowerTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a log entry into the tracker
+    pub fn observe(&mut self, entry: &LogEntry) {
+        if entry.tag != "PowerManagerService" {
+            return;
+        }
+        let message = entry.message.as_str();
+        let time = entry.date_time.clone().unwrap_or_else(|| entry.timestamp.clone());
+
+        if let Some(caps) = ACQUIRE_REGEX.captures(message) {
+            let event = PowerEvent {
+                kind: PowerEventKind::WakeLock,
+                tag: caps[3].to_string(),
+                flags: Some(caps[2].to_string()),
+                uid: caps[4].parse().ok(),
+                pid: caps[5].parse().ok(),
+                start_time: time,
+                end_time: None,
+                duration_ms: None,
+                entry_id: entry.id,
+            };
+            self.open
+                .insert(caps[1].to_string(), (event, entry.device_time_millis()));
+        } else if let Some(caps) = RELEASE_REGEX.captures(message) {
+            if let Some((mut event, start_ms)) = self.open.remove(&caps[1]) {
+                event.duration_ms = match (start_ms, entry.device_time_millis()) {
+                    (Some(start), Some(end)) if end >= start => Some((end - start) as u64),
+                    _ => None,
+                };
+                event.end_time = Some(time);
+                self.push(event);
+            }
+        } else if let Some(caps) = SLEEP_REGEX.captures(message) {
+            self.push(Self::transition(PowerEventKind::Sleep, &caps[1], caps.get(2), time, entry.id));
+        } else if let Some(caps) = WAKE_REGEX.captures(message) {
+            let reason = caps.get(2).map(|m| m.as_str()).unwrap_or("unknown");
+            self.push(Self::transition(PowerEventKind::Wake, reason, caps.get(1), time, entry.id));
+        }
+    }
+
+    fn transition(
+        kind: PowerEventKind,
+        reason: &str,
+        uid: Option<regex::Match>,
+        time: String,
+        entry_id: u64,
+    ) -> PowerEvent {
+        PowerEvent {
+            kind,
+            tag: reason.to_string(),
+            flags: None,
+            uid: uid.and_then(|m| m.as_str().parse().ok()),
+            pid: None,
+            start_time: time,
+            end_time: None,
+            duration_ms: None,
+            entry_id,
+        }
+    }
+
+    fn push(&mut self, event: PowerEvent) {
+        if self.events.len() >= MAX_POWER_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Build a summary of all tracked events
+    pub fn summary(&self) -> PowerSummary {
+        let mut stats: HashMap<&str, WakeLockStat> = HashMap::new();
+        for event in &self.events {
+            if event.kind != PowerEventKind::WakeLock {
+                continue;
+            }
+            let duration = event.duration_ms.unwrap_or(0);
+            let stat = stats.entry(&event.tag).or_insert_with(|| WakeLockStat {
+                tag: event.tag.clone(),
+                count: 0,
+                total_ms: 0,
+                max_ms: 0,
+            });
+            stat.count += 1;
+            stat.total_ms += duration;
+            stat.max_ms = stat.max_ms.max(duration);
+        }
+
+        let mut wakelocks: Vec<WakeLockStat> = stats.into_values().collect();
+        wakelocks.sort_by_key(|s| std::cmp::Reverse(s.total_ms));
+
+        let mut held: Vec<PowerEvent> = self.open.values().map(|(e, _)| e.clone()).collect();
+        held.sort_by_key(|e| e.entry_id);
+
+        PowerSummary {
+            events: self.events.iter().cloned().collect(),
+            held,
+            wakelocks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_wakelock_duration() {
+        let mut parser = LogParser::new();
+        let mut tracker = PowerTracker::new();
+        let lines = [
+            r#"12-04 10:30:45.000  1000  1200 D PowerManagerService: acquireWakeLockInternal: lock=42, flags=0x1, tag="SyncJob", ws=null, uid=10123, pid=4567"#,
+            "12-04 10:30:47.500  1000  1200 D PowerManagerService: releaseWakeLockInternal: lock=42 [SyncJob], flags=0x0",
+        ];
+        for line in lines {
+            tracker.observe(&parser.parse_line(line).unwrap());
+        }
+
+        let summary = tracker.summary();
+        assert_eq!(summary.events.len(), 1);
+        assert_eq!(summary.events[0].tag, "SyncJob");
+        assert_eq!(summary.events[0].uid, Some(10123));
+        assert_eq!(summary.events[0].duration_ms, Some(2500));
+        assert!(summary.held.is_empty());
+        assert_eq!(summary.wakelocks[0].total_ms, 2500);
+    }
+
+    #[test]
+    fn test_unreleased_wakelock_is_held() {
+        let mut parser = LogParser::new();
+        let mut tracker = PowerTracker::new();
+        let line = r#"12-04 10:30:45.000  1000  1200 D PowerManagerService: acquireWakeLockInternal: lock=7, flags=0x1, tag="Leak", ws=null, uid=10200, pid=900"#;
+        tracker.observe(&parser.parse_line(line).unwrap());
+
+        let summary = tracker.summary();
+        assert!(summary.events.is_empty());
+        assert_eq!(summary.held.len(), 1);
+        assert_eq!(summary.held[0].tag, "Leak");
+    }
+
+    #[test]
+    fn test_sleep_transition() {
+        let mut parser = LogParser::new();
+        let mut tracker = PowerTracker::new();
+        let line = "12-04 10:31:00.000  1000  1200 I PowerManagerService: Going to sleep due to power_button (uid 1000)...";
+        tracker.observe(&parser.parse_line(line).unwrap());
+
+        let summary = tracker.summary();
+        assert_eq!(summary.events[0].kind, PowerEventKind::Sleep);
+        assert_eq!(summary.events[0].tag, "power_button");
+        assert_eq!(summary.events[0].uid, Some(1000));
+    }
+}
@@ -0,0 +1,84 @@
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::settings;
+use crate::workspace::AlertRule;
+
+/// Capture started as soon as a matching device connects, e.g. on an
+/// unattended monitoring station
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LaunchProfile {
+    /// Device serial, or `any` for the first device that connects
+    pub device: String,
+    /// Filter query the frontend applies once the stream starts
+    pub filter: Option<String>,
+    /// Logcat buffers; empty uses `defaultBuffers`
+    pub buffers: Vec<String>,
+    /// File every entry is appended to; may contain `{device}` and `{time}`
+    pub sink_path: Option<String>,
+    /// Alert rules used instead of the active workspace's; empty keeps those
+    pub alert_rules: Vec<AlertRule>,
+}
+
+impl Default for LaunchProfile {
+    fn default() -> Self {
+        LaunchProfile {
+            device: "any".to_string(),
+            filter: None,
+            buffers: Vec::new(),
+            sink_path: None,
+            alert_rules: Vec::new(),
+        }
+    }
+}
+
+impl LaunchProfile {
+    pub fn matches(&self, device_id: &str) -> bool {
+        self.device == "any" || self.device == device_id
+    }
+}
+
+/// Profile given with `--profile`, taking precedence over the setting
+static CLI_PROFILE: OnceCell<LaunchProfile> = OnceCell::new();
+
+/// Read a profile file given on the command line
+pub fn load(path: &Path) -> Result<LaunchProfile, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read profile {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid profile {}: {}", path.display(), e))
+}
+
+pub fn set_cli_profile(profile: LaunchProfile) {
+    let _ = CLI_PROFILE.set(profile);
+}
+
+/// Profile in effect: the command line one, else `launchProfile` from settings
+pub fn active() -> Option<LaunchProfile> {
+    CLI_PROFILE
+        .get()
+        .cloned()
+        .or_else(|| settings::current().launch_profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_profile_uses_defaults() {
+        let profile: LaunchProfile =
+            serde_json::from_str(r#"{ "sinkPath": "/var/log/kiosk/{device}.log", "buffers": ["crash"] }"#)
+                .unwrap();
+        assert!(profile.matches("emulator-5554"));
+        assert_eq!(profile.buffers, vec!["crash"]);
+
+        let pinned = LaunchProfile {
+            device: "R58M123".to_string(),
+            ..Default::default()
+        };
+        assert!(pinned.matches("R58M123"));
+        assert!(!pinned.matches("emulator-5554"));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::parser::LogEntry;
+
+/// Bitmask of optional `LogEntry` fields to keep when emitting batches.
+/// Core fields (id, timestamp, pid, tid, level, tag, message) are always sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMask(u8);
+
+impl FieldMask {
+    pub const RAW: u8 = 1;
+    pub const DATE_TIME: u8 = 1 << 1;
+    pub const EPOCH: u8 = 1 << 2;
+    pub const PACKAGE_NAME: u8 = 1 << 3;
+    pub const PROCESS_NAME: u8 = 1 << 4;
+    pub const LINKS: u8 = 1 << 5;
+    pub const ALL: FieldMask = FieldMask(0x3F);
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        FieldMask(bits & Self::ALL.0)
+    }
+
+    /// Build a mask from frontend field names (camelCase, as serialized)
+    pub fn from_names(names: &[String]) -> Result<Self, String> {
+        let mut bits = 0;
+        for name in names {
+            bits |= match name.as_str() {
+                "raw" => Self::RAW,
+                "dateTime" => Self::DATE_TIME,
+                "epoch" => Self::EPOCH,
+                "packageName" => Self::PACKAGE_NAME,
+                "processName" => Self::PROCESS_NAME,
+                "links" => Self::LINKS,
+                "id" | "timestamp" | "pid" | "tid" | "level" | "tag" | "message" => 0,
+                _ => return Err(format!("Unknown entry field: {}", name)),
+            };
+        }
+        Ok(FieldMask(bits))
+    }
+
+    /// Drop the fields not in the mask
+    pub fn apply(self, entry: &mut LogEntry) {
+        if self.0 & Self::RAW == 0 {
+            entry.raw = None;
+        }
+        if self.0 & Self::DATE_TIME == 0 {
+            entry.date_time = None;
+        }
+        if self.0 & Self::EPOCH == 0 {
+            entry.epoch = None;
+        }
+        if self.0 & Self::PACKAGE_NAME == 0 {
+            entry.package_name = None;
+        }
+        if self.0 & Self::PROCESS_NAME == 0 {
+            entry.process_name = None;
+        }
+        if self.0 & Self::LINKS == 0 {
+            entry.links.clear();
+        }
+    }
+}
+
+impl Default for FieldMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_from_names() {
+        let mask = FieldMask::from_names(&["tag".to_string(), "packageName".to_string()]).unwrap();
+        assert_eq!(mask.bits(), FieldMask::PACKAGE_NAME);
+        assert!(FieldMask::from_names(&["bogus".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_apply_drops_unselected_fields() {
+        let mut parser = LogParser::new();
+        let mut entry = parser
+            .parse_line("12-04 10:30:45.123  1234  5678 D MainActivity: onCreate called")
+            .unwrap();
+        entry.package_name = Some("com.example".to_string());
+
+        FieldMask::from_bits(FieldMask::PACKAGE_NAME).apply(&mut entry);
+        assert!(entry.raw.is_none());
+        assert!(entry.date_time.is_none());
+        assert!(entry.epoch.is_none());
+        assert_eq!(entry.package_name.as_deref(), Some("com.example"));
+
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(!json.contains("raw"));
+        assert!(!json.contains("dateTime"));
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the per-tag log level props (`log.tag.<TAG>`)
+const LOG_TAG_PREFIX: &str = "log.tag.";
+
+/// Debugging-relevant system props that may be read and written
+const DEBUG_PROPS: &[(&str, &str)] = &[
+    ("log.tag", "Default minimum level for all tags (V, D, I, W, E, A, S)"),
+    ("persist.log.tag", "Default minimum level, kept across reboots"),
+    ("debug.hwui.profile", "Profile HWUI rendering (true, visual_bars)"),
+    ("debug.hwui.overdraw", "Show GPU overdraw (show, false)"),
+    ("debug.layout", "Show layout bounds (true, false)"),
+    ("debug.hwui.show_dirty_regions", "Flash view updates (true, false)"),
+    ("debug.atrace.tags.enableflags", "Enabled atrace categories (hex mask)"),
+];
+
+/// A curated prop and its current value on a device
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugProp {
+    pub key: String,
+    pub description: String,
+    /// Empty when unset
+    pub value: String,
+}
+
+/// Whether `key` is a curated prop or a `log.tag.<TAG>` level
+pub fn is_allowed(key: &str) -> bool {
+    let valid_tag = |tag: &str| {
+        !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+    };
+    DEBUG_PROPS.iter().any(|(k, _)| *k == key)
+        || key.strip_prefix(LOG_TAG_PREFIX).is_some_and(valid_tag)
+}
+
+/// Check a key and value before they reach `adb shell`, which joins its
+/// arguments into one shell command line
+pub fn validate(key: &str, value: &str) -> Result<(), String> {
+    if !is_allowed(key) {
+        return Err(format!("Prop {} is not in the debugging prop list", key));
+    }
+    if let Some(c) = value.chars().find(|c| !(c.is_ascii_alphanumeric() || "._-,:/".contains(*c))) {
+        return Err(format!("Invalid character {:?} in value for {}", c, key));
+    }
+    Ok(())
+}
+
+/// Developer-option switches that change what apps log or draw
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugToggle {
+    GpuOverdraw,
+    ProfileHwui,
+    LayoutBounds,
+    DontKeepActivities,
+}
+
+impl DebugToggle {
+    /// Name as shown in Developer options
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugToggle::GpuOverdraw => "Show GPU overdraw",
+            DebugToggle::ProfileHwui => "Profile HWUI rendering",
+            DebugToggle::LayoutBounds => "Show layout bounds",
+            DebugToggle::DontKeepActivities => "Don't keep activities",
+        }
+    }
+
+    /// Shell command switching the toggle. Prop changes are followed by the
+    /// SYSPROPS_TRANSACTION broadcast Developer options sends, so running apps
+    /// pick them up without a restart.
+    pub fn command(self, enabled: bool) -> String {
+        let setprop = |key: &str, on: &str| {
+            format!(
+                "setprop {} {} && service call activity 1599295570 > /dev/null",
+                key,
+                if enabled { on } else { "false" }
+            )
+        };
+        match self {
+            DebugToggle::GpuOverdraw => setprop("debug.hwui.overdraw", "show"),
+            DebugToggle::ProfileHwui => setprop("debug.hwui.profile", "visual_bars"),
+            DebugToggle::LayoutBounds => setprop("debug.layout", "true"),
+            DebugToggle::DontKeepActivities => format!(
+                "settings put global always_finish_activities {}",
+                u8::from(enabled)
+            ),
+        }
+    }
+}
+
+/// Curated props with their values from a `getprop` dump
+pub fn debug_props(getprop_output: &str) -> Vec<DebugProp> {
+    let values = parse_getprop(getprop_output);
+    let value = |key: &str| {
+        values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default()
+    };
+    let curated = DEBUG_PROPS.iter().map(|(key, description)| DebugProp {
+        key: key.to_string(),
+        description: description.to_string(),
+        value: value(key),
+    });
+    let tags = values
+        .iter()
+        .filter(|(key, _)| key.starts_with(LOG_TAG_PREFIX) && is_allowed(key))
+        .map(|(key, value)| DebugProp {
+            key: key.clone(),
+            description: format!("Minimum level for tag {}", &key[LOG_TAG_PREFIX.len()..]),
+            value: value.clone(),
+        });
+    curated.chain(tags).collect()
+}
+
+/// `[key]: [value]` lines of `getprop`
+fn parse_getprop(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once("]: [")?;
+            Some((
+                key.strip_prefix('[')?.to_string(),
+                value.strip_suffix(']')?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_keys_and_values() {
+        assert!(validate("debug.hwui.profile", "visual_bars").is_ok());
+        assert!(validate("log.tag.OkHttp", "V").is_ok());
+        assert!(validate("log.tag.OkHttp", "").is_ok());
+        assert!(validate("ro.debuggable", "1").is_err());
+        assert!(validate("log.tag.", "V").is_err());
+        assert!(validate("log.tag.X;reboot", "V").is_err());
+        assert!(validate("debug.layout", "true; reboot").is_err());
+    }
+
+    #[test]
+    fn test_debug_props_from_getprop() {
+        let output = "[debug.hwui.profile]: [visual_bars]\n\
+                      [log.tag.OkHttp]: [V]\n\
+                      [ro.build.type]: [user]\n";
+        let props = debug_props(output);
+        assert_eq!(props.len(), DEBUG_PROPS.len() + 1);
+        let value = |key: &str| props.iter().find(|p| p.key == key).map(|p| p.value.as_str());
+        assert_eq!(value("debug.hwui.profile"), Some("visual_bars"));
+        assert_eq!(value("debug.layout"), Some(""));
+        assert_eq!(value("log.tag.OkHttp"), Some("V"));
+        assert_eq!(value("ro.build.type"), None);
+    }
+
+    #[test]
+    fn test_toggle_commands() {
+        assert_eq!(
+            DebugToggle::GpuOverdraw.command(true),
+            "setprop debug.hwui.overdraw show && service call activity 1599295570 > /dev/null"
+        );
+        assert!(DebugToggle::ProfileHwui.command(false).starts_with("setprop debug.hwui.profile false"));
+        assert_eq!(
+            DebugToggle::DontKeepActivities.command(true),
+            "settings put global always_finish_activities 1"
+        );
+    }
+}
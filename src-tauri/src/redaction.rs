@@ -0,0 +1,153 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parser::LogEntry;
+
+/// A regex and what its matches are replaced with (`$1`-style groups allowed)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+impl RedactionRule {
+    fn new(name: &str, pattern: &str, replacement: &str) -> Self {
+        RedactionRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+}
+
+/// Rules enabled out of the box; users can edit or extend them in settings
+pub fn default_rules() -> Vec<RedactionRule> {
+    vec![
+        RedactionRule::new("email", r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+", "<email>"),
+        RedactionRule::new("jwt", r"eyJ[\w-]+\.[\w-]+\.[\w-]+", "<jwt>"),
+        RedactionRule::new(
+            "token",
+            r"(?i)\b(bearer\s+|token[=:]\s*|access_token[=:]\s*|api[_-]?key[=:]\s*)[\w\-.~+/]{8,}=*",
+            "${1}<token>",
+        ),
+        RedactionRule::new(
+            "phone",
+            r"\+\d{1,3}[\s-]?\d{2,4}[\s-]?\d{3,4}[\s-]?\d{3,4}\b|\b1[3-9]\d{9}\b",
+            "<phone>",
+        ),
+    ]
+}
+
+/// One redacted span, for previews
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionHit {
+    pub entry_id: u64,
+    pub rule: String,
+    pub original: String,
+}
+
+/// Compiled redaction rules
+pub struct Redactor {
+    rules: Vec<(String, Regex, String)>,
+}
+
+impl Redactor {
+    pub fn new(rules: &[RedactionRule]) -> Result<Self, String> {
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid pattern in redaction rule {}: {}", rule.name, e))?;
+            compiled.push((rule.name.clone(), regex, rule.replacement.clone()));
+        }
+        Ok(Redactor { rules: compiled })
+    }
+
+    /// Redact a line of text
+    pub fn redact(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for (_, regex, replacement) in &self.rules {
+            if regex.is_match(&out) {
+                out = regex.replace_all(&out, replacement.as_str()).into_owned();
+            }
+        }
+        out
+    }
+
+    /// Redact an entry's message and raw line in place, returning what was replaced
+    pub fn redact_entry(&self, entry: &mut LogEntry) -> Vec<RedactionHit> {
+        let mut hits = Vec::new();
+        for (name, regex, replacement) in &self.rules {
+            let before = hits.len();
+            for m in regex.find_iter(&entry.message) {
+                hits.push(RedactionHit {
+                    entry_id: entry.id,
+                    rule: name.clone(),
+                    original: m.as_str().to_string(),
+                });
+            }
+            if hits.len() > before {
+                entry.message = regex.replace_all(&entry.message, replacement.as_str()).into_owned();
+            }
+        }
+        if let Some(raw) = entry.raw.as_mut() {
+            *raw = self.redact(raw);
+        }
+        hits
+    }
+}
+
+/// Redacted entries plus everything that was replaced
+#[derive(Debug, Clone, Serialize)]
+pub struct RedactionPreview {
+    pub entries: Vec<LogEntry>,
+    pub hits: Vec<RedactionHit>,
+}
+
+/// Redact copies of `entries` for review before sharing
+pub fn preview(redactor: &Redactor, mut entries: Vec<LogEntry>) -> RedactionPreview {
+    let hits = entries
+        .iter_mut()
+        .flat_map(|entry| redactor.redact_entry(entry))
+        .collect();
+    RedactionPreview { entries, hits }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_default_rules() {
+        let redactor = Redactor::new(&default_rules()).unwrap();
+        assert_eq!(
+            redactor.redact("login ok for jane.doe@example.com"),
+            "login ok for <email>"
+        );
+        assert_eq!(
+            redactor.redact("Authorization: Bearer abcdEFGH1234567890"),
+            "Authorization: Bearer <token>"
+        );
+        assert_eq!(redactor.redact("sms to 13812345678 sent"), "sms to <phone> sent");
+        assert_eq!(redactor.redact("pid 12345 started"), "pid 12345 started");
+    }
+
+    #[test]
+    fn test_preview_lists_hits() {
+        let mut parser = LogParser::new();
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 I Auth: user a@b.io token=s3cr3tvalue99")
+            .unwrap();
+        let redactor = Redactor::new(&default_rules()).unwrap();
+        let result = preview(&redactor, vec![entry]);
+
+        assert_eq!(result.entries[0].message, "user <email> token=<token>");
+        assert!(!result.entries[0].raw.as_deref().unwrap().contains("a@b.io"));
+        let rules: Vec<&str> = result.hits.iter().map(|h| h.rule.as_str()).collect();
+        assert_eq!(rules, vec!["email", "token"]);
+        assert_eq!(result.hits[0].original, "a@b.io");
+    }
+}
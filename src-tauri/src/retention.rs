@@ -0,0 +1,239 @@
+use log::{error, info};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+use tokio::time::interval;
+
+use crate::sessions::SESSIONS_DIR;
+use crate::settings;
+
+/// Marker screenshots directory inside the app config directory
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+
+/// How often the retention policy is applied (also once at startup)
+const PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Directories under the config directory that the retention policy manages
+const MANAGED_DIRS: &[&str] = &[SESSIONS_DIR, SCREENSHOTS_DIR];
+
+/// Files removed together: a session's `.log` and `.json`, or one screenshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredItem {
+    pub dir: &'static str,
+    pub name: String,
+    pub files: Vec<PathBuf>,
+    pub bytes: u64,
+    pub modified: SystemTime,
+}
+
+/// Disk usage of one managed directory
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsage {
+    pub name: String,
+    pub items: usize,
+    pub bytes: u64,
+}
+
+/// Result of `get_storage_usage`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    pub dirs: Vec<DirUsage>,
+    pub total_bytes: u64,
+    /// `maxStorageMb` in bytes, 0 when unlimited
+    pub limit_bytes: u64,
+    pub retention_days: u32,
+}
+
+/// Payload of the `storage-pruned` event
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    /// "sessions/<id>" or "screenshots/<file>"
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Collect the items of the managed directories
+pub fn scan(config_dir: &Path) -> Vec<StoredItem> {
+    let mut items = Vec::new();
+    for dir in MANAGED_DIRS {
+        let Ok(read) = std::fs::read_dir(config_dir.join(dir)) else {
+            continue;
+        };
+        let mut grouped: HashMap<String, StoredItem> = HashMap::new();
+        for entry in read.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if !meta.is_file() {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let item = grouped.entry(stem.clone()).or_insert_with(|| StoredItem {
+                dir,
+                name: stem,
+                files: Vec::new(),
+                bytes: 0,
+                modified,
+            });
+            item.files.push(path);
+            item.bytes += meta.len();
+            item.modified = item.modified.max(modified);
+        }
+        items.extend(grouped.into_values());
+    }
+    items
+}
+
+/// Disk usage per managed directory
+pub fn usage(items: &[StoredItem], limit_bytes: u64, retention_days: u32) -> StorageUsage {
+    let dirs: Vec<DirUsage> = MANAGED_DIRS
+        .iter()
+        .map(|dir| {
+            let in_dir = items.iter().filter(|i| i.dir == *dir);
+            DirUsage {
+                name: dir.to_string(),
+                items: in_dir.clone().count(),
+                bytes: in_dir.map(|i| i.bytes).sum(),
+            }
+        })
+        .collect();
+    StorageUsage {
+        total_bytes: dirs.iter().map(|d| d.bytes).sum(),
+        dirs,
+        limit_bytes,
+        retention_days,
+    }
+}
+
+/// Items to delete: everything older than `max_age`, then the oldest
+/// remaining ones until the total fits in `limit_bytes` (0 = unlimited)
+pub fn plan(
+    mut items: Vec<StoredItem>,
+    now: SystemTime,
+    max_age: Option<Duration>,
+    limit_bytes: u64,
+) -> Vec<StoredItem> {
+    items.sort_by_key(|i| i.modified);
+    let mut total: u64 = items.iter().map(|i| i.bytes).sum();
+    let mut doomed = Vec::new();
+    for item in items {
+        let expired = max_age.is_some_and(|max_age| {
+            now.duration_since(item.modified)
+                .is_ok_and(|age| age > max_age)
+        });
+        let over_limit = limit_bytes > 0 && total > limit_bytes;
+        if expired || over_limit {
+            total -= item.bytes;
+            doomed.push(item);
+        }
+    }
+    doomed
+}
+
+/// Delete the planned items, skipping files that vanished in the meantime
+pub fn prune(items: Vec<StoredItem>) -> PruneReport {
+    let mut report = PruneReport::default();
+    for item in items {
+        let mut removed = false;
+        for file in &item.files {
+            removed |= std::fs::remove_file(file).is_ok();
+        }
+        if removed {
+            report.removed.push(format!("{}/{}", item.dir, item.name));
+            report.freed_bytes += item.bytes;
+        }
+    }
+    report
+}
+
+/// `maxStorageMb` in bytes
+pub fn limit_bytes(max_storage_mb: u64) -> u64 {
+    max_storage_mb.saturating_mul(1024 * 1024)
+}
+
+/// Apply the retention settings (`retentionDays` 0 keeps files forever)
+pub fn enforce(config_dir: &Path) -> PruneReport {
+    let config = settings::current();
+    let max_age = (config.retention_days > 0)
+        .then(|| Duration::from_secs(u64::from(config.retention_days) * 86_400));
+    let doomed = plan(
+        scan(config_dir),
+        SystemTime::now(),
+        max_age,
+        limit_bytes(config.max_storage_mb),
+    );
+    prune(doomed)
+}
+
+/// Apply the retention settings now and periodically, emitting `storage-pruned`
+/// whenever files were deleted
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(PRUNE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(dir) = settings::config_dir() else {
+                continue;
+            };
+            let report = match tokio::task::spawn_blocking(move || enforce(&dir)).await {
+                Ok(report) => report,
+                Err(e) => {
+                    error!("Retention task failed: {}", e);
+                    continue;
+                }
+            };
+            if report.removed.is_empty() {
+                continue;
+            }
+            info!("Pruned {} stored items ({} bytes)", report.removed.len(), report.freed_bytes);
+            if let Err(e) = app.emit("storage-pruned", &report) {
+                error!("Failed to emit storage-pruned: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, bytes: u64, age_days: u64, now: SystemTime) -> StoredItem {
+        StoredItem {
+            dir: SESSIONS_DIR,
+            name: name.to_string(),
+            files: Vec::new(),
+            bytes,
+            modified: now - Duration::from_secs(age_days * 86_400),
+        }
+    }
+
+    #[test]
+    fn test_plan_age_then_size() {
+        let now = SystemTime::now();
+        let items = vec![
+            item("recent", 300, 1, now),
+            item("ancient", 100, 30, now),
+            item("older", 500, 3, now),
+            item("newest", 200, 0, now),
+        ];
+        let week = Some(Duration::from_secs(7 * 86_400));
+
+        let names = |doomed: Vec<StoredItem>| -> Vec<String> {
+            doomed.into_iter().map(|i| i.name).collect()
+        };
+        assert_eq!(names(plan(items.clone(), now, week, 0)), vec!["ancient"]);
+        // 1100 bytes total; dropping ancient and older leaves 500
+        assert_eq!(names(plan(items.clone(), now, week, 600)), vec!["ancient", "older"]);
+        assert!(plan(items, now, None, 0).is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::parser::{LogEntry, LogLevel};
+
+/// Bidi embeddings, overrides and isolates, which reorder the text around
+/// them ("Trojan Source")
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+fn needs_cleaning(text: &str) -> bool {
+    text.chars()
+        .any(|c| (c.is_control() && c != '\t') || is_bidi_control(c))
+}
+
+/// Consume the rest of an escape sequence whose ESC was just read: CSI
+/// (`ESC [ params final`), OSC/DCS strings (up to BEL or `ESC \`), or a
+/// two-character escape. Returns a CSI's parameters and final character.
+fn read_escape(chars: &mut Peekable<Chars>) -> Option<(String, char)> {
+    match chars.next() {
+        Some('[') => read_csi(chars),
+        Some(']' | 'P' | '^' | '_') => {
+            while let Some(c) = chars.next() {
+                if c == '\u{7}' || (c == '\u{1B}' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+fn read_csi(chars: &mut Peekable<Chars>) -> Option<(String, char)> {
+    let mut params = String::new();
+    for c in chars.by_ref() {
+        if ('\u{40}'..='\u{7E}').contains(&c) {
+            return Some((params, c));
+        }
+        params.push(c);
+    }
+    None
+}
+
+/// Text safe to display and export: ANSI escape sequences and bidi controls
+/// removed, other control characters (except tab) shown as their Unicode
+/// control pictures, e.g. BEL as `␇`
+pub fn clean(text: &str) -> Cow<'_, str> {
+    if !needs_cleaning(text) {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1B}' => {
+                read_escape(&mut chars);
+            }
+            // C1 CSI, the single-character form of `ESC [`
+            '\u{9B}' => {
+                read_csi(&mut chars);
+            }
+            '\t' => out.push(c),
+            c if is_bidi_control(c) => {}
+            '\u{0}'..='\u{1F}' => out.push(char::from_u32(0x2400 + c as u32).unwrap_or('\u{FFFD}')),
+            '\u{7F}' => out.push('\u{2421}'),
+            c if c.is_control() => out.push('\u{FFFD}'),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Level of a `-v color` SGR sequence: logcat's 256-color foregrounds
+/// (red 196, orange 166, green 40, blue 75, white 231) or the basic colors
+/// other tools print
+fn sgr_level(params: &str) -> Option<LogLevel> {
+    let codes: Vec<&str> = params.split(';').collect();
+    let color = match codes.iter().position(|code| *code == "38") {
+        Some(i) if codes.get(i + 1) == Some(&"5") => codes.get(i + 2)?,
+        _ => codes.iter().rev().find(|code| code.len() == 2 && (code.starts_with('3') || code.starts_with('9')))?,
+    };
+    match *color {
+        "196" | "31" | "91" => Some(LogLevel::E),
+        "166" | "33" | "93" => Some(LogLevel::W),
+        "40" | "32" | "92" => Some(LogLevel::I),
+        "75" | "34" | "94" => Some(LogLevel::D),
+        "231" | "37" | "97" => Some(LogLevel::V),
+        _ => None,
+    }
+}
+
+/// Line with ANSI escape sequences removed, as printed by `logcat -v color`
+/// or colorizing pipes, plus the level implied by its first color
+pub fn strip_color(line: &str) -> (Cow<'_, str>, Option<LogLevel>) {
+    if !line.contains('\u{1B}') {
+        return (Cow::Borrowed(line), None);
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut level = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1B}' {
+            out.push(c);
+            continue;
+        }
+        if let Some((params, 'm')) = read_escape(&mut chars) {
+            level = level.or_else(|| sgr_level(&params));
+        }
+    }
+    (Cow::Owned(out), level)
+}
+
+/// Clean an entry's tag and message in place. The line as printed stays in
+/// `raw`, and `sanitized` is set when anything changed.
+pub fn apply(entry: &mut LogEntry) {
+    let tag = clean(&entry.tag);
+    let message = clean(&entry.message);
+    if matches!((&tag, &message), (Cow::Borrowed(_), Cow::Borrowed(_))) {
+        return;
+    }
+    let (tag, message) = (tag.into_owned(), message.into_owned());
+    if entry.raw.is_none() {
+        entry.raw = Some(entry.to_line());
+    }
+    entry.tag = tag;
+    entry.message = message;
+    entry.sanitized = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_clean() {
+        assert!(matches!(clean("plain\ttext ünïcode"), Cow::Borrowed(_)));
+        assert_eq!(clean("\u{1B}[31mred\u{1B}[0m"), "red");
+        assert_eq!(clean("\u{1B}]0;pwned\u{7}title"), "title");
+        assert_eq!(clean("\u{1B}]8;;http://x\u{1B}\\link"), "link");
+        assert_eq!(clean("bell\u{7} cr\r nul\u{0}"), "bell␇ cr␍ nul␀");
+        assert_eq!(clean("user \u{202E}gnp.exe\u{202C}"), "user gnp.exe");
+        assert_eq!(clean("del\u{7F} c1\u{85}"), "del␡ c1\u{FFFD}");
+    }
+
+    #[test]
+    fn test_parse_keeps_raw_original() {
+        let mut parser = LogParser::new();
+        let line = "12-04 10:30:45.123  1234  1234 I Pay\u{202E}: \u{1B}[2Jcleared\u{1B}[H screen";
+        let entry = parser.parse_line(line).unwrap();
+        assert_eq!(entry.tag, "Pay");
+        assert_eq!(entry.message, "cleared screen");
+        assert!(entry.sanitized);
+        assert_eq!(entry.raw.as_deref(), Some(line));
+        // Exports use the cleaned fields, not the raw line
+        assert!(entry.to_line().ends_with("I Pay: cleared screen"));
+
+        let plain = parser.parse_line("12-04 10:30:45.123  1234  1234 I Pay: ok").unwrap();
+        assert!(!plain.sanitized);
+    }
+
+    #[test]
+    fn test_color_lines() {
+        let (line, level) = strip_color("\u{1B}[38;5;166m12-04 10:30:45.123  1234  1234 W Net: slow\u{1B}[0m");
+        assert_eq!(line, "12-04 10:30:45.123  1234  1234 W Net: slow");
+        assert_eq!(level, Some(LogLevel::W));
+        assert_eq!(strip_color("\u{1B}[1;31mE/Tag( 12): boom\u{1B}[0m").1, Some(LogLevel::E));
+        assert!(matches!(strip_color("plain").0, Cow::Borrowed(_)));
+
+        let mut parser = LogParser::new();
+        let colored = "\u{1B}[38;5;196m12-04 10:30:45.123  1234  1234 E Pay: declined\u{1B}[0m";
+        let entry = parser.parse_line(colored).unwrap();
+        assert_eq!((entry.level, entry.tag.as_str(), entry.message.as_str()), (LogLevel::E, "Pay", "declined"));
+        assert_eq!(entry.color_level, Some(LogLevel::E));
+        assert!(entry.sanitized);
+        assert_eq!(entry.raw.as_deref(), Some(colored));
+    }
+}
@@ -0,0 +1,165 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+use crate::palette;
+use crate::parser::LogEntry;
+
+/// Templates tracked per device; new ones are ignored beyond this
+const MAX_TEMPLATES: usize = 2000;
+
+/// Values kept per template
+const MAX_POINTS: usize = 500;
+
+/// A number standing alone or with a short unit (`47`, `0.5`, `250ms`, `80%`),
+/// but not inside identifiers like `v2` or `0x1f`
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\d+(?:\.\d+)?)([a-zA-Z%]{0,3})\b").expect("Invalid number regex")
+});
+
+/// One message of a template: its numbers in order
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesPoint {
+    pub entry_id: u64,
+    pub epoch: Option<u64>,
+    pub values: Vec<f64>,
+}
+
+/// Messages sharing a tag and text apart from their numbers, e.g.
+/// `Battery level: {}`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageTemplate {
+    /// Stable across sessions and devices; hex, as JavaScript numbers can't hold 64 bits
+    pub id: String,
+    pub tag: String,
+    /// Message with each number replaced by `{}`
+    pub template: String,
+    pub count: u64,
+}
+
+/// A template's recent values, returned by `get_message_series`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSeries {
+    #[serde(flatten)]
+    pub template: MessageTemplate,
+    pub points: Vec<SeriesPoint>,
+}
+
+/// Template and numbers of a message; `None` when it has no numbers
+pub fn extract(message: &str) -> Option<(String, Vec<f64>)> {
+    let mut template = String::with_capacity(message.len());
+    let mut values = Vec::new();
+    let mut last = 0;
+    for caps in NUMBER_REGEX.captures_iter(message) {
+        let number = caps.get(1).expect("group 1 always matches");
+        let Ok(value) = number.as_str().parse() else {
+            continue;
+        };
+        template.push_str(&message[last..number.start()]);
+        template.push_str("{}");
+        last = number.end();
+        values.push(value);
+    }
+    if values.is_empty() {
+        return None;
+    }
+    template.push_str(&message[last..]);
+    Some((template, values))
+}
+
+pub fn template_id(tag: &str, template: &str) -> String {
+    format!("{:016x}", palette::fnv1a(&format!("{}\0{}", tag, template)))
+}
+
+/// Value series of one device's messages, keyed by template ID
+#[derive(Default)]
+pub struct SeriesStore {
+    series: HashMap<String, (MessageTemplate, VecDeque<SeriesPoint>)>,
+}
+
+impl SeriesStore {
+    pub fn observe(&mut self, entry: &LogEntry) {
+        let Some((template, values)) = extract(&entry.message) else {
+            return;
+        };
+        let id = template_id(&entry.tag, &template);
+        if !self.series.contains_key(&id) && self.series.len() >= MAX_TEMPLATES {
+            return;
+        }
+        let (info, points) = self.series.entry(id.clone()).or_insert_with(|| {
+            let info = MessageTemplate {
+                id,
+                tag: entry.tag.clone(),
+                template,
+                count: 0,
+            };
+            (info, VecDeque::new())
+        });
+        info.count += 1;
+        if points.len() == MAX_POINTS {
+            points.pop_front();
+        }
+        points.push_back(SeriesPoint {
+            entry_id: entry.id,
+            epoch: entry.epoch,
+            values,
+        });
+    }
+
+    /// Templates seen at least twice, most frequent first
+    pub fn templates(&self, limit: usize) -> Vec<MessageTemplate> {
+        let mut templates: Vec<MessageTemplate> = self
+            .series
+            .values()
+            .filter(|(info, _)| info.count > 1)
+            .map(|(info, _)| info.clone())
+            .collect();
+        templates.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.id.cmp(&b.id)));
+        templates.truncate(limit);
+        templates
+    }
+
+    pub fn series(&self, template_id: &str) -> Option<MessageSeries> {
+        self.series.get(template_id).map(|(info, points)| MessageSeries {
+            template: info.clone(),
+            points: points.iter().cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_extract_and_series() {
+        assert_eq!(
+            extract("Battery level: 47, temp 31.5C, took 250ms"),
+            Some(("Battery level: {}, temp {}C, took {}ms".to_string(), vec![47.0, 31.5, 250.0]))
+        );
+        assert_eq!(extract("Using codec v2 at 0x1f"), None);
+
+        let mut store = SeriesStore::default();
+        let mut parser = LogParser::new();
+        for line in [
+            "12-04 10:30:45.000   100   100 I Battery: Battery level: 47",
+            "12-04 10:30:46.000   100   100 I Battery: Battery level: 48",
+            "12-04 10:30:47.000   100   100 I Other: Battery level: 49",
+            "12-04 10:30:48.000   100   100 I Battery: charging",
+        ] {
+            store.observe(&parser.parse_line(line).unwrap());
+        }
+        let templates = store.templates(10);
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].template, "Battery level: {}");
+        assert_eq!(templates[0].id, template_id("Battery", "Battery level: {}"));
+        let series = store.series(&templates[0].id).unwrap();
+        let values: Vec<f64> = series.points.iter().map(|p| p.values[0]).collect();
+        assert_eq!(values, vec![47.0, 48.0]);
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::parser::LogEntry;
+use crate::redaction::Redactor;
+
+/// Session directory name inside the app config directory
+pub const SESSIONS_DIR: &str = "sessions";
+
+/// User-supplied context attached to a capture
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionMetadata {
+    pub build: Option<String>,
+    pub tester: Option<String>,
+    pub ticket: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// A saved capture: `<id>.log` plus this record as `<id>.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub id: String,
+    pub device_id: String,
+    /// RFC 3339 time the session was saved
+    pub saved_at: String,
+    pub entry_count: usize,
+    /// Path of the log file, readable with `parse_log_file`
+    pub log_path: String,
+    pub metadata: SessionMetadata,
+    /// IDs of the entries pinned when the session was saved
+    #[serde(default)]
+    pub pinned: Vec<u64>,
+}
+
+/// Criteria for `list_sessions`; empty fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionFilter {
+    /// Case-insensitive text matched against the metadata and device ID
+    pub text: Option<String>,
+    /// Sessions must carry all of these tags
+    pub tags: Vec<String>,
+    pub device_id: Option<String>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &SessionInfo) -> bool {
+        let meta = &session.metadata;
+        let text_ok = self.text.as_deref().map_or(true, |text| {
+            let needle = text.to_lowercase();
+            [
+                Some(session.device_id.as_str()),
+                meta.build.as_deref(),
+                meta.tester.as_deref(),
+                meta.ticket.as_deref(),
+                meta.note.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .chain(meta.tags.iter().map(String::as_str))
+            .any(|field| field.to_lowercase().contains(&needle))
+        });
+        let tags_ok = self
+            .tags
+            .iter()
+            .all(|tag| meta.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        let device_ok = self
+            .device_id
+            .as_deref()
+            .map_or(true, |id| session.device_id == id);
+        text_ok && tags_ok && device_ok
+    }
+}
+
+/// Session ID from the save time and device, safe as a file name
+fn session_id(device_id: &str, now: chrono::DateTime<chrono::Local>) -> String {
+    let device: String = device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{}-{}", now.format("%Y%m%d-%H%M%S%3f"), device)
+}
+
+/// Write a session's log file and metadata record. Pinned entries missing
+/// from `entries` (hidden by filters) are merged in, and lines are written in
+/// canonical (epoch, device, seq) order.
+pub fn save(
+    dir: &Path,
+    device_id: &str,
+    metadata: SessionMetadata,
+    mut entries: Vec<LogEntry>,
+    pins: Vec<LogEntry>,
+    redactor: Option<&Redactor>,
+) -> Result<SessionInfo, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create sessions dir: {}", e))?;
+    let now = chrono::Local::now();
+    let id = session_id(device_id, now);
+
+    let pinned: Vec<u64> = pins.iter().map(|entry| entry.id).collect();
+    let missing: Vec<LogEntry> = pins
+        .into_iter()
+        .filter(|pin| !entries.iter().any(|entry| entry.id == pin.id))
+        .collect();
+    entries.extend(missing);
+    entries.sort_by(|a, b| a.sort_key(device_id).cmp(&b.sort_key(device_id)));
+
+    let mut text = String::new();
+    for entry in &entries {
+        let line = entry.to_line();
+        match redactor {
+            Some(r) => text.push_str(&r.redact(&line)),
+            None => text.push_str(&line),
+        }
+        text.push('\n');
+    }
+    let log_path = dir.join(format!("{}.log", id));
+    std::fs::write(&log_path, text).map_err(|e| format!("Failed to write session: {}", e))?;
+
+    let info = SessionInfo {
+        id: id.clone(),
+        device_id: device_id.to_string(),
+        saved_at: now.to_rfc3339(),
+        entry_count: entries.len(),
+        log_path: log_path.display().to_string(),
+        metadata,
+        pinned,
+    };
+    let record = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(format!("{}.json", id)), record)
+        .map_err(|e| format!("Failed to write session metadata: {}", e))?;
+    Ok(info)
+}
+
+/// Saved sessions matching `filter`, newest first. Unreadable records are skipped.
+pub fn list(dir: &Path, filter: &SessionFilter) -> Result<Vec<SessionInfo>, String> {
+    let read = match std::fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read sessions dir: {}", e)),
+    };
+
+    let mut sessions: Vec<SessionInfo> = read
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|text| serde_json::from_str::<SessionInfo>(&text).ok())
+        .filter(|session| filter.matches(session))
+        .collect();
+    // IDs start with the save time
+    sessions.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_save_and_list() {
+        let dir = std::env::temp_dir().join(format!("sessions-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut parser = LogParser::new();
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  1234  1234 I Checkout: order placed")
+            .unwrap();
+        let pin = parser
+            .parse_line("12-04 10:30:45.456  1234  1234 W Checkout: retrying payment")
+            .unwrap();
+        let metadata = SessionMetadata {
+            build: Some("2.4.0-rc1".to_string()),
+            ticket: Some("PAY-812".to_string()),
+            tags: vec!["checkout".to_string(), "regression".to_string()],
+            ..Default::default()
+        };
+        let saved = save(&dir, "emulator-5554", metadata, vec![entry], vec![pin], None).unwrap();
+        let log = std::fs::read_to_string(&saved.log_path).unwrap();
+
+        let by_ticket = list(&dir, &SessionFilter {
+            text: Some("pay-812".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let by_tag = list(&dir, &SessionFilter {
+            tags: vec!["smoke".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(log.contains("Checkout: order placed"));
+        assert!(log.contains("retrying payment"));
+        assert_eq!(by_ticket.len(), 1);
+        assert_eq!(by_ticket[0].entry_count, 2);
+        assert_eq!(by_ticket[0].pinned, vec![saved.pinned[0]]);
+        assert!(by_tag.is_empty());
+    }
+}
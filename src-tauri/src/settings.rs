@@ -0,0 +1,254 @@
+use log::{error, info};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::correlation;
+use crate::fold::{self, FoldRule};
+use crate::noise::{self, QuickToggle};
+use crate::profile::LaunchProfile;
+use crate::redaction::{self, RedactionRule};
+use crate::spans::SpanRule;
+use crate::template::ParserTemplate;
+use crate::timezone::DisplayTimezone;
+
+/// Settings file name inside the app config directory
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Named set of devices for fleet actions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceGroup {
+    pub name: String,
+    /// Device serials in the group
+    pub devices: Vec<String>,
+}
+
+/// Backend settings persisted as JSON in the app config directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    /// adb executable, either a name on PATH or an absolute path
+    pub adb_path: String,
+    /// Capacity of the reader -> forwarder channel
+    pub channel_capacity: usize,
+    /// Maximum log lines the frontend keeps (hint for the UI)
+    pub max_log_lines: usize,
+    /// Recent entries per device kept in the backend for filter previews
+    pub history_lines: usize,
+    /// Emit a batch once it holds this many entries
+    pub batch_size: usize,
+    /// Emit a pending batch after this many milliseconds
+    pub batch_interval_ms: u64,
+    /// Logcat buffers passed as `-b` when streaming. Activity create/resume/
+    /// pause/stop/destroy events are only logged to `events`; without it the
+    /// lifecycle timeline has just START and Displayed lines.
+    pub default_buffers: Vec<String>,
+    /// Theme hint for the UI: "light", "dark" or "system"
+    pub theme: String,
+    /// Days to keep saved sessions and marker screenshots (0 keeps them forever)
+    pub retention_days: u32,
+    /// Disk budget for saved sessions and screenshots in MiB (0 = unlimited)
+    pub max_storage_mb: u64,
+    /// Name of the workspace loaded on startup
+    pub active_workspace: Option<String>,
+    /// Device serial to start streaming automatically when it connects
+    pub pinned_device: Option<String>,
+    /// Package to focus on when the pinned device auto-starts
+    pub pinned_package: Option<String>,
+    /// Filter query applied on auto-start (defaults to `package:<pinnedPackage>`)
+    pub pinned_filter: Option<String>,
+    /// Start streaming a device as soon as its USB debugging prompt is accepted
+    pub stream_on_authorize: bool,
+    /// Device groups, e.g. "Pixel farm"
+    pub device_groups: Vec<DeviceGroup>,
+    /// User-defined line formats for custom sources and imported files
+    pub parser_templates: Vec<ParserTemplate>,
+    /// Scrub exported files with `redaction_rules`
+    pub redact_exports: bool,
+    /// Regex replacements applied when exporting or sharing logs
+    pub redaction_rules: Vec<RedactionRule>,
+    /// Hide the main window to the tray on close so capture keeps running
+    pub close_to_tray: bool,
+    /// Stream lines no logcat format matches as info entries instead of dropping them
+    pub emit_unparsed: bool,
+    /// Tag of entries created from unparsed lines
+    pub unparsed_tag: String,
+    /// Noise exclusions stacked on the main filter; curated ones plus user additions
+    pub quick_toggles: Vec<QuickToggle>,
+    /// Timezone of entry times returned by history queries and written to exports
+    pub display_timezone: DisplayTimezone,
+    /// Regexes whose first capture group is an entry's correlation ID
+    pub correlation_patterns: Vec<String>,
+    /// Start/end line pairs measured as spans
+    pub span_rules: Vec<SpanRule>,
+    /// Editor launched by `open_in_editor`, with `{file}` and `{line}` placeholders
+    pub editor_command: Option<String>,
+    /// Project directories searched for the source files of stack frames
+    pub project_roots: Vec<String>,
+    /// Seconds a non-streaming adb command may run before it is killed
+    pub adb_timeout_secs: u64,
+    /// Multi-line blocks (stack traces) whose entries share a `groupId`
+    pub fold_rules: Vec<FoldRule>,
+    /// Seconds between thermal status samples of a streaming device (0 = off)
+    pub thermal_poll_secs: u64,
+    /// Capture started automatically on launch; `--profile <file>` overrides it
+    pub launch_profile: Option<LaunchProfile>,
+    /// Crashes of one package within `crashLoopWindowMins` that count as a
+    /// crash loop and trigger a capture (0 = off)
+    pub crash_loop_threshold: u32,
+    pub crash_loop_window_mins: u64,
+    /// Include an `adb bugreport` in crash loop captures
+    pub crash_loop_bugreport: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            adb_path: "adb".to_string(),
+            channel_capacity: 1000,
+            max_log_lines: 100_000,
+            history_lines: 20_000,
+            batch_size: 50,
+            batch_interval_ms: 100,
+            default_buffers: vec![
+                "main".to_string(),
+                "system".to_string(),
+                "crash".to_string(),
+            ],
+            theme: "dark".to_string(),
+            retention_days: 7,
+            max_storage_mb: 2048,
+            active_workspace: None,
+            pinned_device: None,
+            pinned_package: None,
+            pinned_filter: None,
+            stream_on_authorize: false,
+            device_groups: Vec::new(),
+            parser_templates: Vec::new(),
+            redact_exports: false,
+            redaction_rules: redaction::default_rules(),
+            close_to_tray: false,
+            emit_unparsed: false,
+            unparsed_tag: "RAW".to_string(),
+            quick_toggles: noise::default_toggles(),
+            display_timezone: DisplayTimezone::Device,
+            correlation_patterns: vec![correlation::DEFAULT_PATTERN.to_string()],
+            span_rules: Vec::new(),
+            editor_command: None,
+            project_roots: Vec::new(),
+            adb_timeout_secs: 30,
+            fold_rules: fold::default_rules(),
+            thermal_poll_secs: 15,
+            launch_profile: None,
+            crash_loop_threshold: 0,
+            crash_loop_window_mins: 5,
+            crash_loop_bugreport: false,
+        }
+    }
+}
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(|| RwLock::new(Settings::default()));
+static SETTINGS_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Load settings from the config directory; falls back to defaults on any error
+pub fn init(config_dir: PathBuf) {
+    let path = config_dir.join(SETTINGS_FILE);
+    let loaded = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| match serde_json::from_str::<Settings>(&text) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                error!("Invalid settings file {}: {}", path.display(), e);
+                None
+            }
+        });
+
+    if let Some(settings) = loaded {
+        info!("Loaded settings from {}", path.display());
+        if let Ok(mut current) = SETTINGS.write() {
+            *current = settings;
+        }
+    }
+    let _ = SETTINGS_PATH.set(path);
+}
+
+/// Directory holding the settings file and other persisted data
+pub fn config_dir() -> Option<PathBuf> {
+    SETTINGS_PATH.get().and_then(|p| p.parent()).map(|p| p.to_path_buf())
+}
+
+/// Snapshot of the current settings
+pub fn current() -> Settings {
+    SETTINGS.read().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Merge a partial JSON object into the settings
+pub fn apply_patch(settings: &Settings, patch: serde_json::Value) -> Result<Settings, String> {
+    let patch = match patch {
+        serde_json::Value::Object(map) => map,
+        _ => return Err("Settings patch must be an object".to_string()),
+    };
+
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    if let serde_json::Value::Object(ref mut map) = value {
+        for (key, val) in patch {
+            if !map.contains_key(&key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            map.insert(key, val);
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))
+}
+
+/// Apply a patch, persist it and return the new settings
+pub fn update(patch: serde_json::Value) -> Result<Settings, String> {
+    let updated = apply_patch(&current(), patch)?;
+
+    if let Some(path) = SETTINGS_PATH.get() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create config dir: {}", e))?;
+        }
+        let text = serde_json::to_string_pretty(&updated).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("Failed to save settings: {}", e))?;
+    }
+
+    let mut current = SETTINGS.write().map_err(|e| e.to_string())?;
+    *current = updated.clone();
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_patch() {
+        let settings = Settings::default();
+        let updated =
+            apply_patch(&settings, json!({ "adbPath": "/opt/adb", "batchSize": 200 })).unwrap();
+        assert_eq!(updated.adb_path, "/opt/adb");
+        assert_eq!(updated.batch_size, 200);
+        assert_eq!(updated.batch_interval_ms, settings.batch_interval_ms);
+    }
+
+    #[test]
+    fn test_apply_patch_rejects_unknown_and_invalid() {
+        let settings = Settings::default();
+        assert!(apply_patch(&settings, json!({ "nope": 1 })).is_err());
+        assert!(apply_patch(&settings, json!({ "batchSize": "big" })).is_err());
+        assert!(apply_patch(&settings, json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{ "theme": "light" }"#).unwrap();
+        assert_eq!(settings.theme, "light");
+        assert_eq!(settings.channel_capacity, 1000);
+    }
+}
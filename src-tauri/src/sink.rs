@@ -0,0 +1,84 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::parser::LogEntry;
+
+/// Sink path with `{device}` and `{time}` (local, `YYYYMMDD-HHMMSS`) filled in,
+/// so one profile can write a file per device and capture
+pub fn expand_path(template: &str, device_id: &str, now: chrono::DateTime<chrono::Local>) -> PathBuf {
+    let device: String = device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    PathBuf::from(
+        template
+            .replace("{device}", &device)
+            .replace("{time}", &now.format("%Y%m%d-%H%M%S").to_string()),
+    )
+}
+
+/// Appends a stream's entries to a file as logcat lines
+pub struct FileSink {
+    writer: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Ok(FileSink {
+            writer: BufWriter::new(file),
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        writeln!(self.writer, "{}", entry.to_line())
+    }
+
+    /// Called after each emitted batch, so the file trails the UI by one batch at most
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_expand_and_append() {
+        let now = chrono::Local.with_ymd_and_hms(2024, 12, 4, 10, 30, 45).unwrap();
+        let dir = std::env::temp_dir().join(format!("sink-test-{}", std::process::id()));
+        let template = format!("{}/{{device}}-{{time}}.log", dir.display());
+        let path = expand_path(&template, "192.168.1.5:5555", now);
+        assert!(path.ends_with("192_168_1_5_5555-20241204-103045.log"));
+
+        let mut parser = LogParser::new();
+        let entry = parser.parse_line("12-04 10:30:45.123  1234  1234 I Kiosk: heartbeat").unwrap();
+        for _ in 0..2 {
+            let mut sink = FileSink::open(&path).unwrap();
+            sink.write(&entry).unwrap();
+            sink.flush().unwrap();
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("Kiosk: heartbeat"));
+    }
+}
@@ -0,0 +1,202 @@
+use log::{debug, info};
+use std::process::Stdio;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+use crate::parser::{LogEntry, LogParser};
+
+/// Turns one line of a source's output into a log entry
+pub trait LineParser: Send {
+    fn parse_line(&mut self, line: &str) -> Option<LogEntry>;
+}
+
+impl LineParser for LogParser {
+    fn parse_line(&mut self, line: &str) -> Option<LogEntry> {
+        LogParser::parse_line(self, line)
+    }
+}
+
+/// A process whose stdout is a stream of log lines
+pub trait LogSource: Send + Sync {
+    /// Human-readable source name, used in logs and errors
+    fn name(&self) -> String;
+
+    /// Program and arguments to spawn
+    fn command(&self) -> (String, Vec<String>);
+
+    /// Parser for the lines the process prints
+    fn parser(&self) -> Box<dyn LineParser>;
+}
+
+/// Known stderr messages of logcat/adb and what they usually mean
+const STDERR_HINTS: &[(&str, &str)] = &[
+    ("unexpected EOF", "logcat lost its connection to logd; the device may have rebooted or the buffer was cleared"),
+    ("Permission denied", "The shell user cannot read this buffer; privileged buffers need adb root"),
+    ("Unable to open log device", "The requested buffer does not exist on this device"),
+    ("Invalid argument", "The device's logcat rejected an option; it may be too old for it"),
+    ("' not found", "The device disconnected or its serial changed"),
+    ("device offline", "The device is offline; reconnect the cable or restart adb"),
+    ("device unauthorized", "Accept the USB debugging prompt on the device"),
+];
+
+/// Explanation for a stderr line of a source process, when one is known
+pub fn stderr_hint(line: &str) -> Option<&'static str> {
+    STDERR_HINTS
+        .iter()
+        .find(|(needle, _)| line.contains(needle))
+        .map(|(_, hint)| *hint)
+}
+
+/// Longest line kept; the rest of it is dropped
+pub const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Appended to lines cut at `MAX_LINE_BYTES`
+const TRUNCATION_MARKER: &str = " …[truncated]";
+
+/// Line reader for process and socket output. Unlike `lines()`, a bad line
+/// can't end the stream: invalid UTF-8 is replaced per line, overlong lines are
+/// truncated and `\r` before the newline (Windows adb) is stripped.
+pub struct LineReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    max_bytes: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_limit(reader, MAX_LINE_BYTES)
+    }
+
+    fn with_limit(reader: R, max_bytes: usize) -> Self {
+        LineReader {
+            reader,
+            buf: Vec::new(),
+            max_bytes,
+        }
+    }
+
+    /// Next line without its terminator; `None` at end of stream
+    pub async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        self.buf.clear();
+        let mut truncated = false;
+        let mut read_any = false;
+        loop {
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                if !read_any {
+                    return Ok(None);
+                }
+                break;
+            }
+            read_any = true;
+            let newline = available.iter().position(|b| *b == b'\n');
+            let chunk = &available[..newline.unwrap_or(available.len())];
+            let room = self.max_bytes.saturating_sub(self.buf.len());
+            truncated |= chunk.len() > room;
+            self.buf.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            let consumed = newline.map_or(available.len(), |i| i + 1);
+            self.reader.consume(consumed);
+            if newline.is_some() {
+                break;
+            }
+        }
+
+        while self.buf.last() == Some(&b'\r') {
+            self.buf.pop();
+        }
+        let mut line = String::from_utf8_lossy(&self.buf).into_owned();
+        if truncated {
+            line.push_str(TRUNCATION_MARKER);
+        }
+        Ok(Some(line))
+    }
+}
+
+/// Spawn a log source and forward its parsed entries to `sender`
+pub fn spawn(source: &dyn LogSource, sender: mpsc::Sender<LogEntry>) -> Result<Child, String> {
+    let (program, args) = source.command();
+    let name = source.name();
+    info!("Starting log source: {}", name);
+
+    let mut child = Command::new(&program)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
+    let mut parser = source.parser();
+
+    tokio::spawn(async move {
+        let forwarded = pump(BufReader::new(stdout), parser.as_mut(), &sender).await;
+        info!("Log source {} finished after {} entries", name, forwarded);
+    });
+
+    Ok(child)
+}
+
+/// Parse every line of `reader` and send the entries until the input ends or
+/// the receiver is dropped; returns how many were sent. Independent of where
+/// the bytes come from (process, socket, file or canned test input).
+pub async fn pump<R: AsyncBufRead + Unpin>(
+    reader: R,
+    parser: &mut dyn LineParser,
+    sender: &mpsc::Sender<LogEntry>,
+) -> usize {
+    let mut lines = LineReader::new(reader);
+    let mut sent = 0;
+    while let Ok(Some(line)) = lines.next_line().await {
+        if let Some(entry) = parser.parse_line(&line) {
+            if sender.send(entry).await.is_err() {
+                debug!("Receiver dropped, stopping");
+                break;
+            }
+            sent += 1;
+        }
+    }
+    sent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stderr_hint() {
+        assert!(stderr_hint("read: unexpected EOF!").unwrap().contains("logd"));
+        assert!(stderr_hint("error: device 'abc' not found").is_some());
+        assert_eq!(stderr_hint("--------- beginning of main"), None);
+    }
+
+    #[tokio::test]
+    async fn test_pump_parses_canned_input() {
+        let input: &[u8] = b"12-04 10:30:45.123  1234  1234 I Net: up\nnot a log line\n12-04 10:30:45.124  1234  1234 W Net: slow\n";
+        let (tx, mut rx) = mpsc::channel(8);
+        let mut parser = LogParser::new();
+        assert_eq!(pump(input, &mut parser, &tx).await, 2);
+        assert_eq!(rx.recv().await.unwrap().message, "up");
+        assert_eq!(rx.recv().await.unwrap().level, crate::parser::LogLevel::W);
+    }
+
+    #[tokio::test]
+    async fn test_line_reader_survives_bad_input() {
+        let input: &[u8] = b"first\r\r\nbad \xff\xfe bytes\n0123456789abcdef\nlast";
+        let mut reader = LineReader::with_limit(input, 10);
+        let mut lines = Vec::new();
+        while let Some(line) = reader.next_line().await.unwrap() {
+            lines.push(line);
+        }
+        assert_eq!(
+            lines,
+            vec![
+                "first".to_string(),
+                "bad \u{fffd}\u{fffd} byt".to_string() + TRUNCATION_MARKER,
+                "0123456789".to_string() + TRUNCATION_MARKER,
+                "last".to_string(),
+            ]
+        );
+    }
+}
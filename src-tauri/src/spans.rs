@@ -0,0 +1,215 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::parser::LogEntry;
+
+/// Completed spans kept per device for `get_spans` and the percentiles
+const MAX_SPANS: usize = 1000;
+
+/// What pairs an end line with its start line
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpanKey {
+    /// The entries' `correlation_id`
+    Correlation,
+    #[default]
+    Pid,
+    Tid,
+}
+
+/// Start/end message patterns whose matching lines delimit a span
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanRule {
+    pub name: String,
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub key: SpanKey,
+}
+
+/// A start/end pair; payload of the `span` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub device_id: String,
+    pub rule: String,
+    /// Correlation ID, PID or "pid/tid" the lines were paired by
+    pub key: String,
+    pub start_entry_id: u64,
+    pub end_entry_id: u64,
+    pub start_epoch: u64,
+    pub duration_ms: u64,
+}
+
+/// Duration distribution of one rule's recent spans
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanStats {
+    pub rule: String,
+    pub count: usize,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+struct CompiledRule {
+    name: String,
+    start: Regex,
+    end: Regex,
+    key: SpanKey,
+}
+
+fn compile(rules: &[SpanRule]) -> Result<Vec<CompiledRule>, String> {
+    rules
+        .iter()
+        .map(|rule| {
+            let regex = |pattern: &str| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Invalid pattern in span rule {}: {}", rule.name, e))
+            };
+            Ok(CompiledRule {
+                name: rule.name.clone(),
+                start: regex(&rule.start)?,
+                end: regex(&rule.end)?,
+                key: rule.key,
+            })
+        })
+        .collect()
+}
+
+/// Pairs start and end lines of one device's stream into spans
+#[derive(Default)]
+pub struct SpanTracker {
+    rules: Vec<CompiledRule>,
+    /// Open spans: (rule index, key) -> (start entry ID, start epoch)
+    open: HashMap<(usize, String), (u64, u64)>,
+    done: VecDeque<Span>,
+}
+
+impl SpanTracker {
+    pub fn new(rules: &[SpanRule]) -> Result<Self, String> {
+        Ok(SpanTracker {
+            rules: compile(rules)?,
+            ..Default::default()
+        })
+    }
+
+    /// Feed an entry; returns the spans it closes. A repeated start line
+    /// restarts its span, and entries without a time are ignored.
+    pub fn observe(&mut self, device_id: &str, entry: &LogEntry) -> Vec<Span> {
+        let mut closed = Vec::new();
+        let Some(epoch) = entry.epoch else {
+            return closed;
+        };
+        for (index, rule) in self.rules.iter().enumerate() {
+            let key = match rule.key {
+                SpanKey::Correlation => match &entry.correlation_id {
+                    Some(id) => id.clone(),
+                    None => continue,
+                },
+                SpanKey::Pid => entry.pid.to_string(),
+                SpanKey::Tid => format!("{}/{}", entry.pid, entry.tid),
+            };
+            if rule.end.is_match(&entry.message) {
+                if let Some((start_id, start_epoch)) = self.open.remove(&(index, key.clone())) {
+                    closed.push(Span {
+                        device_id: device_id.to_string(),
+                        rule: rule.name.clone(),
+                        key,
+                        start_entry_id: start_id,
+                        end_entry_id: entry.id,
+                        start_epoch,
+                        duration_ms: epoch.saturating_sub(start_epoch),
+                    });
+                    continue;
+                }
+            }
+            if rule.start.is_match(&entry.message) {
+                self.open.insert((index, key), (entry.id, epoch));
+            }
+        }
+        for span in &closed {
+            if self.done.len() == MAX_SPANS {
+                self.done.pop_front();
+            }
+            self.done.push_back(span.clone());
+        }
+        closed
+    }
+
+    /// Recent completed spans, oldest first
+    pub fn spans(&self) -> Vec<Span> {
+        self.done.iter().cloned().collect()
+    }
+
+    /// Percentiles per rule over the recent spans
+    pub fn stats(&self) -> Vec<SpanStats> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                let mut durations: Vec<u64> = self
+                    .done
+                    .iter()
+                    .filter(|span| span.rule == rule.name)
+                    .map(|span| span.duration_ms)
+                    .collect();
+                durations.sort_unstable();
+                SpanStats {
+                    rule: rule.name.clone(),
+                    count: durations.len(),
+                    p50_ms: percentile(&durations, 50),
+                    p90_ms: percentile(&durations, 90),
+                    p99_ms: percentile(&durations, 99),
+                    max_ms: durations.last().copied().unwrap_or(0),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Nearest-rank percentile of sorted values; 0 when empty
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p * sorted.len() + 99) / 100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_pairs_by_pid_and_computes_stats() {
+        let rules = vec![SpanRule {
+            name: "sync".to_string(),
+            start: "^Begin sync".to_string(),
+            end: "^Sync complete".to_string(),
+            key: SpanKey::Pid,
+        }];
+        let mut tracker = SpanTracker::new(&rules).unwrap();
+        let mut parser = LogParser::new();
+        let mut closed = Vec::new();
+        for line in [
+            "12-04 10:30:45.000   100   100 I Sync: Begin sync",
+            "12-04 10:30:45.100   200   200 I Sync: Begin sync",
+            "12-04 10:30:45.250   100   100 I Sync: Sync complete",
+            "12-04 10:30:46.100   200   200 I Sync: Sync complete",
+            "12-04 10:30:46.200   300   300 I Sync: Sync complete",
+        ] {
+            closed.extend(tracker.observe("emulator-5554", &parser.parse_line(line).unwrap()));
+        }
+
+        let durations: Vec<u64> = closed.iter().map(|span| span.duration_ms).collect();
+        assert_eq!(durations, vec![250, 1000]);
+        assert_eq!(closed[0].key, "100");
+        let stats = &tracker.stats()[0];
+        assert_eq!((stats.count, stats.p50_ms, stats.max_ms), (2, 250, 1000));
+        assert!(SpanTracker::new(&[SpanRule { start: "(".to_string(), ..rules[0].clone() }]).is_err());
+    }
+}
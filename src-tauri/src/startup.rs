@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::lifecycle::{LifecycleEvent, LifecycleKind};
+
+/// History file name inside the app config directory
+pub const STARTUP_FILE: &str = "startup_times.json";
+
+/// Measurements kept per package; older ones are dropped
+const MAX_PER_PACKAGE: usize = 500;
+
+/// One app launch, from an `ActivityTaskManager: Displayed ... +NNNms` line
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupMeasurement {
+    pub package_name: String,
+    pub activity: String,
+    pub duration_ms: u64,
+    /// Epoch millis of the log line
+    pub epoch: u64,
+    pub device_id: String,
+    /// Installed `versionName` at the time of the launch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_name: Option<String>,
+}
+
+impl StartupMeasurement {
+    /// Build a measurement from a `Displayed` lifecycle event
+    pub fn from_event(device_id: &str, event: &LifecycleEvent, epoch: u64) -> Option<Self> {
+        if event.kind != LifecycleKind::Displayed {
+            return None;
+        }
+        Some(StartupMeasurement {
+            package_name: event.package_name.clone(),
+            activity: event.activity.clone(),
+            duration_ms: event.duration_ms?,
+            epoch,
+            device_id: device_id.to_string(),
+            version_name: None,
+        })
+    }
+}
+
+/// Launch time statistics for one app version
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionStats {
+    pub version_name: Option<String>,
+    pub count: usize,
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Launch history of a package plus a per-version trend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupHistory {
+    pub measurements: Vec<StartupMeasurement>,
+    pub by_version: Vec<VersionStats>,
+}
+
+/// Load all measurements; a missing file is an empty history
+pub fn load(path: &Path) -> Result<Vec<StartupMeasurement>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid startup history {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read startup history: {}", e)),
+    }
+}
+
+/// Append a measurement, trimming the package's oldest entries
+pub fn append(path: &Path, measurement: StartupMeasurement) -> Result<(), String> {
+    let mut all = load(path)?;
+    let package = measurement.package_name.clone();
+    all.push(measurement);
+
+    let count = all.iter().filter(|m| m.package_name == package).count();
+    let mut excess = count.saturating_sub(MAX_PER_PACKAGE);
+    all.retain(|m| {
+        if excess > 0 && m.package_name == package {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let text = serde_json::to_string(&all).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("Failed to save startup history: {}", e))
+}
+
+/// Measurements of one package (oldest first) with per-version statistics
+pub fn history(all: Vec<StartupMeasurement>, package: &str) -> StartupHistory {
+    let measurements: Vec<StartupMeasurement> = all
+        .into_iter()
+        .filter(|m| m.package_name == package)
+        .collect();
+
+    // Versions in the order they were first seen
+    let mut by_version: Vec<(Option<String>, Vec<u64>)> = Vec::new();
+    for m in &measurements {
+        match by_version.iter_mut().find(|(v, _)| *v == m.version_name) {
+            Some((_, durations)) => durations.push(m.duration_ms),
+            None => by_version.push((m.version_name.clone(), vec![m.duration_ms])),
+        }
+    }
+
+    let by_version = by_version
+        .into_iter()
+        .map(|(version_name, mut durations)| {
+            durations.sort_unstable();
+            VersionStats {
+                version_name,
+                count: durations.len(),
+                min_ms: durations[0],
+                median_ms: durations[durations.len() / 2],
+                max_ms: durations[durations.len() - 1],
+            }
+        })
+        .collect();
+
+    StartupHistory {
+        measurements,
+        by_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(package: &str, version: &str, duration_ms: u64) -> StartupMeasurement {
+        StartupMeasurement {
+            package_name: package.to_string(),
+            activity: format!("{}.MainActivity", package),
+            duration_ms,
+            epoch: 0,
+            device_id: "emulator-5554".to_string(),
+            version_name: Some(version.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_history_groups_by_version() {
+        let all = vec![
+            measurement("com.example", "1.0", 900),
+            measurement("com.other", "1.0", 100),
+            measurement("com.example", "1.0", 700),
+            measurement("com.example", "1.1", 500),
+            measurement("com.example", "1.0", 800),
+        ];
+        let history = history(all, "com.example");
+        assert_eq!(history.measurements.len(), 4);
+        assert_eq!(history.by_version.len(), 2);
+        let v1 = &history.by_version[0];
+        assert_eq!(v1.version_name.as_deref(), Some("1.0"));
+        assert_eq!((v1.count, v1.min_ms, v1.median_ms, v1.max_ms), (3, 700, 800, 900));
+        assert_eq!(history.by_version[1].median_ms, 500);
+    }
+
+    #[test]
+    fn test_append_round_trip() {
+        let path = std::env::temp_dir().join(format!("startup-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        append(&path, measurement("com.example", "1.0", 640)).unwrap();
+        append(&path, measurement("com.example", "1.0", 610)).unwrap();
+        let loaded = load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].duration_ms, 610);
+    }
+}
@@ -0,0 +1,298 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::parser::{LogEntry, LogLevel};
+
+/// Seconds between `stream-stats` events
+pub const STATS_INTERVAL_SECS: u64 = 5;
+
+/// Counters of one running stream, shared by its reader and forwarder
+#[derive(Debug, Default)]
+pub struct StreamStats {
+    lines_read: AtomicU64,
+    parsed: AtomicU64,
+    unparsed: AtomicU64,
+    /// Entries given a process name from the process cache
+    enriched: AtomicU64,
+    latency_ms_total: AtomicU64,
+    latency_samples: AtomicU64,
+}
+
+/// Payload of the `stream-stats` event and `get_stream_stats`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamStatsReport {
+    pub device_id: String,
+    pub lines_read: u64,
+    pub parsed: u64,
+    pub unparsed: u64,
+    /// Share of parsed entries whose process was resolved, 0.0-1.0
+    pub enrichment_rate: f64,
+    /// Mean delay between an entry's device time and its emission to the UI.
+    /// Includes any clock difference between host and device.
+    pub avg_latency_ms: Option<u64>,
+}
+
+impl StreamStats {
+    pub fn record_line(&self) {
+        self.lines_read.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_parsed(&self, enriched: bool) {
+        self.parsed.fetch_add(1, Ordering::Relaxed);
+        if enriched {
+            self.enriched.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_unparsed(&self) {
+        self.unparsed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the latency of entries emitted at `now_ms` (Unix milliseconds)
+    pub fn record_emitted(&self, entries: &[LogEntry], now_ms: u64) {
+        let (total, samples) = entries
+            .iter()
+            .filter_map(|entry| entry.epoch)
+            .fold((0, 0), |(total, samples), epoch| {
+                (total + now_ms.saturating_sub(epoch), samples + 1)
+            });
+        self.latency_ms_total.fetch_add(total, Ordering::Relaxed);
+        self.latency_samples.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    pub fn report(&self, device_id: &str) -> StreamStatsReport {
+        let parsed = self.parsed.load(Ordering::Relaxed);
+        let samples = self.latency_samples.load(Ordering::Relaxed);
+        StreamStatsReport {
+            device_id: device_id.to_string(),
+            lines_read: self.lines_read.load(Ordering::Relaxed),
+            parsed,
+            unparsed: self.unparsed.load(Ordering::Relaxed),
+            enrichment_rate: if parsed == 0 {
+                0.0
+            } else {
+                self.enriched.load(Ordering::Relaxed) as f64 / parsed as f64
+            },
+            avg_latency_ms: (samples > 0)
+                .then(|| self.latency_ms_total.load(Ordering::Relaxed) / samples),
+        }
+    }
+}
+
+/// Entries per second over the current and the previous one-second window
+#[derive(Debug)]
+pub struct RateMeter {
+    window_start: Instant,
+    current: u32,
+    previous: u32,
+}
+
+impl Default for RateMeter {
+    fn default() -> Self {
+        RateMeter {
+            window_start: Instant::now(),
+            current: 0,
+            previous: 0,
+        }
+    }
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= Duration::from_secs(1) {
+            // A gap of more than a full window means nothing arrived in between
+            self.previous = if elapsed < Duration::from_secs(2) { self.current } else { 0 };
+            self.current = 0;
+            self.window_start = now;
+        }
+        self.current += 1;
+    }
+
+    /// Recent rate; the larger window so a burst counts right away
+    pub fn per_second(&self) -> u32 {
+        self.current.max(self.previous)
+    }
+}
+
+/// Window warning/error counts are compared against their baseline over
+pub const ANOMALY_WINDOW: Duration = Duration::from_secs(30);
+/// A window this many times over the baseline is a spike
+const ANOMALY_FACTOR: f64 = 10.0;
+/// Fewer entries than this in a window never count as a spike
+const ANOMALY_MIN_COUNT: u32 = 10;
+/// Windows at the start of a session spent learning, without alerts
+const ANOMALY_WARMUP_WINDOWS: u32 = 2;
+/// Weight of the latest window in a baseline
+const BASELINE_SMOOTHING: f64 = 0.2;
+/// (tag, level) pairs tracked; new ones are ignored beyond this
+const MAX_ANOMALY_KEYS: usize = 5000;
+
+/// Payload of the `anomaly-detected` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Anomaly {
+    pub device_id: String,
+    pub tag: String,
+    /// `W`, or `E` for errors and asserts
+    pub level: LogLevel,
+    /// Entries in the current window so far
+    pub count: u32,
+    /// Usual entries per window
+    pub baseline: f64,
+    pub window_secs: u64,
+    /// Entry that crossed the threshold
+    pub entry_id: u64,
+}
+
+#[derive(Debug, Default)]
+struct LevelRate {
+    baseline: f64,
+    current: u32,
+    alerted: bool,
+}
+
+/// Learns the usual warning/error rate of each tag during a session and
+/// reports windows where it spikes, e.g. for unattended soak runs
+#[derive(Debug)]
+pub struct AnomalyDetector {
+    window_start: Instant,
+    windows: u32,
+    rates: HashMap<(String, LogLevel), LevelRate>,
+}
+
+impl AnomalyDetector {
+    pub fn new(now: Instant) -> Self {
+        AnomalyDetector {
+            window_start: now,
+            windows: 0,
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Count a warning or error, returning an anomaly the first time its tag
+    /// spikes within the current window
+    pub fn observe(&mut self, device_id: &str, entry: &LogEntry, now: Instant) -> Option<Anomaly> {
+        self.roll(now);
+        let level = match entry.level {
+            LogLevel::W => LogLevel::W,
+            LogLevel::E | LogLevel::A => LogLevel::E,
+            _ => return None,
+        };
+        let key = (entry.tag.clone(), level);
+        if !self.rates.contains_key(&key) && self.rates.len() >= MAX_ANOMALY_KEYS {
+            return None;
+        }
+        let rate = self.rates.entry(key).or_default();
+        rate.current += 1;
+        let threshold = (rate.baseline.max(1.0) * ANOMALY_FACTOR).max(f64::from(ANOMALY_MIN_COUNT));
+        if rate.alerted || self.windows < ANOMALY_WARMUP_WINDOWS || f64::from(rate.current) < threshold {
+            return None;
+        }
+        rate.alerted = true;
+        Some(Anomaly {
+            device_id: device_id.to_string(),
+            tag: entry.tag.clone(),
+            level,
+            count: rate.current,
+            baseline: rate.baseline,
+            window_secs: ANOMALY_WINDOW.as_secs(),
+            entry_id: entry.id,
+        })
+    }
+
+    /// Fold finished windows into the baselines
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        let finished = (elapsed.as_secs() / ANOMALY_WINDOW.as_secs()) as u32;
+        if finished == 0 {
+            return;
+        }
+        for rate in self.rates.values_mut() {
+            rate.baseline += (f64::from(rate.current) - rate.baseline) * BASELINE_SMOOTHING;
+            // Windows that passed without entries; beyond a few the baseline is near zero anyway
+            for _ in 1..finished.min(10) {
+                rate.baseline *= 1.0 - BASELINE_SMOOTHING;
+            }
+            rate.current = 0;
+            rate.alerted = false;
+        }
+        self.rates.retain(|_, rate| rate.baseline >= 0.01);
+        self.windows = self.windows.saturating_add(finished);
+        self.window_start += ANOMALY_WINDOW * finished;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_report_rates_and_latency() {
+        let stats = StreamStats::default();
+        for enriched in [true, true, true, false] {
+            stats.record_line();
+            stats.record_parsed(enriched);
+        }
+        stats.record_line();
+        stats.record_unparsed();
+
+        let mut parser = LogParser::new();
+        let entry = parser.parse_line("12-04 10:30:45.000   100   100 I Sync: Begin sync").unwrap();
+        let epoch = entry.epoch.unwrap();
+        stats.record_emitted(&[entry.clone(), entry], epoch + 120);
+
+        let report = stats.report("emulator-5554");
+        assert_eq!((report.lines_read, report.parsed, report.unparsed), (5, 4, 1));
+        assert_eq!(report.enrichment_rate, 0.75);
+        assert_eq!(report.avg_latency_ms, Some(120));
+        assert_eq!(StreamStats::default().report("x").avg_latency_ms, None);
+    }
+
+    #[test]
+    fn test_rate_meter_windows() {
+        let mut meter = RateMeter::new();
+        let start = meter.window_start;
+        for _ in 0..30 {
+            meter.record(start);
+        }
+        meter.record(start + Duration::from_millis(1500));
+        assert_eq!(meter.per_second(), 30);
+        meter.record(start + Duration::from_secs(5));
+        assert_eq!(meter.per_second(), 1);
+    }
+
+    #[test]
+    fn test_anomaly_after_warmup() {
+        let mut parser = LogParser::new();
+        let error = parser.parse_line("12-04 10:30:45.000   100   100 E Sync: Sync failed").unwrap();
+        let start = Instant::now();
+        let mut detector = AnomalyDetector::new(start);
+
+        // One error per window is the baseline
+        for window in 0..4 {
+            let now = start + ANOMALY_WINDOW * window;
+            assert_eq!(detector.observe("dev", &error, now), None);
+        }
+        let spike = start + ANOMALY_WINDOW * 4;
+        let fired: Vec<Anomaly> = (0..12)
+            .filter_map(|_| detector.observe("dev", &error, spike))
+            .collect();
+        assert_eq!(fired.len(), 1);
+        assert_eq!((fired[0].level, fired[0].count), (LogLevel::E, 10));
+
+        // Info entries and spikes during warmup are ignored
+        let info = parser.parse_line("12-04 10:30:45.000   100   100 I Sync: ok").unwrap();
+        let mut fresh = AnomalyDetector::new(start);
+        assert!((0..50).all(|_| fresh.observe("dev", &error, start).is_none()));
+        assert_eq!(fresh.observe("dev", &info, start), None);
+    }
+}
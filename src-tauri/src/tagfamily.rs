@@ -0,0 +1,138 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Characters separating the levels of a tag family, e.g. `okhttp.` or `WM-`
+const SEPARATORS: &[char] = &['.', '-', '_', ':', '/'];
+
+/// End of the camel-case head of a word, e.g. 8 for "FirebaseMessaging"
+fn camel_head(word: &str) -> Option<usize> {
+    let mut prev_lower = false;
+    for (i, c) in word.char_indices() {
+        if prev_lower && c.is_uppercase() {
+            return Some(i);
+        }
+        prev_lower = c.is_lowercase();
+    }
+    None
+}
+
+/// Families a tag belongs to, outermost first: the camel-case head of its
+/// first segment ("Firebase" of "FirebaseInstanceId"), then every prefix
+/// ending before a separator ("okhttp", "okhttp.internal" of
+/// "okhttp.internal.Http2")
+pub fn families(tag: &str) -> Vec<&str> {
+    let first_end = tag.find(SEPARATORS).unwrap_or(tag.len());
+    let mut families: Vec<&str> = camel_head(&tag[..first_end])
+        .map(|end| &tag[..end])
+        .into_iter()
+        .collect();
+    let mut prev_separator = true;
+    for (i, c) in tag.char_indices() {
+        let separator = SEPARATORS.contains(&c);
+        if separator && !prev_separator && i + c.len_utf8() < tag.len() {
+            families.push(&tag[..i]);
+        }
+        prev_separator = separator;
+    }
+    families
+}
+
+/// Family name as users write it in a rule: `WM-`, `Firebase*` and
+/// `okhttp.*` all name the same kind of family as `WM`, `Firebase`, `okhttp`
+pub fn normalize(family: &str) -> &str {
+    family.trim_end_matches('*').trim_end_matches(SEPARATORS)
+}
+
+/// Regex matching the tags of a family: the family itself, tags continuing
+/// it after a separator, and, when it ends in a lowercase letter, camel-case
+/// continuations ("Firebase" matches "FirebaseMessaging" but not "Firebased")
+pub fn pattern(family: &str) -> String {
+    let family = normalize(family);
+    let camel = if family.ends_with(char::is_lowercase) { r"|(?-i:\p{Lu})" } else { "" };
+    format!(r"^{}(?:$|[.\-_:/]{})", regex::escape(family), camel)
+}
+
+/// A family or tag with its entry count, including everything below it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FamilyNode {
+    pub name: String,
+    pub count: u64,
+    /// Sub-families and tags, most entries first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FamilyNode>,
+}
+
+#[derive(Default)]
+struct Branch {
+    count: u64,
+    children: BTreeMap<String, Branch>,
+}
+
+impl Branch {
+    fn into_nodes(self) -> Vec<FamilyNode> {
+        let mut nodes: Vec<FamilyNode> = self
+            .children
+            .into_iter()
+            .map(|(name, branch)| FamilyNode {
+                name,
+                count: branch.count,
+                children: branch.into_nodes(),
+            })
+            .collect();
+        nodes.sort_by_key(|node| std::cmp::Reverse(node.count));
+        nodes
+    }
+}
+
+/// Roll per-tag counts up into their families. A tag that is also a family
+/// name (e.g. "okhttp" next to "okhttp.Http2") counts towards that family.
+pub fn rollup<'a>(counts: impl IntoIterator<Item = (&'a str, u64)>) -> Vec<FamilyNode> {
+    let mut root = Branch::default();
+    for (tag, count) in counts {
+        let mut branch = &mut root;
+        for name in families(tag).into_iter().chain([tag]) {
+            branch = branch.children.entry(name.to_string()).or_default();
+            branch.count += count;
+        }
+    }
+    root.into_nodes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_families_and_pattern() {
+        assert_eq!(families("okhttp.internal.Http2"), vec!["okhttp", "okhttp.internal"]);
+        assert_eq!(families("FirebaseMessaging"), vec!["Firebase"]);
+        assert_eq!(families("WM-WorkerWrapper"), vec!["WM"]);
+        assert_eq!(families("chatty"), Vec::<&str>::new());
+        assert_eq!(families("a..b-"), vec!["a"]);
+
+        let firebase = Regex::new(&format!("(?i){}", pattern("Firebase*"))).unwrap();
+        assert!(firebase.is_match("FirebaseMessaging"));
+        assert!(firebase.is_match("firebase"));
+        assert!(!firebase.is_match("Firebased"));
+        let wm = Regex::new(&pattern("WM-")).unwrap();
+        assert!(wm.is_match("WM-WorkerWrapper"));
+        assert!(!wm.is_match("WMS"));
+    }
+
+    #[test]
+    fn test_rollup() {
+        let tree = rollup([
+            ("okhttp.Http2", 5),
+            ("okhttp.internal.Cache", 2),
+            ("okhttp", 1),
+            ("FirebaseMessaging", 3),
+            ("chatty", 4),
+        ]);
+        let summary: Vec<(&str, u64)> = tree.iter().map(|n| (n.name.as_str(), n.count)).collect();
+        assert_eq!(summary, vec![("okhttp", 8), ("chatty", 4), ("Firebase", 3)]);
+        let okhttp: Vec<&str> = tree[0].children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(okhttp, vec!["okhttp.Http2", "okhttp.internal"]);
+        assert_eq!(tree[0].children[1].children[0].name, "okhttp.internal.Cache");
+    }
+}
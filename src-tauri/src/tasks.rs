@@ -0,0 +1,213 @@
+use log::{error, warn};
+use serde::Serialize;
+use std::any::Any;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tauri::async_runtime::JoinHandle;
+use tokio::time::Duration;
+
+/// Panics after which a supervised task is given up on
+const MAX_RESTARTS: u32 = 5;
+
+/// Pause before restarting a task that panicked
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Liveness of a background task, as returned by `get_task_health`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHealth {
+    pub name: String,
+    /// Device the task belongs to; `None` for app-wide tasks
+    pub owner: Option<String>,
+    /// RFC 3339 time the task was first started
+    pub started_at: String,
+    /// False once the task finished, was given up on, or was stopped
+    pub alive: bool,
+    pub restarts: u32,
+    /// Message of the most recent panic
+    pub last_panic: Option<String>,
+}
+
+#[derive(Default)]
+struct Status {
+    restarts: u32,
+    last_panic: Option<String>,
+}
+
+struct Task {
+    name: String,
+    owner: Option<String>,
+    started_at: String,
+    status: Arc<Mutex<Status>>,
+    handle: JoinHandle<()>,
+}
+
+/// Aborts the task when dropped, so aborting a supervisor stops its task too
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Background tasks with their join handles. A task is keyed by name and
+/// owner; starting one again replaces (aborts) the previous instance.
+#[derive(Default)]
+pub struct TaskSet {
+    tasks: Mutex<Vec<Task>>,
+}
+
+impl TaskSet {
+    fn lock(&self) -> MutexGuard<'_, Vec<Task>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run the future `task` builds, building and running it again after a
+    /// panic, up to `MAX_RESTARTS` times
+    pub fn supervise<F, Fut>(&self, name: &str, owner: Option<&str>, task: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.start(name, owner, MAX_RESTARTS, task);
+    }
+
+    /// Run a task that can't be restarted, e.g. one owning a channel end,
+    /// so it still shows in `health` and stops with its owner
+    pub fn spawn<Fut>(&self, name: &str, owner: Option<&str>, task: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut task = Some(task);
+        self.start(name, owner, 0, move || {
+            task.take().expect("one-shot task is never restarted")
+        });
+    }
+
+    fn start<F, Fut>(&self, name: &str, owner: Option<&str>, max_restarts: u32, mut task: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let status = Arc::new(Mutex::new(Status::default()));
+        let task_status = status.clone();
+        let label = match owner {
+            Some(owner) => format!("{} ({})", name, owner),
+            None => name.to_string(),
+        };
+        let handle = tauri::async_runtime::spawn(async move {
+            loop {
+                let mut running = AbortOnDrop(tokio::spawn(task()));
+                let Err(e) = (&mut running.0).await else {
+                    break;
+                };
+                if !e.is_panic() {
+                    break;
+                }
+                let message = panic_message(e.into_panic());
+                error!("Task {} panicked: {}", label, message);
+                let restarts = {
+                    let mut status = task_status.lock().unwrap_or_else(|e| e.into_inner());
+                    status.restarts += 1;
+                    status.last_panic = Some(message);
+                    status.restarts
+                };
+                if restarts > max_restarts {
+                    if max_restarts > 0 {
+                        warn!("Giving up on task {} after {} restarts", label, max_restarts);
+                    }
+                    break;
+                }
+                tokio::time::sleep(RESTART_DELAY).await;
+            }
+        });
+
+        let mut tasks = self.lock();
+        tasks.retain(|existing| {
+            let same = existing.name == name && existing.owner.as_deref() == owner;
+            if same {
+                existing.handle.abort();
+            }
+            !same
+        });
+        tasks.push(Task {
+            name: name.to_string(),
+            owner: owner.map(str::to_string),
+            started_at: chrono::Local::now().to_rfc3339(),
+            status,
+            handle,
+        });
+    }
+
+    /// Abort and forget every task of `owner`
+    pub fn stop(&self, owner: &str) {
+        self.lock().retain(|task| {
+            let owned = task.owner.as_deref() == Some(owner);
+            if owned {
+                task.handle.abort();
+            }
+            !owned
+        });
+    }
+
+    /// Liveness of every task, in start order
+    pub fn health(&self) -> Vec<TaskHealth> {
+        self.lock()
+            .iter()
+            .map(|task| {
+                let status = task.status.lock().unwrap_or_else(|e| e.into_inner());
+                TaskHealth {
+                    name: task.name.clone(),
+                    owner: task.owner.clone(),
+                    started_at: task.started_at.clone(),
+                    alive: !task.handle.inner().is_finished(),
+                    restarts: status.restarts,
+                    last_panic: status.last_panic.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_supervised_task_restarts_after_panic() {
+        let tasks = TaskSet::default();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        tasks.supervise("refresh", Some("emulator-5554"), move || {
+            let counter = counter.clone();
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("ps parse failed");
+                }
+                std::future::pending::<()>().await
+            }
+        });
+        tasks.spawn("reader", Some("emulator-5554"), async {});
+        tokio::time::sleep(RESTART_DELAY + Duration::from_millis(300)).await;
+
+        let health = tasks.health();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(health[0].alive);
+        assert_eq!(health[0].restarts, 1);
+        assert_eq!(health[0].last_panic.as_deref(), Some("ps parse failed"));
+        assert!(!health[1].alive);
+
+        tasks.stop("emulator-5554");
+        assert!(tasks.health().is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{Datelike, NaiveDateTime, NaiveTime, TimeZone};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{LogEntry, LogLevel};
+use crate::settings;
+
+/// User-defined line format: a regex with named groups
+/// (`timestamp`, `level`, `tag`, `message`, `pid`, `tid`) plus a chrono
+/// format string for the `timestamp` group.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParserTemplate {
+    pub name: String,
+    pub pattern: String,
+    /// e.g. "%Y-%m-%d %H:%M:%S%.3f"; when absent the receive time is used
+    #[serde(default)]
+    pub timestamp_format: Option<String>,
+}
+
+/// A template with its regex compiled
+#[derive(Debug, Clone)]
+pub struct CompiledTemplate {
+    regex: Regex,
+    timestamp_format: Option<String>,
+}
+
+impl ParserTemplate {
+    pub fn compile(&self) -> Result<CompiledTemplate, String> {
+        let regex = Regex::new(&self.pattern)
+            .map_err(|e| format!("Invalid pattern in template {}: {}", self.name, e))?;
+        if !regex.capture_names().any(|n| n == Some("message")) {
+            return Err(format!(
+                "Template {} needs a named group (?P<message>...)",
+                self.name
+            ));
+        }
+        Ok(CompiledTemplate {
+            regex,
+            timestamp_format: self.timestamp_format.clone(),
+        })
+    }
+}
+
+/// Parse a captured timestamp; formats without a date use today, without a year the current year
+fn parse_timestamp(value: &str, format: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+        return Some(dt);
+    }
+    let today = chrono::Local::now().date_naive();
+    if let Ok(time) = NaiveTime::parse_from_str(value, format) {
+        return Some(today.and_time(time));
+    }
+    // Formats like "%m-%d %H:%M:%S" lack a year; retry with the current one
+    let with_year = format!("{} {}", today.year(), value);
+    NaiveDateTime::parse_from_str(&with_year, &format!("%Y {}", format)).ok()
+}
+
+impl CompiledTemplate {
+    /// Parse a line into an entry, or None if the template doesn't match
+    pub fn parse(&self, line: &str, id: u64) -> Option<LogEntry> {
+        let caps = self.regex.captures(line)?;
+        let group = |name: &str| caps.name(name).map(|m| m.as_str());
+
+        let parsed_time = match (group("timestamp"), &self.timestamp_format) {
+            (Some(value), Some(format)) => parse_timestamp(value, format),
+            _ => None,
+        };
+        let time = parsed_time.unwrap_or_else(|| chrono::Local::now().naive_local());
+        let local = chrono::Local.from_local_datetime(&time).single();
+        let epoch = local.map(|t| t.timestamp_millis() as u64);
+        let timestamp = time.format("%H:%M:%S%.3f").to_string();
+
+        let level = group("level")
+            .and_then(|l| l.trim().chars().next())
+            .and_then(|c| LogLevel::from_char(c.to_ascii_uppercase()))
+            .unwrap_or(LogLevel::I);
+
+        Some(LogEntry {
+            id,
+            seq: 0,
+            timestamp: timestamp.clone(),
+            date_time: Some(format!("{} {}", time.format("%m-%d"), timestamp)),
+            epoch,
+            time: local.map(Into::into),
+            pid: group("pid").and_then(|p| p.parse().ok()).unwrap_or(0),
+            tid: group("tid").and_then(|t| t.parse().ok()).unwrap_or(0),
+            level,
+            tag: group("tag").unwrap_or_default().trim().to_string(),
+            message: group("message").unwrap_or(line).to_string(),
+            package_name: None,
+            process_name: None,
+            device_color: None,
+            package_color: None,
+            correlation_id: None,
+            links: Vec::new(),
+            group_id: None,
+            sensitive: false,
+            sanitized: false,
+            marker: false,
+            color_level: None,
+            raw: Some(line.to_string()),
+        })
+    }
+}
+
+/// Compile the templates registered in settings, skipping invalid ones
+pub fn registered() -> Vec<CompiledTemplate> {
+    settings::current()
+        .parser_templates
+        .iter()
+        .filter_map(|t| match t.compile() {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                log::error!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Look up one registered template by name
+pub fn find(name: &str) -> Result<CompiledTemplate, String> {
+    settings::current()
+        .parser_templates
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| format!("Unknown parser template: {}", name))?
+        .compile()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(pattern: &str, format: Option<&str>) -> CompiledTemplate {
+        ParserTemplate {
+            name: "test".to_string(),
+            pattern: pattern.to_string(),
+            timestamp_format: format.map(str::to_string),
+        }
+        .compile()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_template_requires_message_group() {
+        let t = ParserTemplate {
+            name: "bad".to_string(),
+            pattern: r"(?P<tag>\w+)".to_string(),
+            timestamp_format: None,
+        };
+        assert!(t.compile().is_err());
+    }
+
+    #[test]
+    fn test_template_parses_fields() {
+        let t = template(
+            r"^(?P<timestamp>\S+ \S+) \[(?P<level>\w+)\] (?P<tag>[\w.]+) \((?P<pid>\d+)\): (?P<message>.*)$",
+            Some("%Y-%m-%d %H:%M:%S%.3f"),
+        );
+        let entry = t
+            .parse("2024-03-14 10:23:45.120 [ERROR] app.Db (321): connection lost", 7)
+            .unwrap();
+        assert_eq!(entry.id, 7);
+        assert_eq!(entry.timestamp, "10:23:45.120");
+        assert_eq!(entry.date_time.as_deref(), Some("03-14 10:23:45.120"));
+        assert_eq!(entry.level, LogLevel::E);
+        assert_eq!(entry.tag, "app.Db");
+        assert_eq!(entry.pid, 321);
+        assert_eq!(entry.message, "connection lost");
+    }
+
+    #[test]
+    fn test_time_only_format() {
+        let t = template(r"^(?P<timestamp>\S+) (?P<message>.*)$", Some("%H:%M:%S"));
+        let entry = t.parse("08:15:00 boot", 0).unwrap();
+        assert_eq!(entry.timestamp, "08:15:00.000");
+        assert!(t.parse("", 0).is_none());
+    }
+}
@@ -0,0 +1,366 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::parser::LogEntry;
+use crate::redaction::Redactor;
+
+/// Maximum number of test cases kept per device
+const MAX_TEST_CASES: usize = 5000;
+
+/// Only the most recent cases keep their log lines
+const MAX_CASES_WITH_LOGS: usize = 200;
+
+/// Log lines kept per test case
+const MAX_LINES_PER_CASE: usize = 20_000;
+
+/// "started: testLogin(com.example.LoginTest)"
+static TEST_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(started|finished|failed|ignored|assumption failed): (\S+)\(([\w.$]+)\)")
+        .expect("Invalid TestRunner regex")
+});
+
+/// "run started: 12 tests" / "run finished: 12 tests, 1 failed, 0 ignored"
+static RUN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^run (started|finished): (\d+) tests?").expect("Invalid TestRunner run regex")
+});
+
+/// What a `TestRunner` line reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TestEventKind {
+    RunStarted,
+    RunFinished,
+    Started,
+    Finished,
+    Failed,
+    Ignored,
+    AssumptionFailed,
+}
+
+/// A test marker parsed from AndroidJUnitRunner's `TestRunner` tag
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestEvent {
+    pub kind: TestEventKind,
+    /// Test class; empty for run events
+    pub class_name: String,
+    /// Test method; empty for run events
+    pub method: String,
+    /// Test count for run events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    pub time: String,
+    pub entry_id: u64,
+}
+
+/// Parse a test marker out of a log entry, if it is one
+pub fn parse_test_event(entry: &LogEntry) -> Option<TestEvent> {
+    if entry.tag != "TestRunner" {
+        return None;
+    }
+    let time = entry.date_time.clone().unwrap_or_else(|| entry.timestamp.clone());
+    let message = entry.message.trim();
+
+    if let Some(caps) = RUN_REGEX.captures(message) {
+        let kind = if &caps[1] == "started" {
+            TestEventKind::RunStarted
+        } else {
+            TestEventKind::RunFinished
+        };
+        return Some(TestEvent {
+            kind,
+            class_name: String::new(),
+            method: String::new(),
+            count: caps[2].parse().ok(),
+            time,
+            entry_id: entry.id,
+        });
+    }
+
+    let caps = TEST_REGEX.captures(message)?;
+    let kind = match &caps[1] {
+        "started" => TestEventKind::Started,
+        "finished" => TestEventKind::Finished,
+        "failed" => TestEventKind::Failed,
+        "ignored" => TestEventKind::Ignored,
+        _ => TestEventKind::AssumptionFailed,
+    };
+    Some(TestEvent {
+        kind,
+        class_name: caps[3].to_string(),
+        method: caps[2].to_string(),
+        count: None,
+        time,
+        entry_id: entry.id,
+    })
+}
+
+/// Outcome of a test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TestStatus {
+    Running,
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// One test case and the range of entries logged while it ran
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    pub class_name: String,
+    pub method: String,
+    pub status: TestStatus,
+    pub start_entry_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_entry_id: Option<u64>,
+    pub start_time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    /// Crashes and ANRs that happened while the test ran
+    pub problems: Vec<String>,
+    #[serde(skip)]
+    lines: Vec<String>,
+}
+
+impl TestCase {
+    fn new(event: &TestEvent, status: TestStatus) -> Self {
+        TestCase {
+            class_name: event.class_name.clone(),
+            method: event.method.clone(),
+            status,
+            start_entry_id: event.entry_id,
+            end_entry_id: None,
+            start_time: event.time.clone(),
+            end_time: None,
+            problems: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn contains(&self, entry_id: u64) -> bool {
+        entry_id >= self.start_entry_id && self.end_entry_id.map_or(true, |end| entry_id <= end)
+    }
+
+    /// File name for the per-test export, e.g. "0003_com.example.T.testB_failed.log"
+    fn file_name(&self, index: usize) -> String {
+        let name: String = format!("{}.{}", self.class_name, self.method)
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+            .collect();
+        let status = format!("{:?}", self.status).to_lowercase();
+        format!("{:04}_{}_{}.log", index, name, status)
+    }
+}
+
+/// "ActivityManager: ANR in com.example (com.example/.MainActivity)"
+fn is_anr(entry: &LogEntry) -> bool {
+    (entry.tag == "ActivityManager" || entry.tag == "ActivityTaskManager")
+        && entry.message.starts_with("ANR in ")
+}
+
+/// Builds test cases from a device's test events
+#[derive(Debug, Default)]
+pub struct TestTracker {
+    cases: VecDeque<TestCase>,
+}
+
+impl TestTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, case: TestCase) {
+        if self.cases.len() >= MAX_TEST_CASES {
+            self.cases.pop_front();
+        }
+        if self.cases.len() >= MAX_CASES_WITH_LOGS {
+            let old = self.cases.len() - MAX_CASES_WITH_LOGS;
+            self.cases[old].lines = Vec::new();
+        }
+        self.cases.push_back(case);
+    }
+
+    /// Attach a crash/ANR description to the test that was running at `entry_id`
+    pub fn record_problem(&mut self, entry_id: u64, problem: String) {
+        if let Some(case) = self.cases.iter_mut().rev().find(|c| c.contains(entry_id)) {
+            case.problems.push(problem);
+        }
+    }
+
+    /// Latest case for the event's test that hasn't finished yet
+    fn find_open(&mut self, event: &TestEvent) -> Option<&mut TestCase> {
+        self.cases.iter_mut().rev().find(|c| {
+            c.end_entry_id.is_none()
+                && c.class_name == event.class_name
+                && c.method == event.method
+        })
+    }
+
+    /// Feed a log entry, returning the test event it carried
+    pub fn observe(&mut self, entry: &LogEntry) -> Option<TestEvent> {
+        let event = parse_test_event(entry);
+        if let Some(ref event) = event {
+            self.apply(event);
+        }
+
+        if let Some(case) = self.cases.back_mut() {
+            if case.contains(entry.id) && case.status != TestStatus::Ignored {
+                if case.lines.len() < MAX_LINES_PER_CASE {
+                    case.lines.push(entry.to_line());
+                }
+                if is_anr(entry) {
+                    case.problems.push(format!("ANR: {}", entry.message));
+                }
+            }
+        }
+        event
+    }
+
+    fn apply(&mut self, event: &TestEvent) {
+        match event.kind {
+            TestEventKind::Started => self.push(TestCase::new(event, TestStatus::Running)),
+            TestEventKind::Failed => {
+                if let Some(case) = self.find_open(event) {
+                    case.status = TestStatus::Failed;
+                }
+            }
+            TestEventKind::Ignored => {
+                // Ignored tests are reported without a "started" line
+                let mut case = TestCase::new(event, TestStatus::Ignored);
+                case.end_entry_id = Some(event.entry_id);
+                case.end_time = Some(event.time.clone());
+                self.push(case);
+            }
+            TestEventKind::Finished => {
+                // A failed case already got "failed" before its "finished"
+                if let Some(case) = self.find_open(event) {
+                    if case.status == TestStatus::Running {
+                        case.status = TestStatus::Passed;
+                    }
+                    case.end_entry_id = Some(event.entry_id);
+                    case.end_time = Some(event.time.clone());
+                }
+            }
+            TestEventKind::RunStarted | TestEventKind::RunFinished | TestEventKind::AssumptionFailed => {}
+        }
+    }
+
+    pub fn cases(&self) -> &VecDeque<TestCase> {
+        &self.cases
+    }
+
+    /// Write one log file per test case that still has its lines; returns the file count
+    pub fn export(&self, out_dir: &Path, redactor: Option<&Redactor>) -> Result<usize, String> {
+        std::fs::create_dir_all(out_dir)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        let mut written = 0;
+        for (index, case) in self.cases.iter().enumerate() {
+            if case.lines.is_empty() {
+                continue;
+            }
+            let mut text = format!(
+                "# Test: {}#{}\n# Status: {:?}\n# Started: {}\n",
+                case.class_name, case.method, case.status, case.start_time
+            );
+            if let Some(end) = &case.end_time {
+                text.push_str(&format!("# Finished: {}\n", end));
+            }
+            for problem in &case.problems {
+                text.push_str(&format!("# Problem: {}\n", problem));
+            }
+            text.push('\n');
+            for line in &case.lines {
+                match redactor {
+                    Some(r) => text.push_str(&r.redact(line)),
+                    None => text.push_str(line),
+                }
+                text.push('\n');
+            }
+            std::fs::write(out_dir.join(case.file_name(index)), text)
+                .map_err(|e| format!("Failed to write test log: {}", e))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_parse_test_events() {
+        let mut parser = LogParser::new();
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  4321  4340 I TestRunner: started: testLogin(com.example.LoginTest)")
+            .unwrap();
+        let event = parse_test_event(&entry).unwrap();
+        assert_eq!(event.kind, TestEventKind::Started);
+        assert_eq!(event.class_name, "com.example.LoginTest");
+        assert_eq!(event.method, "testLogin");
+
+        let entry = parser
+            .parse_line("12-04 10:30:45.123  4321  4340 I TestRunner: run finished: 3 tests, 1 failed, 0 ignored")
+            .unwrap();
+        let event = parse_test_event(&entry).unwrap();
+        assert_eq!(event.kind, TestEventKind::RunFinished);
+        assert_eq!(event.count, Some(3));
+    }
+
+    #[test]
+    fn test_tracker_builds_cases() {
+        let mut parser = LogParser::new();
+        let mut tracker = TestTracker::new();
+        let lines = [
+            "12-04 10:30:45.000  4321  4340 I TestRunner: run started: 2 tests",
+            "12-04 10:30:45.100  4321  4340 I TestRunner: started: testA(com.example.T)",
+            "12-04 10:30:45.200  4321  4340 I TestRunner: finished: testA(com.example.T)",
+            "12-04 10:30:45.300  4321  4340 I TestRunner: started: testB(com.example.T)",
+            "12-04 10:30:45.350  4321  4340 D Login: request sent",
+            "12-04 10:30:45.400  4321  4340 E TestRunner: failed: testB(com.example.T)",
+            "12-04 10:30:45.500  4321  4340 I TestRunner: finished: testB(com.example.T)",
+        ];
+        let entries: Vec<LogEntry> = lines.iter().map(|l| parser.parse_line(l).unwrap()).collect();
+        for entry in &entries {
+            tracker.observe(entry);
+        }
+
+        let cases = tracker.cases();
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].status, TestStatus::Passed);
+        assert_eq!(cases[1].status, TestStatus::Failed);
+        assert_eq!(
+            (cases[1].start_entry_id, cases[1].end_entry_id),
+            (entries[3].id, Some(entries[6].id))
+        );
+        assert_eq!(cases[1].lines.len(), 4);
+        assert_eq!(cases[1].file_name(1), "0001_com.example.T.testB_failed.log");
+    }
+
+    #[test]
+    fn test_problems_attach_to_running_test() {
+        let mut parser = LogParser::new();
+        let mut tracker = TestTracker::new();
+        let lines = [
+            "12-04 10:30:45.100  4321  4340 I TestRunner: started: testA(com.example.T)",
+            "12-04 10:30:45.200  1000  1200 E ActivityManager: ANR in com.example (com.example/.MainActivity)",
+            "12-04 10:30:45.300  4321  4340 I TestRunner: finished: testA(com.example.T)",
+        ];
+        let entries: Vec<LogEntry> = lines.iter().map(|l| parser.parse_line(l).unwrap()).collect();
+        for entry in &entries {
+            tracker.observe(entry);
+        }
+        tracker.record_problem(entries[1].id, "Crash: java.lang.IllegalStateException".to_string());
+        tracker.record_problem(entries[2].id + 1, "Crash: outside any test".to_string());
+
+        let problems = &tracker.cases()[0].problems;
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("ANR: ANR in com.example"));
+    }
+}
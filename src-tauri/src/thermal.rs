@@ -0,0 +1,270 @@
+use log::{error, info};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration};
+
+use crate::adb::AdbManager;
+use crate::channels::ChannelMask;
+use crate::commands;
+use crate::parser::LogEntry;
+
+/// `Temperature{mValue=38.5, mType=3, mName=battery, mStatus=0}`
+static TEMPERATURE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Temperature\{mValue=(-?[\d.]+),.*?mName=([^,}]+)").expect("Invalid temperature regex")
+});
+
+/// Thermal throttling level reported by `thermalservice`
+/// (`PowerManager.THERMAL_STATUS_*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThermalStatus {
+    None,
+    Light,
+    Moderate,
+    Severe,
+    Critical,
+    Emergency,
+    Shutdown,
+}
+
+impl ThermalStatus {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(ThermalStatus::None),
+            1 => Some(ThermalStatus::Light),
+            2 => Some(ThermalStatus::Moderate),
+            3 => Some(ThermalStatus::Severe),
+            4 => Some(ThermalStatus::Critical),
+            5 => Some(ThermalStatus::Emergency),
+            6 => Some(ThermalStatus::Shutdown),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ThermalStatus::None => "none",
+            ThermalStatus::Light => "light",
+            ThermalStatus::Moderate => "moderate",
+            ThermalStatus::Severe => "severe",
+            ThermalStatus::Critical => "critical",
+            ThermalStatus::Emergency => "emergency",
+            ThermalStatus::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// One sensor's temperature
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ThermalReading {
+    pub name: String,
+    pub celsius: f64,
+}
+
+/// Thermal state at one poll, returned by `get_thermal_status`
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalSnapshot {
+    /// Unknown on devices without `thermalservice` (before Android 10)
+    pub status: Option<ThermalStatus>,
+    pub temperatures: Vec<ThermalReading>,
+}
+
+impl ThermalSnapshot {
+    fn hottest(&self) -> Option<&ThermalReading> {
+        self.temperatures
+            .iter()
+            .max_by(|a, b| a.celsius.total_cmp(&b.celsius))
+    }
+}
+
+/// Parse `dumpsys thermalservice`. Current HAL temperatures come after the
+/// cached ones, so a later reading of a sensor replaces an earlier one.
+pub fn parse_thermalservice(output: &str) -> ThermalSnapshot {
+    let mut snapshot = ThermalSnapshot::default();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(code) = line.strip_prefix("Thermal Status:") {
+            snapshot.status = code.trim().parse().ok().and_then(ThermalStatus::from_code);
+        } else if let Some(caps) = TEMPERATURE_REGEX.captures(line) {
+            let Ok(celsius) = caps[1].parse() else {
+                continue;
+            };
+            let name = caps[2].trim().to_string();
+            snapshot.temperatures.retain(|reading| reading.name != name);
+            snapshot.temperatures.push(ThermalReading { name, celsius });
+        }
+    }
+    snapshot
+}
+
+/// Parse `<zone type> <temp>` lines read from `/sys/class/thermal`; zones
+/// report millidegrees, a few older kernels whole degrees
+pub fn parse_thermal_zones(output: &str) -> Vec<ThermalReading> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.trim().rsplit_once(' ')?;
+            let value: f64 = value.parse().ok()?;
+            let celsius = if value.abs() >= 1000.0 { value / 1000.0 } else { value };
+            Some(ThermalReading {
+                name: name.trim().to_string(),
+                celsius,
+            })
+        })
+        .collect()
+}
+
+/// Payload of the `thermal-status` event
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalEvent {
+    pub device_id: String,
+    pub status: ThermalStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<ThermalStatus>,
+    /// Hottest sensor at the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hottest: Option<ThermalReading>,
+}
+
+impl ThermalEvent {
+    /// Note of the marker entry written into the stream
+    pub fn note(&self) -> String {
+        let mut note = format!("Thermal status: {}", self.status.label());
+        if let Some(previous) = self.previous {
+            note.push_str(&format!(" (was {})", previous.label()));
+        }
+        if let Some(hottest) = &self.hottest {
+            note.push_str(&format!(", {} {:.1}°C", hottest.name, hottest.celsius));
+        }
+        note
+    }
+}
+
+/// Turns successive snapshots into status change events
+#[derive(Debug)]
+pub struct ThermalMonitor {
+    device_id: String,
+    last: Option<ThermalStatus>,
+}
+
+impl ThermalMonitor {
+    pub fn new(device_id: &str) -> Self {
+        ThermalMonitor {
+            device_id: device_id.to_string(),
+            last: None,
+        }
+    }
+
+    /// An event when the status changed; the first snapshot only reports
+    /// throttling already under way
+    pub fn step(&mut self, snapshot: &ThermalSnapshot) -> Option<ThermalEvent> {
+        let status = snapshot.status?;
+        let previous = self.last.replace(status);
+        if previous == Some(status) || (previous.is_none() && status == ThermalStatus::None) {
+            return None;
+        }
+        Some(ThermalEvent {
+            device_id: self.device_id.clone(),
+            status,
+            previous,
+            hottest: snapshot.hottest().cloned(),
+        })
+    }
+}
+
+/// Poll a streaming device's thermal status every `poll_secs`, emitting
+/// `thermal-status` events (on the system events channel) and marker entries
+/// into its stream. Ends with the stream, once `sender` can no longer be upgraded.
+pub fn spawn(
+    app: AppHandle,
+    adb: &'static AdbManager,
+    device_id: String,
+    (sender, event_channels): (mpsc::WeakSender<LogEntry>, Arc<AtomicU8>),
+    poll_secs: u64,
+) {
+    tokio::spawn(async move {
+        info!("Sampling thermal status of {}", device_id);
+        let mut monitor = ThermalMonitor::new(&device_id);
+        let mut ticker = interval(Duration::from_secs(poll_secs.max(1)));
+        loop {
+            ticker.tick().await;
+            if sender.upgrade().is_none() {
+                break;
+            }
+            // Skip failed polls, e.g. while the device is briefly offline
+            let Ok(snapshot) = adb.thermal_snapshot(&device_id).await else {
+                continue;
+            };
+            let Some(event) = monitor.step(&snapshot) else {
+                continue;
+            };
+            let channels = ChannelMask::from_bits(event_channels.load(Ordering::Relaxed));
+            if channels.contains(ChannelMask::SYSTEM_EVENTS) {
+                if let Err(e) = app.emit("thermal-status", &event) {
+                    error!("Failed to emit thermal status: {}", e);
+                }
+            }
+            let Some(sender) = sender.upgrade() else {
+                break;
+            };
+            let marker = commands::marker_entry(&event.note(), chrono::Local::now());
+            let _ = sender.send(marker).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMPSYS: &str = "IsStatusOverride: false
+ThermalEventListeners:
+	callbacks: 1
+Thermal Status: 3
+Cached temperatures:
+	Temperature{mValue=41.0, mType=0, mName=cpu0, mStatus=0}
+HAL Ready: true
+Current temperatures from HAL:
+	Temperature{mValue=47.5, mType=0, mName=cpu0, mStatus=3}
+	Temperature{mValue=36.2, mType=2, mName=battery, mStatus=0}
+";
+
+    #[test]
+    fn test_parse_outputs() {
+        let snapshot = parse_thermalservice(DUMPSYS);
+        assert_eq!(snapshot.status, Some(ThermalStatus::Severe));
+        assert_eq!(snapshot.temperatures.len(), 2);
+        assert_eq!(snapshot.hottest().unwrap().celsius, 47.5);
+
+        let zones = parse_thermal_zones("battery 31500\nxo_therm 40\n");
+        assert_eq!(zones[0], ThermalReading { name: "battery".to_string(), celsius: 31.5 });
+        assert_eq!(zones[1].celsius, 40.0);
+    }
+
+    #[test]
+    fn test_monitor_reports_changes() {
+        let mut monitor = ThermalMonitor::new("dev");
+        let mut snapshot = ThermalSnapshot {
+            status: Some(ThermalStatus::None),
+            temperatures: Vec::new(),
+        };
+        assert_eq!(monitor.step(&snapshot), None);
+
+        snapshot = parse_thermalservice(DUMPSYS);
+        let event = monitor.step(&snapshot).unwrap();
+        assert_eq!(event.previous, Some(ThermalStatus::None));
+        assert_eq!(event.note(), "Thermal status: severe (was none), cpu0 47.5°C");
+        assert_eq!(monitor.step(&snapshot), None);
+
+        // Already throttling when sampling starts
+        let mut late = ThermalMonitor::new("dev");
+        assert_eq!(late.step(&snapshot).unwrap().previous, None);
+    }
+}
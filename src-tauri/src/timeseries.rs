@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+use crate::parser::{LogEntry, LogLevel};
+use crate::tagfamily::{self, FamilyNode};
+
+/// Series beyond this many (by volume) are folded into "other"
+const MAX_SERIES: usize = 20;
+
+/// Dimension entries are grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Level,
+    Tag,
+    /// Outermost tag family, e.g. "okhttp" for "okhttp.Http2"
+    TagFamily,
+    Pid,
+}
+
+impl GroupBy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "level" => Ok(GroupBy::Level),
+            "tag" => Ok(GroupBy::Tag),
+            "tagFamily" => Ok(GroupBy::TagFamily),
+            "pid" => Ok(GroupBy::Pid),
+            _ => Err(format!("Unknown group_by: {}", value)),
+        }
+    }
+}
+
+/// Compact per-entry record; tags are interned
+struct Sample {
+    epoch: u64,
+    level: LogLevel,
+    tag: u32,
+    pid: u32,
+}
+
+/// One group's counts, one value per bucket
+#[derive(Debug, Clone, Serialize)]
+pub struct Series {
+    pub key: String,
+    pub counts: Vec<u32>,
+}
+
+/// Bucketed entry counts for charting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeSeries {
+    pub bucket_ms: u64,
+    /// Epoch millis of the first bucket
+    pub start: u64,
+    pub series: Vec<Series>,
+}
+
+/// Bounded history of entry timestamps for one device
+pub struct TimeSeriesStore {
+    samples: VecDeque<Sample>,
+    tags: Vec<String>,
+    tag_ids: HashMap<String, u32>,
+    capacity: usize,
+}
+
+impl TimeSeriesStore {
+    pub fn new(capacity: usize) -> Self {
+        TimeSeriesStore {
+            samples: VecDeque::new(),
+            tags: Vec::new(),
+            tag_ids: HashMap::new(),
+            capacity,
+        }
+    }
+
+    pub fn observe(&mut self, entry: &LogEntry) {
+        let epoch = entry
+            .epoch
+            .or_else(|| entry.device_time_millis().map(|ms| ms as u64))
+            .unwrap_or_else(|| chrono::Local::now().timestamp_millis() as u64);
+        let tag = match self.tag_ids.get(&entry.tag) {
+            Some(id) => *id,
+            None => {
+                let id = self.tags.len() as u32;
+                self.tags.push(entry.tag.clone());
+                self.tag_ids.insert(entry.tag.clone(), id);
+                id
+            }
+        };
+
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            epoch,
+            level: entry.level,
+            tag,
+            pid: entry.pid,
+        });
+    }
+
+    /// Entry counts of the retained samples rolled up by tag family
+    pub fn tag_families(&self) -> Vec<FamilyNode> {
+        let mut counts = vec![0u64; self.tags.len()];
+        for sample in &self.samples {
+            counts[sample.tag as usize] += 1;
+        }
+        let counts = self
+            .tags
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(tag, count)| (tag.as_str(), count));
+        tagfamily::rollup(counts)
+    }
+
+    /// Aggregate samples in `[from, to)` (epoch millis) into buckets
+    pub fn aggregate(
+        &self,
+        bucket_ms: u64,
+        group_by: GroupBy,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> Result<TimeSeries, String> {
+        if bucket_ms == 0 {
+            return Err("bucket_ms must be greater than zero".to_string());
+        }
+        let in_range = |s: &&Sample| {
+            from.map_or(true, |f| s.epoch >= f) && to.map_or(true, |t| s.epoch < t)
+        };
+
+        let (min, max) = self
+            .samples
+            .iter()
+            .filter(in_range)
+            .fold((u64::MAX, 0), |(lo, hi), s| (lo.min(s.epoch), hi.max(s.epoch)));
+        if min > max {
+            return Ok(TimeSeries {
+                bucket_ms,
+                start: from.unwrap_or(0),
+                series: Vec::new(),
+            });
+        }
+        let start = from.unwrap_or(min) / bucket_ms * bucket_ms;
+        let buckets = ((max - start) / bucket_ms + 1) as usize;
+
+        let mut groups: HashMap<String, Vec<u32>> = HashMap::new();
+        for sample in self.samples.iter().filter(in_range) {
+            let key = match group_by {
+                GroupBy::Level => format!("{:?}", sample.level),
+                GroupBy::Tag => self.tags[sample.tag as usize].clone(),
+                GroupBy::TagFamily => {
+                    let tag = &self.tags[sample.tag as usize];
+                    tagfamily::families(tag).first().unwrap_or(&tag.as_str()).to_string()
+                }
+                GroupBy::Pid => sample.pid.to_string(),
+            };
+            let counts = groups.entry(key).or_insert_with(|| vec![0; buckets]);
+            counts[((sample.epoch - start) / bucket_ms) as usize] += 1;
+        }
+
+        let mut series: Vec<Series> = groups
+            .into_iter()
+            .map(|(key, counts)| Series { key, counts })
+            .collect();
+        series.sort_by_key(|s| std::cmp::Reverse(s.counts.iter().sum::<u32>()));
+        if series.len() > MAX_SERIES {
+            let mut other = vec![0; buckets];
+            for s in series.drain(MAX_SERIES..) {
+                for (total, count) in other.iter_mut().zip(s.counts) {
+                    *total += count;
+                }
+            }
+            series.push(Series {
+                key: "other".to_string(),
+                counts: other,
+            });
+        }
+
+        Ok(TimeSeries {
+            bucket_ms,
+            start,
+            series,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    fn entry(parser: &mut LogParser, line: &str, epoch: u64) -> LogEntry {
+        let mut entry = parser.parse_line(line).unwrap();
+        entry.epoch = Some(epoch);
+        entry
+    }
+
+    #[test]
+    fn test_aggregate_by_level() {
+        let mut parser = LogParser::new();
+        let mut store = TimeSeriesStore::new(100);
+        store.observe(&entry(&mut parser, "12-04 10:30:45.123  1  1 I A: x", 1_000));
+        store.observe(&entry(&mut parser, "12-04 10:30:45.123  1  1 E A: x", 1_500));
+        store.observe(&entry(&mut parser, "12-04 10:30:45.123  1  1 E B: x", 3_200));
+
+        let ts = store.aggregate(1_000, GroupBy::Level, None, None).unwrap();
+        assert_eq!(ts.start, 1_000);
+        assert_eq!(ts.series[0].key, "E");
+        assert_eq!(ts.series[0].counts, vec![1, 0, 1]);
+        assert_eq!(ts.series[1].counts, vec![1, 0, 0]);
+
+        let zoomed = store.aggregate(100, GroupBy::Tag, Some(3_000), None).unwrap();
+        assert_eq!(zoomed.series.len(), 1);
+        assert_eq!(zoomed.series[0].key, "B");
+        assert_eq!(zoomed.series[0].counts, vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_capacity_bound() {
+        let mut parser = LogParser::new();
+        let mut store = TimeSeriesStore::new(2);
+        for epoch in [0, 10, 20] {
+            store.observe(&entry(&mut parser, "12-04 10:30:45.123  1  1 I A: x", epoch));
+        }
+        let ts = store.aggregate(10, GroupBy::Pid, None, None).unwrap();
+        assert_eq!(ts.start, 10);
+        assert_eq!(ts.series[0].counts, vec![1, 1]);
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::parser::LogEntry;
+
+/// Timezone entry times are shown in by history queries and exports
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayTimezone {
+    /// As printed by the device
+    #[default]
+    Device,
+    /// The machine running the app
+    Host,
+    Utc,
+}
+
+impl DisplayTimezone {
+    fn convert(self, time: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self {
+            DisplayTimezone::Device => time,
+            DisplayTimezone::Host => time.with_timezone(&Local).fixed_offset(),
+            DisplayTimezone::Utc => time.with_timezone(&Utc).fixed_offset(),
+        }
+    }
+
+    /// Rewrite the entry's time strings (and the timestamp in its raw line) in
+    /// this timezone. Entries without a structured time are left unchanged.
+    pub fn apply(self, entry: &mut LogEntry) {
+        let Some(time) = entry.time else {
+            return;
+        };
+        let converted = self.convert(time);
+        if converted.offset() == time.offset() {
+            return;
+        }
+        let timestamp = converted.format("%H:%M:%S%.3f").to_string();
+        let date_time = format!("{} {}", converted.format("%m-%d"), timestamp);
+        if let (Some(raw), Some(old)) = (entry.raw.as_mut(), entry.date_time.as_deref()) {
+            if raw.contains(old) {
+                *raw = raw.replacen(old, &date_time, 1);
+            }
+        }
+        entry.time = Some(converted);
+        entry.timestamp = timestamp;
+        entry.date_time = Some(date_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::LogParser;
+
+    #[test]
+    fn test_apply_converts_strings_and_raw() {
+        let mut parser = LogParser::with_offset(FixedOffset::east_opt(8 * 3600).unwrap());
+        let line = "01-02 03:04:05.678  1234  1234 I Tag: message";
+        let mut entry = parser.parse_line(line).unwrap();
+        let epoch = entry.epoch;
+
+        DisplayTimezone::Utc.apply(&mut entry);
+        assert_eq!(entry.timestamp, "19:04:05.678");
+        assert_eq!(entry.date_time.as_deref(), Some("01-01 19:04:05.678"));
+        assert_eq!(entry.raw.as_deref(), Some("01-01 19:04:05.678  1234  1234 I Tag: message"));
+        assert_eq!(entry.epoch, epoch);
+
+        let mut unchanged = parser.parse_line(line).unwrap();
+        DisplayTimezone::Device.apply(&mut unchanged);
+        assert_eq!(unchanged.raw.as_deref(), Some(line));
+    }
+}
@@ -0,0 +1,149 @@
+use log::{error, info};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::menu::{Menu, MenuEvent, MenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tokio::time::{interval, Duration};
+
+use crate::commands::{LogcatState, MAIN_WINDOW};
+
+/// Tray icon ID
+const TRAY_ID: &str = "main";
+
+/// How often the device count and alert badge are refreshed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Menu items updated while the app runs, plus alerts not yet seen
+pub struct TrayMenu {
+    devices: MenuItem<Wry>,
+    pause: MenuItem<Wry>,
+    unseen_alerts: AtomicUsize,
+}
+
+/// Label of the device count item
+fn devices_label(count: usize) -> String {
+    format!("活动设备：{}", count)
+}
+
+/// Tooltip with an alert badge when alerts fired while the window was hidden
+fn tooltip(count: usize, alerts: usize) -> String {
+    match alerts {
+        0 => format!("Logcat - {} 台设备采集中", count),
+        n => format!("Logcat - {} 台设备采集中，{} 条新告警", count, n),
+    }
+}
+
+/// Build the tray icon and start refreshing its menu
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let devices = MenuItem::with_id(app, "devices", devices_label(0), false, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "pause", "暂停采集", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&devices, &pause, &show, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(tooltip(0, 0))
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu {
+        devices,
+        pause,
+        unseen_alerts: AtomicUsize::new(0),
+    });
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(REFRESH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            refresh(&app_handle).await;
+        }
+    });
+    Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id.as_ref() {
+        "pause" => {
+            let paused = !app.state::<LogcatState>().paused.load(Ordering::Relaxed);
+            set_paused(app, paused);
+        }
+        "show" => show_main_window(app),
+        "quit" => {
+            info!("Quit requested from tray");
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
+
+/// Pause or resume capture on every stream and sync the menu and frontend
+pub fn set_paused(app: &AppHandle, paused: bool) {
+    info!("Capture {}", if paused { "paused" } else { "resumed" });
+    app.state::<LogcatState>().paused.store(paused, Ordering::Relaxed);
+    if let Some(tray) = app.try_state::<TrayMenu>() {
+        let label = if paused { "恢复采集" } else { "暂停采集" };
+        if let Err(e) = tray.pause.set_text(label) {
+            error!("Failed to update tray menu: {}", e);
+        }
+    }
+    if let Err(e) = app.emit("capture-paused", paused) {
+        error!("Failed to emit pause state: {}", e);
+    }
+}
+
+/// Bring the main window back and clear the alert badge
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    if let Some(tray) = app.try_state::<TrayMenu>() {
+        tray.unseen_alerts.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Count an alert toward the badge when the main window is hidden
+pub fn note_alert(app: &AppHandle) {
+    let hidden = app
+        .get_webview_window(MAIN_WINDOW)
+        .and_then(|w| w.is_visible().ok())
+        .map_or(true, |visible| !visible);
+    if !hidden {
+        return;
+    }
+    if let Some(tray) = app.try_state::<TrayMenu>() {
+        tray.unseen_alerts.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+async fn refresh(app: &AppHandle) {
+    let Some(tray) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let count = app.state::<LogcatState>().streams.lock().await.len();
+    let alerts = tray.unseen_alerts.load(Ordering::Relaxed);
+    if let Err(e) = tray.devices.set_text(devices_label(count)) {
+        error!("Failed to update tray menu: {}", e);
+    }
+    if let Some(icon) = app.tray_by_id(TRAY_ID) {
+        let _ = icon.set_tooltip(Some(tooltip(count, alerts)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tooltip_badge() {
+        assert_eq!(tooltip(2, 0), "Logcat - 2 台设备采集中");
+        assert_eq!(tooltip(1, 3), "Logcat - 1 台设备采集中，3 条新告警");
+    }
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::parser::LogLevel;
+
+/// Sub-directory of the config dir holding one JSON file per workspace
+pub const WORKSPACES_DIR: &str = "workspaces";
+
+/// A named filter query (Android Studio syntax)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedFilter {
+    pub name: String,
+    pub query: String,
+}
+
+/// Highlight matching lines with a color
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub is_regex: bool,
+    pub color: String,
+}
+
+/// Notify when a matching entry arrives
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub is_regex: bool,
+    #[serde(default)]
+    pub min_level: Option<LogLevel>,
+}
+
+/// Per-project bundle of filters, highlights, aliases, mappings and alerts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Workspace {
+    pub name: String,
+    pub saved_filters: Vec<SavedFilter>,
+    pub highlight_rules: Vec<HighlightRule>,
+    /// Device serial -> display name
+    pub device_aliases: HashMap<String, String>,
+    /// Package name -> ProGuard/R8 mapping file path
+    pub mapping_files: HashMap<String, String>,
+    pub alert_rules: Vec<AlertRule>,
+}
+
+/// Reject names that could escape the workspaces directory
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.trim().is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.'))
+        && !name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid workspace name: {}", name))
+    }
+}
+
+fn workspace_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    validate_name(name)?;
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// List workspace names in `dir`, sorted
+pub fn list(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let path = e.path();
+                    if path.extension()? != "json" {
+                        return None;
+                    }
+                    Some(path.file_stem()?.to_string_lossy().to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Load a workspace by name
+pub fn load(dir: &Path, name: &str) -> Result<Workspace, String> {
+    let path = workspace_path(dir, name)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read workspace {}: {}", name, e))?;
+    let mut workspace: Workspace =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid workspace {}: {}", name, e))?;
+    workspace.name = name.to_string();
+    Ok(workspace)
+}
+
+/// Save (create or overwrite) a workspace
+pub fn save(dir: &Path, workspace: &Workspace) -> Result<(), String> {
+    let path = workspace_path(dir, &workspace.name)?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create workspaces dir: {}", e))?;
+    let text = serde_json::to_string_pretty(workspace).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("Failed to save workspace: {}", e))
+}
+
+/// Delete a workspace by name
+pub fn delete(dir: &Path, name: &str) -> Result<(), String> {
+    let path = workspace_path(dir, name)?;
+    std::fs::remove_file(path).map_err(|e| format!("Failed to delete workspace {}: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("My App").is_ok());
+        assert!(validate_name("app-2_debug").is_ok());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("logcat-ws-test-{}", std::process::id()));
+        let mut workspace = Workspace {
+            name: "demo".to_string(),
+            ..Default::default()
+        };
+        workspace.saved_filters.push(SavedFilter {
+            name: "Network".to_string(),
+            query: "tag:OkHttp level:WARN".to_string(),
+        });
+        workspace
+            .device_aliases
+            .insert("emulator-5554".to_string(), "Pixel Emu".to_string());
+
+        save(&dir, &workspace).unwrap();
+        assert_eq!(list(&dir), vec!["demo".to_string()]);
+        assert_eq!(load(&dir, "demo").unwrap(), workspace);
+        delete(&dir, "demo").unwrap();
+        assert!(list(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
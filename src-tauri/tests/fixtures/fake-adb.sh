@@ -0,0 +1,46 @@
+#!/bin/sh
+# Stand-in for adb serving canned output, so device listing, process
+# enrichment and streaming can be tested without hardware (see adb.rs tests).
+# Arguments arrive as adb gets them: [-s SERIAL] COMMAND ARGS...
+
+if [ "$1" = "-s" ]; then
+    shift 2
+fi
+
+case "$*" in
+    "version")
+        echo "Android Debug Bridge version 1.0.41"
+        ;;
+    "devices -l")
+        echo "List of devices attached"
+        echo "emulator-5554          device product:sdk_gphone64 model:sdk_gphone64_x86_64 device:emu64x transport_id:1"
+        echo "R58M123ABC             unauthorized usb:1-1 transport_id:2"
+        ;;
+    "shell getprop ro.build.version.sdk; getprop ro.build.version.release")
+        printf '34\n14\n'
+        ;;
+    "shell ps -A -o PID,NAME")
+        echo "  PID NAME"
+        echo "    1 init"
+        echo " 4321 com.example.app"
+        echo " 4322 com.example.app:sync"
+        ;;
+    "shell date +%z")
+        echo "+0800"
+        ;;
+    "shell cat /proc/sys/kernel/random/boot_id")
+        echo "6f1c2a52-0c4e-4d7e-9a43-2b8f1f0c9d11"
+        ;;
+    "logcat -c")
+        ;;
+    logcat\ *)
+        echo "--------- beginning of main"
+        echo "12-04 10:30:45.123  4321  4321 I MainActivity: onCreate"
+        echo "12-04 10:30:45.200  4322  4330 D SyncAdapter: sync started"
+        echo "12-04 10:30:45.300     1     1 W init: service restarted"
+        ;;
+    *)
+        echo "fake adb: unsupported command: $*" >&2
+        exit 1
+        ;;
+esac
@@ -1,4 +1,5 @@
 import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { Toolbar } from "./components/Toolbar";
 import { LogList } from "./components/LogList";
 import { StatusBar } from "./components/StatusBar";
@@ -7,8 +8,14 @@ import { useLogStore } from "./stores/logStore";
 import { useDeviceMonitor } from "./hooks/useDeviceMonitor";
 import { useAutoSelectDevice } from "./hooks/useAutoSelectDevice";
 
+interface MarkerResult {
+  note: string;
+  deviceIds: string[];
+  screenshots: string[];
+}
+
 function App() {
-  const { settings } = useLogStore();
+  const { settings, setNotice } = useLogStore();
 
   // Monitor device connection/disconnection events
   useDeviceMonitor();
@@ -25,6 +32,26 @@ function App() {
     }
   }, [settings.theme]);
 
+  // Cmd/Ctrl+Shift+M marks "the bug just happened" in every active stream,
+  // with a screenshot of each device
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!(e.metaKey || e.ctrlKey) || !e.shiftKey || e.key.toLowerCase() !== "m") return;
+      e.preventDefault();
+      invoke<MarkerResult>("insert_marker")
+        .then((result) =>
+          setNotice(
+            result.deviceIds.length > 0
+              ? `已插入标记「${result.note}」，截图 ${result.screenshots.length} 张`
+              : "没有正在采集的设备，未插入标记"
+          )
+        )
+        .catch((error) => console.error("Failed to insert marker:", error));
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [setNotice]);
+
   return (
     <div className="h-full flex flex-col bg-surface transition-theme">
       {/* Top Toolbar with Filter */}
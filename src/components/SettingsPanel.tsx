@@ -307,6 +307,24 @@ export function SettingsPanel({ isOpen, onClose }: SettingsPanelProps) {
             </p>
           </div>
 
+          {/* Device-side Filter */}
+          <div className="space-y-2">
+            <label className="flex items-center justify-between cursor-pointer">
+              <span className="text-sm text-text-primary font-medium">设备端过滤</span>
+              <input
+                type="checkbox"
+                checked={settings.deviceSideFilter}
+                onChange={(e) =>
+                  updateSettings({ deviceSideFilter: e.target.checked })
+                }
+                className="w-4 h-4 accent-accent cursor-pointer"
+              />
+            </label>
+            <p className="text-xs text-text-muted">
+              开始采集时按当前筛选的 tag/level 让设备只发送相关日志，节省 USB 带宽；之后放宽筛选需重新开始采集
+            </p>
+          </div>
+
           {/* Reset */}
           <div className="pt-4 border-t border-border">
             <button
@@ -326,6 +344,7 @@ export function SettingsPanel({ isOpen, onClose }: SettingsPanelProps) {
                   showTag: true,
                   hideRepeatedTags: false,
                   maxLogLines: 100000,
+                  deviceSideFilter: false,
                 });
               }}
               className="w-full px-4 py-2 text-sm text-text-secondary hover:text-text-primary bg-surface-secondary hover:bg-surface rounded-md transition-colors"
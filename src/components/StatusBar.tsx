@@ -9,6 +9,8 @@ export function StatusBar() {
     stats,
     isPaused,
     isConnected,
+    notice,
+    setNotice,
   } = useLogStore();
 
   // 缓存级别统计的渲染，避免每次 stats 更新都重新计算
@@ -68,6 +70,17 @@ export function StatusBar() {
           {levelCounts}
         </div>
       </div>
+
+      {/* Right: Notice */}
+      {notice && (
+        <button
+          className="text-log-warn truncate max-w-[50%] hover:underline"
+          title="点击关闭"
+          onClick={() => setNotice(null)}
+        >
+          {notice}
+        </button>
+      )}
     </div>
   );
 }
@@ -4,7 +4,7 @@ import { useLogStream } from './useLogStream';
 
 /**
  * Hook to automatically select and connect to a device on startup
- * Priority: lastSelectedDevice > first available device
+ * Priority: window's device (?device=) > lastSelectedDevice > first available device
  */
 export function useAutoSelectDevice() {
   const {
@@ -26,11 +26,14 @@ export function useAutoSelectDevice() {
   useEffect(() => {
     // Conditions: has devices, not connected, no device selected, no imported file, not already auto-selected
     if (devices.length > 0 && !isConnected && !selectedDevice && !importedFileName && !hasAutoSelectedRef.current) {
-      const targetDevice =
-        // Priority 1: Select last selected device if still online
-        devices.find(d => d.id === lastSelectedDeviceId && d.state === 'device') ||
-        // Priority 2: Select first available device
-        devices.find(d => d.state === 'device');
+      // Device windows are opened with ?device=<id> and only stream that device
+      const windowDeviceId = new URLSearchParams(window.location.search).get('device');
+      const targetDevice = windowDeviceId
+        ? devices.find(d => d.id === windowDeviceId && d.state === 'device')
+        : // Priority 1: Select last selected device if still online
+          devices.find(d => d.id === lastSelectedDeviceId && d.state === 'device') ||
+          // Priority 2: Select first available device
+          devices.find(d => d.state === 'device');
 
       if (targetDevice) {
         console.log(`自动选择设备: ${targetDevice.name}`);
@@ -1,16 +1,54 @@
 import { useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { useLogStore } from '../stores/logStore';
 import { useLogStream } from './useLogStream';
 import type { Device } from '../types';
 
 interface DeviceEvent {
-  type: 'connected' | 'disconnected' | 'listUpdated';
+  type: 'connected' | 'disconnected' | 'listUpdated' | 'unauthorized' | 'authorized';
   device?: Device;
   deviceId?: string;
   devices?: Device[];
 }
 
+interface StreamAutostarted {
+  deviceId: string;
+  reason: 'pinned' | 'authorized' | 'profile';
+  packageName?: string;
+  filter?: string;
+}
+
+interface PackageFollowEvent {
+  deviceId: string;
+  packageName: string;
+  kind: 'installed' | 'uninstalled' | 'firstLaunch' | 'started' | 'pidChanged' | 'died';
+  pid?: number;
+  previousPid?: number;
+}
+
+interface CrashLoop {
+  deviceId: string;
+  packageName: string;
+  windowMins: number;
+  crashes: unknown[];
+  folder?: string;
+}
+
+interface CrashLoopExported {
+  deviceId: string;
+  packageName: string;
+  folder: string;
+  bugreport?: string;
+  error?: string;
+}
+
+const AUTOSTART_REASONS: Record<StreamAutostarted['reason'], string> = {
+  pinned: '置顶设备已连接',
+  authorized: '设备已授权',
+  profile: '启动配置匹配的设备已连接',
+};
+
 /**
  * Hook to monitor device connection/disconnection events from backend
  * Automatically handles device reconnection and adds system markers
@@ -21,8 +59,9 @@ export function useDeviceMonitor() {
     isConnected,
     addDeviceMarker,
     setDevices,
+    setNotice,
   } = useLogStore();
-  const { stopLogcat, startLogcat } = useLogStream();
+  const { stopLogcat, startLogcat, attachLogcat } = useLogStream();
 
   useEffect(() => {
     const unlisten = listen<DeviceEvent>('device-event', async (event) => {
@@ -64,6 +103,20 @@ export function useDeviceMonitor() {
           }
           break;
 
+        case 'unauthorized':
+          if (deviceId) {
+            console.log(`设备等待授权: ${deviceId}`);
+            setNotice(`设备 ${deviceId} 等待授权，请在设备上允许 USB 调试`);
+          }
+          break;
+
+        case 'authorized':
+          if (device) {
+            console.log(`设备已授权: ${device.name}`);
+            setNotice(`设备已授权: ${device.name}`);
+          }
+          break;
+
         case 'listUpdated':
           if (deviceList) {
             // 更新设备列表
@@ -76,5 +129,106 @@ export function useDeviceMonitor() {
     return () => {
       unlisten.then(fn => fn());
     };
-  }, [selectedDevice, isConnected, addDeviceMarker, stopLogcat, startLogcat, setDevices]);
+  }, [selectedDevice, isConnected, addDeviceMarker, stopLogcat, startLogcat, setDevices, setNotice]);
+
+  // 后端自动开始的日志流（置顶设备、授权后、启动配置）都在主窗口，接入后再应用其过滤条件
+  useEffect(() => {
+    if (getCurrentWindow().label !== 'main') return;
+    const unlisten = listen<StreamAutostarted>('stream-autostarted', async (event) => {
+      const { deviceId, reason, filter } = event.payload;
+      console.log(`自动开始日志流: ${deviceId} (${reason})`);
+      try {
+        await attachLogcat(deviceId);
+      } catch {
+        return;
+      }
+      const state = useLogStore.getState();
+      state.addDeviceMarker(
+        deviceId,
+        `${AUTOSTART_REASONS[reason]}，已自动开始日志流 (${new Date().toLocaleTimeString()})`,
+        'autostart'
+      );
+      if (reason === 'authorized') {
+        state.setNotice(`设备已授权，已自动开始日志流: ${deviceId}`);
+      }
+      if (filter) {
+        state.setFilter({ searchText: filter });
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [attachLogcat]);
+
+  // 设备组操作：后端只把过滤条件发给正在显示该组设备的窗口
+  useEffect(() => {
+    const unlisten = listen<string>('apply-filter', (event) => {
+      useLogStore.getState().setFilter({ searchText: event.payload });
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // 跟随的包重启或重装后进程 PID 改变：按该包进程筛选时换成新 PID
+  useEffect(() => {
+    const unlisten = listen<PackageFollowEvent>('package-follow', (event) => {
+      const { deviceId, packageName, pid, previousPid } = event.payload;
+      const state = useLogStore.getState();
+      if (pid === undefined || deviceId !== state.selectedDevice?.id) return;
+
+      const pids = state.filter.pids ?? [];
+      if (state.selectedProcess?.name === packageName) {
+        state.selectProcess({ ...state.selectedProcess, pid });
+      } else if (previousPid !== undefined && pids.includes(previousPid)) {
+        state.setFilter({ pids: pids.map((p) => (p === previousPid ? pid : p)) });
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // 崩溃循环：后端已保存现场，随后导出 bugreport；在显示该设备的窗口提示
+  useEffect(() => {
+    const unlistenLoop = listen<CrashLoop>('crash-loop', (event) => {
+      const { deviceId, packageName, windowMins, crashes, folder } = event.payload;
+      const state = useLogStore.getState();
+      if (deviceId !== state.selectedDevice?.id) return;
+      const message = `${packageName} 在 ${windowMins} 分钟内崩溃 ${crashes.length} 次`;
+      state.addDeviceMarker(deviceId, `${message}，现场已保存${folder ? `到 ${folder}` : ''}`, 'crash');
+      state.setNotice(`崩溃循环: ${message}`);
+    });
+    const unlistenExported = listen<CrashLoopExported>('crash-loop-exported', (event) => {
+      const { deviceId, packageName, folder, bugreport, error } = event.payload;
+      const state = useLogStore.getState();
+      if (deviceId !== state.selectedDevice?.id) return;
+      state.setNotice(
+        error
+          ? `${packageName} 崩溃循环的 bugreport 导出失败: ${error}`
+          : `${packageName} 崩溃循环已导出到 ${bugreport ?? folder}`
+      );
+    });
+
+    return () => {
+      unlistenLoop.then(fn => fn());
+      unlistenExported.then(fn => fn());
+    };
+  }, []);
+
+  // 置顶条目：后端维护置顶列表，过滤时始终保留这些条目
+  useEffect(() => {
+    const unlisten = listen<{ deviceId: string; entryIds: number[] }>('pins-changed', (event) => {
+      if (event.payload.deviceId === useLogStore.getState().selectedDevice?.id) {
+        useLogStore.getState().setFilter({ pinnedIds: event.payload.entryIds });
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
 }
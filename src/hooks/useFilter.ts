@@ -97,7 +97,7 @@ export function useFilter(): UseFilterReturn {
   // Set PID filter
   const setPid = useCallback(
     (pid: number | undefined) => {
-      setFilter({ pid });
+      setFilter({ pids: pid === undefined ? [] : [pid] });
     },
     [setFilter]
   );
@@ -1,7 +1,9 @@
-import { useEffect, useCallback, useRef } from "react";
+import { useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import { useLogStore } from "../stores/logStore";
+import { buildFilterSpecs } from "../lib/utils";
 import type { LogEntry, Device, ProcessInfo } from "../types";
 
 interface UseLogStreamReturn {
@@ -10,13 +12,70 @@ interface UseLogStreamReturn {
   devices: Device[];
   selectedDevice: Device | null;
   processes: ProcessInfo[];
-  startLogcat: (deviceId: string) => Promise<void>;
+  startLogcat: (deviceId: string, filterSpecs?: string[]) => Promise<void>;
+  attachLogcat: (deviceId: string) => Promise<void>;
   stopLogcat: () => Promise<void>;
   refreshDevices: () => Promise<void>;
   refreshProcesses: (deviceId: string) => Promise<void>;
   clearDeviceLogs: (deviceId: string) => Promise<void>;
 }
 
+// One entries listener per window, shared by every component using the hook
+let entriesUnlisten: UnlistenFn | null = null;
+
+function releaseEntries() {
+  if (entriesUnlisten) {
+    entriesUnlisten();
+    entriesUnlisten = null;
+  }
+}
+
+// Listen for the window's entry batches. Detached device windows get their own channel.
+async function listenEntries(onBatch: (entries: LogEntry[]) => void) {
+  releaseEntries();
+  const label = getCurrentWindow().label;
+  const channel = label === "main" ? "logcat-entries" : `logcat-entries:${label}`;
+  const unlisten = await listen<LogEntry[]>(channel, (event) => onBatch(event.payload));
+  releaseEntries();
+  entriesUnlisten = unlisten;
+}
+
+// Stream the device's entries into the store. Batches arriving while `begin`
+// runs are held back and added after the backlog it returns, skipping entries
+// already shown.
+async function streamInto(deviceId: string, begin: () => Promise<LogEntry[]>) {
+  let held: LogEntry[] | null = [];
+  await listenEntries((entries) => {
+    if (held) {
+      held.push(...entries);
+    } else {
+      useLogStore.getState().addLogsForDevice(deviceId, entries);
+    }
+  });
+
+  const backlog = await begin();
+  const state = useLogStore.getState();
+  const shown = state.deviceLogs.get(deviceId)?.logs ?? [];
+  const seen = new Set(shown.map((entry) => entry.id));
+  const fresh = [...backlog, ...held].filter((entry) => {
+    if (seen.has(entry.id)) return false;
+    seen.add(entry.id);
+    return true;
+  });
+  state.addLogsForDevice(deviceId, fresh);
+  held = null;
+}
+
+// Start/attach/stop run one at a time, so an autostarted stream being attached
+// and a user or auto-select start can't swap each other's listener
+let streamOps: Promise<unknown> = Promise.resolve();
+
+function serialized<T>(op: () => Promise<T>): Promise<T> {
+  const next = streamOps.then(op, op);
+  streamOps = next.catch(() => undefined);
+  return next;
+}
+
 export function useLogStream(): UseLogStreamReturn {
   const {
     devices,
@@ -33,8 +92,6 @@ export function useLogStream(): UseLogStreamReturn {
     clearLogs,
   } = useLogStore();
 
-  const unlistenRef = useRef<UnlistenFn | null>(null);
-
   // Refresh device list
   const refreshDevices = useCallback(async () => {
     setLoading(true);
@@ -65,25 +122,18 @@ export function useLogStream(): UseLogStreamReturn {
 
   // Start logcat for a device
   const startLogcat = useCallback(
-    async (deviceId: string) => {
+    (deviceId: string, filterSpecs?: string[]) => serialized(async () => {
       try {
-        // Clean up existing listener
-        if (unlistenRef.current) {
-          unlistenRef.current();
-          unlistenRef.current = null;
-        }
-
-        // Set up listener for log entries - use addLogsForDevice with deviceId
-        unlistenRef.current = await listen<LogEntry[]>(
-          "logcat-entries",
-          (event) => {
-            const state = useLogStore.getState();
-            state.addLogsForDevice(deviceId, event.payload);
-          }
-        );
-
-        // Start logcat on backend
-        await invoke("start_logcat", { deviceId });
+        // Start logcat on backend; filterSpecs narrow the stream on the device,
+        // built from the current query when device-side filtering is on.
+        // A stream the backend already started for this window is attached to.
+        const { settings, filter } = useLogStore.getState();
+        const specs =
+          filterSpecs ?? (settings.deviceSideFilter ? buildFilterSpecs(filter.searchText) : undefined);
+        await streamInto(deviceId, async () => {
+          const attached = await invoke<boolean>("start_logcat", { deviceId, filterSpecs: specs });
+          return attached ? invoke<LogEntry[]>("attach_logcat", { deviceId }) : [];
+        });
         setConnected(true);
 
         // Switch to device - use switchToDevice to load history
@@ -94,28 +144,46 @@ export function useLogStream(): UseLogStreamReturn {
         await refreshProcesses(deviceId);
       } catch (error) {
         console.error("Failed to start logcat:", error);
+        releaseEntries();
         setConnected(false);
         throw error;
       }
-    },
+    }),
+    [setConnected, refreshProcesses]
+  );
+
+  // Attach to a stream the backend started on its own (pinned device, launch
+  // profile...), adding the entries it already streamed
+  const attachLogcat = useCallback(
+    (deviceId: string) => serialized(async () => {
+      try {
+        await streamInto(deviceId, () => invoke<LogEntry[]>("attach_logcat", { deviceId }));
+        setConnected(true);
+        useLogStore.getState().switchToDevice(deviceId);
+
+        await refreshProcesses(deviceId);
+      } catch (error) {
+        console.error("Failed to attach to logcat:", error);
+        releaseEntries();
+        setConnected(false);
+        throw error;
+      }
+    }),
     [setConnected, refreshProcesses]
   );
 
   // Stop logcat
-  const stopLogcat = useCallback(async () => {
+  const stopLogcat = useCallback(() => serialized(async () => {
     try {
       await invoke("stop_logcat");
       setConnected(false);
 
       // Clean up listener
-      if (unlistenRef.current) {
-        unlistenRef.current();
-        unlistenRef.current = null;
-      }
+      releaseEntries();
     } catch (error) {
       console.error("Failed to stop logcat:", error);
     }
-  }, [setConnected]);
+  }), [setConnected]);
 
   // Clear device logs
   const clearDeviceLogs = useCallback(
@@ -130,14 +198,6 @@ export function useLogStream(): UseLogStreamReturn {
     [clearLogs]
   );
 
-  useEffect(() => {
-    return () => {
-      if (unlistenRef.current) {
-        unlistenRef.current();
-      }
-    };
-  }, []);
-
   return {
     isConnected,
     isLoading,
@@ -145,6 +205,7 @@ export function useLogStream(): UseLogStreamReturn {
     selectedDevice,
     processes,
     startLogcat,
+    attachLogcat,
     stopLogcat,
     refreshDevices,
     refreshProcesses,
@@ -502,6 +502,32 @@ function matchOrGroup(value: string, group: OrGroup): boolean {
   return true;
 }
 
+// Build logcat filterspecs (TAG:LEVEL *:S) that narrow the device stream
+// without dropping anything the query would show. Returns undefined when the
+// query can't be expressed safely; host-side filtering always still applies.
+export function buildFilterSpecs(query: string): string[] | undefined {
+  if (query.includes("|")) {
+    return undefined;
+  }
+  const parsed = parseLogcatQuery(query);
+  const level = parsed.minLevel ? parsed.minLevel.charAt(0).replace("A", "F") : "V";
+
+  if (parsed.tagGroups.length === 1) {
+    const conditions = parsed.tagGroups[0].conditions;
+    // Tags logcat can't take as a filterspec are left to host-side filtering
+    const exactTags = conditions.every(
+      (c) => c.mode === "exact" && !c.exclude && /^[^\s:*]+$/.test(c.value)
+    );
+    if (exactTags) {
+      return [...conditions.map((c) => `${c.value}:${level}`), "*:S"];
+    }
+  }
+  if (parsed.minLevel) {
+    return [`*:${level}`];
+  }
+  return undefined;
+}
+
 // Check if log entry matches parsed query
 export function matchesQuery(
   entry: import("../types").LogEntry,
@@ -633,6 +659,7 @@ import type {
   LogEntry, 
   LogLevel, 
   Device,
+  SearchField,
   AndroidStudioLogcatFile, 
   AndroidStudioLogMessage 
 } from "../types";
@@ -894,3 +921,21 @@ export function importLogs(content: string, filename: string): {
   return { logs: [], format: "unknown" };
 }
 
+// Values the search regex is tested against, one per field
+export function searchTargets(log: LogEntry, fields: SearchField[] = []): string[] {
+  const selected: SearchField[] = fields.length ? fields : ["all"];
+  return selected.flatMap((field) => {
+    switch (field) {
+      case "message":
+        return [log.message];
+      case "tag":
+        return [log.tag];
+      case "process":
+        return log.processName ? [log.processName] : [];
+      case "raw":
+        return [log.raw ?? `${log.timestamp} ${log.pid} ${log.tid} ${log.level} ${log.tag}: ${log.message}`];
+      case "all":
+        return [log.message, log.tag, ...(log.processName ? [log.processName] : [])];
+    }
+  });
+}
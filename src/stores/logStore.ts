@@ -16,7 +16,7 @@ import {
   DEFAULT_FILTER,
   DEFAULT_SETTINGS,
 } from "../types";
-import { createSearchRegex, generateId, parseLogcatQuery, matchesQuery } from "../lib/utils";
+import { createSearchRegex, generateId, parseLogcatQuery, matchesQuery, searchTargets } from "../lib/utils";
 
 interface LogState {
   // Logs - 多设备日志存储
@@ -46,6 +46,7 @@ interface LogState {
   isConnected: boolean;
   isLoading: boolean;
   autoScroll: boolean;
+  notice: string | null;  // 状态栏提示（设备授权、自动开始等），点击关闭
 
   // Settings
   settings: AppSettings;
@@ -65,7 +66,7 @@ interface LogState {
   setDevices: (devices: Device[]) => void;
   selectDevice: (device: Device | null) => void;
   switchToDevice: (deviceId: string) => void;  // 新增：切换到设备
-  addDeviceMarker: (deviceId: string, message: string, type: 'disconnect' | 'reconnect') => void;  // 新增：添加设备标注
+  addDeviceMarker: (deviceId: string, message: string, type: 'disconnect' | 'reconnect' | 'autostart' | 'crash') => void;  // 新增：添加设备标注
   
   // Actions - Processes
   setProcesses: (processes: ProcessInfo[]) => void;
@@ -89,6 +90,7 @@ interface LogState {
   setConnected: (connected: boolean) => void;
   setLoading: (loading: boolean) => void;
   setAutoScroll: (autoScroll: boolean) => void;
+  setNotice: (notice: string | null) => void;
   
   // Actions - Settings
   updateSettings: (settings: Partial<AppSettings>) => void;
@@ -105,7 +107,14 @@ function filterLogs(logs: LogEntry[], filter: FilterConfig): LogEntry[] {
     ? createSearchRegex(parsedQuery.text, true, filter.isCaseSensitive)
     : null;
   
+  const pinned = new Set(filter.pinnedIds ?? []);
+
   return logs.filter((log) => {
+    // Pinned entries are always shown
+    if (pinned.has(log.id)) {
+      return true;
+    }
+
     // Use the parsed query matcher
     if (!matchesQuery(log, parsedQuery, filter.isCaseSensitive)) {
       return false;
@@ -113,14 +122,16 @@ function filterLogs(logs: LogEntry[], filter: FilterConfig): LogEntry[] {
     
     // Additional regex matching for regex mode
     if (regex) {
-      const searchTarget = `${log.tag} ${log.message}`;
-      if (!regex.test(searchTarget)) {
+      if (!searchTargets(log, filter.searchFields).some((target) => regex.test(target))) {
         return false;
       }
     }
     
     // Filter by PID (from process selector, not query)
-    if (filter.pid !== undefined && log.pid !== filter.pid) {
+    if (filter.pids?.length && !filter.pids.includes(log.pid)) {
+      return false;
+    }
+    if (filter.excludePids?.includes(log.pid)) {
       return false;
     }
     
@@ -169,6 +180,7 @@ export const useLogStore = create<LogState>()(
     isConnected: false,
     isLoading: false,
     autoScroll: true,
+    notice: null,
     settings: DEFAULT_SETTINGS,
     stats: {
       total: 0,
@@ -367,9 +379,9 @@ export const useLogStore = create<LogState>()(
     selectProcess: (process) => {
       set({ selectedProcess: process });
       if (process) {
-        get().setFilter({ pid: process.pid });
+        get().setFilter({ pids: [process.pid] });
       } else {
-        get().setFilter({ pid: undefined });
+        get().setFilter({ pids: [] });
       }
     },
     
@@ -483,6 +495,8 @@ export const useLogStore = create<LogState>()(
     },
     
     setConnected: (connected) => set({ isConnected: connected }),
+
+    setNotice: (notice) => set({ notice }),
     
     setLoading: (loading) => set({ isLoading: loading }),
     
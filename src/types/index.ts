@@ -7,10 +7,12 @@ export type TimestampFormat = "datetime" | "time" | "epoch";
 // Single log entry structure
 export interface LogEntry {
   id: number;
+  seq?: number;       // Position in the device stream, breaks epoch ties
   deviceId?: string;  // 新增：设备ID，用于多设备日志分离
   timestamp: string;
   dateTime?: string;  // Full date-time string
-  epoch?: number;     // Unix timestamp
+  epoch?: number;     // Unix timestamp (ms)
+  time?: string;      // Device time, RFC 3339 with the device UTC offset
   pid: number;
   tid: number;
   level: LogLevel;
@@ -18,10 +20,30 @@ export interface LogEntry {
   message: string;
   packageName?: string;
   processName?: string;
+  deviceColor?: number;   // Palette index of the source device
+  packageColor?: number;  // Palette index of packageName
+  correlationId?: string; // Request/trace ID captured by the correlation patterns
+  links?: LogLink[];      // Clickable parts of the message
+  groupId?: number;       // Fold group (stack trace, dump): ID of its first entry
+  sensitive?: boolean;    // Message looks like it holds a credential
+  sanitized?: boolean;    // Control/bidi characters removed; raw keeps the line as printed
+  marker?: boolean;       // Synthetic marker from insert_marker or a device reboot
+  colorLevel?: LogLevel;  // Level implied by the color of a `-v color` line
   raw?: string;
   isSystemMarker?: boolean;  // 新增：标记系统消息（断开/重连）
 }
 
+// Clickable part of a message; offsets index the JS string
+export interface LogLink {
+  kind: "source" | "url" | "contentUri" | "component";
+  start: number;
+  end: number;
+  text: string;
+  file?: string;
+  line?: number;
+  class?: string;
+}
+
 // Connected Android device
 export interface Device {
   id: string;
@@ -29,6 +51,9 @@ export interface Device {
   model: string;
   state: "device" | "offline" | "unauthorized" | "no device";
   isEmulator: boolean;
+  transport?: "usb" | "tcpip" | "unknown";
+  transportId?: number;
+  colorIndex?: number;  // Stable palette index derived from the serial
 }
 
 // Process info
@@ -46,17 +71,39 @@ export interface DeviceLogCollection {
   lastActiveTime: number;
 }
 
+// How a tag or package criterion is compared
+export type MatchMode = "contains" | "exact" | "prefix" | "regex" | "family";
+
+export interface MatchOptions {
+  mode: MatchMode;
+  caseSensitive: boolean;
+}
+
+// Entry field the search text is matched against ("all" = message, tag and process)
+export type SearchField = "message" | "tag" | "process" | "raw" | "all";
+
 // Filter configuration
 export interface FilterConfig {
   id?: string;
   name?: string;
   levels: LogLevel[];
   tags: string[];
+  tagMatch?: MatchOptions;
+  // Tags hidden regardless of the other criteria, compared like tags
+  excludeTags?: string[];
   packageName?: string;
-  pid?: number;
+  packageMatch?: MatchOptions;
+  pids?: number[];
+  excludePids?: number[];
+  // Package whose current PIDs are added to pids (resolved by the backend)
+  pidPackage?: string;
   searchText: string;
+  // Fields matched independently by the search regex; empty means all
+  searchFields?: SearchField[];
   isRegex: boolean;
   isCaseSensitive: boolean;
+  // Entries pinned in the backend, shown regardless of the other criteria
+  pinnedIds?: number[];
 }
 
 // Saved filter preset
@@ -98,6 +145,8 @@ export interface AppSettings {
   showTag: boolean;
   hideRepeatedTags: boolean;  // Hide repeated TAG values
   wrapLines: boolean;
+  // Pass filterspecs built from the query to logcat on the device when starting
+  deviceSideFilter: boolean;
 }
 
 // Log statistics
@@ -217,6 +266,7 @@ export const DEFAULT_SETTINGS: AppSettings = {
   showTag: true,
   hideRepeatedTags: false,
   wrapLines: false,
+  deviceSideFilter: false,
 };
 
 // Log level display info
@@ -1,4 +1,5 @@
 import type { LogEntry, FilterConfig } from "../types";
+import { searchTargets } from "../lib/utils";
 
 // ============================================
 // Filter Worker - 在后台线程执行过滤操作
@@ -408,7 +409,14 @@ function filterLogs(logs: LogEntry[], filter: FilterConfig): LogEntry[] {
     ? getCachedRegex(parsedQuery.text, filter.isCaseSensitive)
     : null;
 
+  const pinned = new Set(filter.pinnedIds ?? []);
+
   return logs.filter((log) => {
+    // 置顶条目始终显示
+    if (pinned.has(log.id)) {
+      return true;
+    }
+
     // 使用解析后的查询匹配
     if (!matchesQuery(log, parsedQuery, filter.isCaseSensitive)) {
       return false;
@@ -416,14 +424,16 @@ function filterLogs(logs: LogEntry[], filter: FilterConfig): LogEntry[] {
 
     // 额外的正则匹配
     if (regex) {
-      const searchTarget = `${log.tag} ${log.message}`;
-      if (!regex.test(searchTarget)) {
+      if (!searchTargets(log, filter.searchFields).some((target) => regex.test(target))) {
         return false;
       }
     }
 
     // PID 过滤
-    if (filter.pid !== undefined && log.pid !== filter.pid) {
+    if (filter.pids?.length && !filter.pids.includes(log.pid)) {
+      return false;
+    }
+    if (filter.excludePids?.includes(log.pid)) {
       return false;
     }
 